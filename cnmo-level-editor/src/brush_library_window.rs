@@ -0,0 +1,195 @@
+use cnmo_parse::lparse::level_data::brushes::{AutoTileRule, BrushLibrary, Stamp, TerrainPiece};
+use eframe::egui;
+
+use crate::editor_data::{EditorData, Tool};
+
+/// The brush library is shared by every level in the project
+const BRUSH_LIBRARY_PATH: &str = "brushes.json";
+
+pub fn load_brush_library() -> BrushLibrary {
+    let file = match std::fs::File::open(BRUSH_LIBRARY_PATH) {
+        Ok(file) => file,
+        Err(_) => return BrushLibrary::default(),
+    };
+    match serde_json::from_reader(file) {
+        Ok(library) => library,
+        Err(err) => {
+            log::error!("Can't load {BRUSH_LIBRARY_PATH} due to {err}");
+            BrushLibrary::default()
+        }
+    }
+}
+
+fn save_brush_library(library: &BrushLibrary) {
+    match std::fs::File::create(BRUSH_LIBRARY_PATH) {
+        Ok(file) => match serde_json::to_writer_pretty(file, library) {
+            Ok(_) => log::info!("Saved the brush library to {BRUSH_LIBRARY_PATH}"),
+            Err(err) => log::error!("Can't save the brush library due to {err}"),
+        },
+        Err(_) => log::error!("Can't open the file for writing!"),
+    }
+}
+
+pub struct BrushLibraryWindow {
+    pub open: bool,
+    new_name: String,
+    editing_rule: Option<usize>,
+}
+
+impl BrushLibraryWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            new_name: String::new(),
+            editing_rule: None,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, editor_data: &mut EditorData) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Brush Library")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Name: ");
+                let response = ui.text_edit_singleline(&mut self.new_name);
+                if response.gained_focus() {
+                    editor_data.editing_text = Some(response.id);
+                } else if response.lost_focus() {
+                    editor_data.editing_text = None;
+                }
+            });
+            ui.horizontal(|ui| {
+                let can_save_stamp = editor_data.viewer_selection.is_some() && !self.new_name.is_empty();
+                if ui
+                    .add_enabled(can_save_stamp, egui::Button::new("Save Stamp"))
+                    .on_hover_text("Saves the tiles copied with the brush (shift drag) or picked in the tile list")
+                    .clicked()
+                {
+                    if let Some(cells) = editor_data.viewer_selection.clone() {
+                        editor_data.brush_library.stamps.push(Stamp { name: self.new_name.clone(), cells });
+                    }
+                }
+                if ui.add_enabled(!self.new_name.is_empty(), egui::Button::new("New Auto-Tile Rule")).clicked() {
+                    editor_data.brush_library.auto_tiles.push(AutoTileRule::new(&self.new_name));
+                    self.editing_rule = Some(editor_data.brush_library.auto_tiles.len() - 1);
+                }
+                if ui.button("Save Library").clicked() {
+                    save_brush_library(&editor_data.brush_library);
+                }
+                if ui.button("Reload").clicked() {
+                    editor_data.brush_library = load_brush_library();
+                    editor_data.auto_tile = None;
+                    self.editing_rule = None;
+                }
+            });
+            ui.separator();
+
+            ui.heading("Stamps");
+            let mut remove_stamp = None;
+            egui::Grid::new("brush_stamps_grid").striped(true).show(ui, |ui| {
+                for (idx, stamp) in editor_data.brush_library.stamps.iter().enumerate() {
+                    ui.label(format!("{} ({}x{})", stamp.name, stamp.cells.width(), stamp.cells.height()));
+                    if ui.button("Use").clicked() {
+                        editor_data.selected_tiles = vec![];
+                        editor_data.viewer_selection = Some(stamp.cells.clone());
+                        editor_data.has_copied_tiles = true;
+                        editor_data.paste_light = true;
+                        editor_data.auto_tile = None;
+                        editor_data.tool = Tool::Brush;
+                    }
+                    if ui.button("Delete").clicked() {
+                        remove_stamp = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(idx) = remove_stamp {
+                editor_data.brush_library.stamps.remove(idx);
+            }
+            ui.separator();
+
+            ui.heading("Auto-Tiling");
+            ui.horizontal(|ui| {
+                let selected_text = match editor_data.auto_tile.and_then(|idx| editor_data.brush_library.auto_tiles.get(idx)) {
+                    Some(rule) => rule.name.as_str(),
+                    None => "Off",
+                };
+                egui::ComboBox::new("auto_tile_combo_box", "Paint with")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut editor_data.auto_tile, None, "Off");
+                        for (idx, rule) in editor_data.brush_library.auto_tiles.iter().enumerate() {
+                            ui.selectable_value(&mut editor_data.auto_tile, Some(idx), rule.name.as_str());
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("The brush places the terrain with left click and removes it with right click");
+            let mut remove_rule = None;
+            for (idx, rule) in editor_data.brush_library.auto_tiles.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(self.editing_rule == Some(idx), rule.name.as_str()).clicked() {
+                        self.editing_rule = if self.editing_rule == Some(idx) { None } else { Some(idx) };
+                    }
+                    ui.label(format!("{} pieces", rule.pieces.len()));
+                    if ui.button("Delete").clicked() {
+                        remove_rule = Some(idx);
+                    }
+                });
+            }
+            if let Some(idx) = remove_rule {
+                editor_data.brush_library.auto_tiles.remove(idx);
+                editor_data.auto_tile = None;
+                self.editing_rule = None;
+            }
+
+            if let Some(rule) = self.editing_rule.and_then(|idx| editor_data.brush_library.auto_tiles.get_mut(idx)) {
+                ui.separator();
+                ui.label("Select a tile in the tile list, then click a piece to tag it. Right click a piece to clear it.");
+                let selected_tile = editor_data.selected_tiles.first().map(|id| *id as u16);
+                egui::Grid::new("auto_tile_pieces_grid").num_columns(3).show(ui, |ui| {
+                    for (idx, piece) in TerrainPiece::ALL.iter().enumerate() {
+                        let text = match rule.get_piece(*piece) {
+                            Some(id) => format!("{}: {id}", get_piece_name(piece)),
+                            None => format!("{}: -", get_piece_name(piece)),
+                        };
+                        let response = ui.button(text);
+                        if response.clicked() && selected_tile.is_some() {
+                            rule.set_piece(*piece, selected_tile);
+                        }
+                        if response.secondary_clicked() {
+                            rule.set_piece(*piece, None);
+                        }
+                        if idx % 3 == 2 {
+                            ui.end_row();
+                        }
+                    }
+                });
+            }
+        });
+        self.open = open;
+    }
+}
+
+fn get_piece_name(piece: &TerrainPiece) -> &str {
+    match piece {
+        TerrainPiece::Fill => "Fill",
+        TerrainPiece::Top => "Top",
+        TerrainPiece::Bottom => "Bottom",
+        TerrainPiece::Left => "Left",
+        TerrainPiece::Right => "Right",
+        TerrainPiece::TopLeft => "Top Left",
+        TerrainPiece::TopRight => "Top Right",
+        TerrainPiece::BottomLeft => "Bottom Left",
+        TerrainPiece::BottomRight => "Bottom Right",
+        TerrainPiece::InnerTopLeft => "Inner Top Left",
+        TerrainPiece::InnerTopRight => "Inner Top Right",
+        TerrainPiece::InnerBottomLeft => "Inner Bottom Left",
+        TerrainPiece::InnerBottomRight => "Inner Bottom Right",
+    }
+}
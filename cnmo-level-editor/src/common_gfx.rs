@@ -9,7 +9,7 @@ pub struct GfxCommonResources {
 }
 
 impl GfxCommonResources {
-    pub fn insert_resource<P: AsRef<std::path::Path>>(wgpu_render_state: &egui_wgpu::RenderState, gfx_path: P) -> (Vec<[u8; 3]>, (u32, u32), Vec<Vec<bool>>) {
+    pub fn insert_resource<P: AsRef<std::path::Path>>(wgpu_render_state: &egui_wgpu::RenderState, gfx_path: P) -> (Vec<[u8; 3]>, (u32, u32), Vec<Vec<bool>>, Vec<Vec<[u8; 3]>>) {
         let device = &wgpu_render_state.device;
         let queue = &wgpu_render_state.queue;
         let paint_callback_resources = &mut wgpu_render_state
@@ -63,6 +63,7 @@ impl GfxCommonResources {
         let palette = texture.palette.clone();
         let dimensions = texture.dimensions.clone();
         let opaques = texture.opaques.clone();
+        let tile_colors = texture.tile_colors.clone();
 
         if paint_callback_resources.contains::<GfxCommonResources>() {
             let resource = paint_callback_resources.get_mut::<GfxCommonResources>().unwrap();
@@ -76,6 +77,6 @@ impl GfxCommonResources {
             });
         }
 
-        (palette, dimensions, opaques)
+        (palette, dimensions, opaques, tile_colors)
     }
 }
@@ -0,0 +1,215 @@
+use cnmo_parse::lparse::level_data::cnms_types::item_type::ItemType;
+use cnmo_parse::lparse::level_data::cnms_types::wobj_type::WobjType;
+use cnmo_parse::lparse::level_data::cnms_types::Spawner;
+use cnmo_parse::lparse::level_data::LevelData;
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+use crate::level_panel::{get_item_type_name, get_wobj_type_name, ItemIter, WobjIter};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum DropTarget {
+    Selected,
+    Type,
+    Group,
+    All,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum DropAssignment {
+    List,
+    WeightedRandom,
+}
+
+pub struct DropTableEntry {
+    pub item: Option<ItemType>,
+    pub weight: u32,
+}
+
+pub struct DropTable {
+    pub open: bool,
+    pub target: DropTarget,
+    pub target_type: WobjType,
+    pub target_group: u8,
+    pub assignment: DropAssignment,
+    pub entries: Vec<DropTableEntry>,
+    pub seed: u64,
+}
+
+impl DropTable {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            target: DropTarget::Selected,
+            target_type: WobjType::Slime { flying: false },
+            target_group: 0,
+            assignment: DropAssignment::List,
+            entries: vec![DropTableEntry { item: Some(ItemType::Apple), weight: 1 }],
+            seed: 0,
+        }
+    }
+
+    fn is_target(&self, idx: usize, spawner: &Spawner, editor_data: &EditorData) -> bool {
+        match self.target {
+            DropTarget::Selected => editor_data.selected_spawners.contains(&idx),
+            DropTarget::Type => std::mem::discriminant(&spawner.type_data) == std::mem::discriminant(&self.target_type),
+            DropTarget::Group => spawner.spawner_group == Some(self.target_group),
+            DropTarget::All => true,
+        }
+    }
+
+    fn get_targets(&self, level_data: &LevelData, editor_data: &EditorData) -> Vec<usize> {
+        level_data.spawners
+            .iter()
+            .enumerate()
+            .filter(|(idx, spawner)| self.is_target(*idx, spawner, editor_data))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Sets the dropped item of every targeted spawner. The list assignment goes
+    /// through the entries in order and wraps around, the weighted one picks an
+    /// entry at random so the same seed always gives the same level.
+    pub fn apply(&self, level_data: &mut LevelData, editor_data: &mut EditorData) {
+        let targets = self.get_targets(level_data, editor_data);
+        if targets.is_empty() || self.entries.is_empty() {
+            return;
+        }
+        let total_weight: u64 = self.entries.iter().map(|entry| entry.weight as u64).sum();
+        if self.assignment == DropAssignment::WeightedRandom && total_weight == 0 {
+            log::warn!("Can't pick dropped items at random when every weight is 0");
+            return;
+        }
+        editor_data.history.label("Assign Dropped Items");
+
+        let mut rng = SplitMix64(self.seed);
+        for (nth, idx) in targets.into_iter().enumerate() {
+            let entry = match self.assignment {
+                DropAssignment::List => &self.entries[nth % self.entries.len()],
+                DropAssignment::WeightedRandom => {
+                    let mut roll = rng.next() % total_weight;
+                    self.entries
+                        .iter()
+                        .find(|entry| {
+                            if roll < entry.weight as u64 {
+                                true
+                            } else {
+                                roll -= entry.weight as u64;
+                                false
+                            }
+                        })
+                        .unwrap()
+                }
+            };
+            level_data.spawners[idx].dropped_item = entry.item;
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &mut LevelData, editor_data: &mut EditorData) {
+        let mut open = self.open;
+        egui::Window::new("Drop Table")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            ui.heading("Targets");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.target, DropTarget::Selected, "Selected");
+                ui.selectable_value(&mut self.target, DropTarget::Type, "By Type");
+                ui.selectable_value(&mut self.target, DropTarget::Group, "By Group");
+                ui.selectable_value(&mut self.target, DropTarget::All, "All");
+            });
+            match self.target {
+                DropTarget::Type => {
+                    egui::ComboBox::new("drop_table_type_combobox", "")
+                        .selected_text(get_wobj_type_name(&self.target_type))
+                        .show_ui(ui, |ui| {
+                        for wobj_type in WobjIter::new() {
+                            let selected = std::mem::discriminant(&wobj_type) == std::mem::discriminant(&self.target_type);
+                            if ui.selectable_label(selected, get_wobj_type_name(&wobj_type)).clicked() {
+                                self.target_type = wobj_type;
+                            }
+                        }
+                    });
+                },
+                DropTarget::Group => {
+                    ui.horizontal(|ui| {
+                        ui.label("Group ID: ");
+                        ui.add(egui::DragValue::new(&mut self.target_group).clamp_range(0..=31));
+                    });
+                },
+                _ => {},
+            }
+            ui.label(format!("{} spawners targeted", self.get_targets(level_data, editor_data).len()));
+            ui.separator();
+
+            ui.heading("Items");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.assignment, DropAssignment::List, "In Order")
+                    .on_hover_text("Targets get the items in the list one after another, wrapping around");
+                ui.selectable_value(&mut self.assignment, DropAssignment::WeightedRandom, "Weighted Random");
+            });
+            if self.assignment == DropAssignment::WeightedRandom {
+                ui.horizontal(|ui| {
+                    ui.label("Seed: ");
+                    ui.add(egui::DragValue::new(&mut self.seed));
+                });
+            }
+            let mut remove_idx = None;
+            egui::Grid::new("drop_table_entries_grid").num_columns(3).striped(true).show(ui, |ui| {
+                for (idx, entry) in self.entries.iter_mut().enumerate() {
+                    let name = entry.item.as_ref().map(get_item_type_name).unwrap_or("Nothing");
+                    egui::ComboBox::new(("drop_table_entry_combobox", idx), "")
+                        .selected_text(name)
+                        .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut entry.item, None, "Nothing");
+                        for item_type in ItemIter::new() {
+                            ui.selectable_value(&mut entry.item, Some(item_type), get_item_type_name(&item_type));
+                        }
+                    });
+                    if self.assignment == DropAssignment::WeightedRandom {
+                        ui.add(egui::DragValue::new(&mut entry.weight).prefix("weight: "));
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove_idx = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(idx) = remove_idx {
+                self.entries.remove(idx);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Add Item").clicked() {
+                    self.entries.push(DropTableEntry { item: Some(ItemType::Apple), weight: 1 });
+                }
+                if ui.button("Apply").clicked() {
+                    self.apply(level_data, editor_data);
+                }
+            });
+            ui.separator();
+
+            ui.heading("Items In Level");
+            egui::Grid::new("drop_table_summary_grid").num_columns(2).striped(true).show(ui, |ui| {
+                for (item, count) in level_data.count_items() {
+                    ui.label(get_item_type_name(&item));
+                    ui.label(count.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+        self.open = open;
+    }
+}
+
+/// Small seeded random number generator so drop tables can be rerolled the same way.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
@@ -1,4 +1,4 @@
-use cnmo_parse::lparse::level_data::{cnmb_types::Cells, cnms_types::{Spawner, wobj_type::WobjType}};
+use cnmo_parse::lparse::level_data::{cnmb_types::{Cells, TileProperties}, cnms_types::{Spawner, wobj_type::WobjType}, consts::FRAME_RATE};
 use eframe::egui;
 use std::path::Path;
 
@@ -10,6 +10,27 @@ pub enum Tool {
     TilePicker,
     Spawners,
     Light,
+    Rectangle,
+    Line,
+    Ellipse,
+    Replace,
+}
+
+impl Tool {
+    /// The shape the tool draws by dragging, if it's a shape tool
+    pub fn get_shape(&self) -> Option<crate::shapes::Shape> {
+        match self {
+            Tool::Rectangle => Some(crate::shapes::Shape::Rectangle),
+            Tool::Line => Some(crate::shapes::Shape::Line),
+            Tool::Ellipse => Some(crate::shapes::Shape::Ellipse),
+            _ => None,
+        }
+    }
+
+    /// If the tool can paint light levels instead of tiles
+    pub fn can_paint_light(&self) -> bool {
+        self.get_shape().is_some() || matches!(self, Tool::Replace)
+    }
 }
 
 pub struct EditorData {
@@ -17,32 +38,60 @@ pub struct EditorData {
     pub foreground_placing: bool,
     //pub light_placing: Option<u8>,
     pub light_tool_level: u8,
+    pub light_options: crate::light_tools::LightToolOptions,
+    /// Shape and replace tools change the light level instead of tiles
+    pub shape_on_light: bool,
+    pub shape_filled: bool,
     pub palette: Vec<[u8; 3]>,
     pub dt: std::time::Duration,
     pub time_past: std::time::Duration,
+    /// Drives tile and spawner animations in the world view, stops while paused
+    pub preview_time: std::time::Duration,
+    pub preview_paused: bool,
     last_update: std::time::Instant,
     pub gfx_size: (u32, u32),
     pub tool: Tool,
     pub viewer_selection: Option<Cells>,
     pub has_copied_tiles: bool,
+    /// If the copied tiles paste their light too, which only saved stamps do
+    pub paste_light: bool,
     pub opaques: Vec<Vec<bool>>,
+    /// Average color of each tile in the gfx sheet, for the minimap
+    pub tile_colors: Vec<Vec<[u8; 3]>>,
     pub current_background: usize,
     pub gray_out_background: bool,
     pub selecting_background_color: bool,
     pub selecting_background_image: bool,
-    pub cells_history: Vec<(Cells, Vec<Spawner>)>,
-    pub selected_spawner: Option<usize>,
+    pub history: crate::history::History,
+    pub layers: crate::layers::Layers,
+    pub brush_library: cnmo_parse::lparse::level_data::brushes::BrushLibrary,
+    /// The auto-tiling rule the brush paints with instead of the selected tiles
+    pub auto_tile: Option<usize>,
+    /// The last one is the primary selection shown in the properties
+    pub selected_spawners: Vec<usize>,
     pub spawner_template: Spawner,
+    /// Copied spawners with positions relative to where they were copied from
+    pub copied_spawners: Vec<Spawner>,
+    pub copy_spawners_with_tiles: bool,
     pub spawner_grid_size: f32,
     pub editing_text: Option<egui::Id>,
     pub game_config_file: cnmo_parse::cnma::Cnma,
+    pub game_config_document: cnmo_parse::cnma::cst::CnmaDocument,
     pub info_bar: String,
     pub level_file_name: String,
     pub level_gfx_file: String,
+    pub keybinds: crate::preferences::Keybinds,
+    /// Level file names, most recent first
+    pub recent_levels: Vec<String>,
 }
 
 impl EditorData {
-    pub fn new(palette: Vec<[u8; 3]>, gfx_size: (u32, u32), opaques: Vec<Vec<bool>>) -> Self {
+    pub fn new(palette: Vec<[u8; 3]>, gfx_size: (u32, u32), opaques: Vec<Vec<bool>>, tile_colors: Vec<Vec<[u8; 3]>>) -> Self {
+        let (game_config_document, warnings) = cnmo_parse::cnma::cst::CnmaDocument::from_file_with_options("audio.cnma", Default::default())
+            .unwrap_or_else(|err| panic!("Expected a valid audio.cnma in current directory!\n{err}"));
+        for warning in warnings {
+            log::warn!("audio.cnma:\n{warning}");
+        }
         Self {
             selected_tiles: Vec::new(),
             foreground_placing: true,
@@ -50,19 +99,29 @@ impl EditorData {
             dt: std::time::Duration::from_secs_f32(f32::EPSILON),
             last_update: std::time::Instant::now(),
             light_tool_level: cnmo_parse::lparse::level_data::consts::LIGHT_NORMAL,
+            light_options: crate::light_tools::LightToolOptions::new(),
+            shape_on_light: false,
+            shape_filled: true,
             //light_placing: None,
             gfx_size,
             time_past: std::time::Duration::ZERO,
+            preview_time: std::time::Duration::ZERO,
+            preview_paused: false,
             tool: Tool::Brush,
             viewer_selection: None,
             has_copied_tiles: false,
+            paste_light: false,
             opaques,
+            tile_colors,
             current_background: 0,
             gray_out_background: true,
             selecting_background_color: false,
             selecting_background_image: false,
-            cells_history: vec![],
-            selected_spawner: None,
+            history: crate::history::History::new(),
+            layers: crate::layers::Layers::new(),
+            brush_library: crate::brush_library_window::load_brush_library(),
+            auto_tile: None,
+            selected_spawners: vec![],
             spawner_template: Spawner {
                 pos: cnmo_parse::lparse::level_data::Point(0.0, 0.0),
                 type_data: WobjType::Slime { flying: false },
@@ -74,12 +133,17 @@ impl EditorData {
                 dropped_item: None,
                 spawner_group: None,
             },
+            copied_spawners: vec![],
+            copy_spawners_with_tiles: false,
             spawner_grid_size: 8.0,
             editing_text: None,
-            game_config_file: cnmo_parse::cnma::Cnma::from_file("audio.cnma").expect("Expected audio.cnma in current directory!"),
+            game_config_file: game_config_document.cnma(),
+            game_config_document,
             info_bar: "Welcome to the CNM Online Editor!".to_string(),
             level_file_name: "newlvl".to_string(),
             level_gfx_file: "gfx.bmp".to_string(),
+            keybinds: Default::default(),
+            recent_levels: vec![],
         }
     }
 
@@ -89,20 +153,58 @@ impl EditorData {
         self.viewer_selection = None;
     }
 
+    pub fn get_selected_spawner(&self) -> Option<usize> {
+        self.selected_spawners.last().copied()
+    }
+
+    pub fn select_spawner(&mut self, idx: usize) {
+        self.selected_spawners = vec![idx];
+    }
+
+    /// Adds the spawner to the selection, or removes it if it's already selected
+    pub fn toggle_spawner(&mut self, idx: usize) {
+        if let Some(pos) = self.selected_spawners.iter().position(|selected| *selected == idx) {
+            self.selected_spawners.remove(pos);
+        } else {
+            self.selected_spawners.push(idx);
+        }
+    }
+
     pub fn update_delta_time(&mut self) {
         let now = std::time::Instant::now();
         self.dt = now - self.last_update;
         self.last_update = now;
         self.time_past += self.dt;
+        if !self.preview_paused {
+            self.preview_time += self.dt;
+        }
     }
 
-    pub fn set_gfx_file(&mut self, lvlname: &str) -> bool {
-        let old_file = self.level_gfx_file.clone();
-        if lvlname != "gfx.bmp" && Path::new(("./levels/".to_string() + lvlname + ".bmp").as_str()).exists() {
-            self.level_gfx_file = "levels/".to_string() + lvlname + ".bmp";
-        } else {
-            self.level_gfx_file = "gfx.bmp".to_string();
+    /// Moves the paused preview forward by one game frame
+    pub fn step_preview(&mut self) {
+        self.preview_time += std::time::Duration::from_secs(1) / FRAME_RATE as u32;
+    }
+
+    /// Which frame of the tile's animation to show in the preview
+    pub fn get_tile_frame(&self, props: &TileProperties) -> usize {
+        if props.anim_speed.0 <= 0 {
+            return 0;
         }
+        let frames = self.preview_time.as_secs_f32() * FRAME_RATE as f32;
+        (frames / props.anim_speed.0 as f32) as usize % props.frames.len()
+    }
+
+    pub fn set_gfx_file(&mut self, lvlname: &str) -> bool {
+        let old_file = std::mem::replace(&mut self.level_gfx_file, get_gfx_file(lvlname));
         return self.level_gfx_file != old_file;
     }
 }
+
+/// The graphics file a level uses, its own `levels/<name>.bmp` if it has one
+pub fn get_gfx_file(lvlname: &str) -> String {
+    if lvlname != "gfx.bmp" && Path::new(("./levels/".to_string() + lvlname + ".bmp").as_str()).exists() {
+        "levels/".to_string() + lvlname + ".bmp"
+    } else {
+        "gfx.bmp".to_string()
+    }
+}
@@ -1,4 +1,7 @@
-use cnmo_parse::cnma::{MaxPowerAbility, Mode, ResourceId, PetAI};
+use cnmo_parse::cnma::lua::{self, LuaCheck, Token, TokenKind};
+use cnmo_parse::cnma::max_power::MaxPowerProblem;
+use cnmo_parse::cnma::{MaxPowerAbility, MaxPowerDef, Mode, ResourceId, PetAI, PetDef};
+use cnmo_parse::lparse::level_data::consts::FRAME_RATE;
 use eframe::egui;
 
 use crate::{
@@ -8,6 +11,38 @@ use crate::{
 };
 
 crate::create_instance_resource!(GfxPreviewResource);
+crate::create_instance_resource!(PetPreviewResource);
+
+/// The tokens and syntax check of the Lua code, redone only when the code changes
+struct LuaCache {
+    hash: u64,
+    tokens: Vec<Token>,
+    check: LuaCheck,
+}
+
+impl LuaCache {
+    fn get<'a>(cache: &'a mut Option<LuaCache>, code: &str) -> &'a LuaCache {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        code.hash(&mut hasher);
+        let hash = hasher.finish();
+        if cache.as_ref().is_none_or(|cache| cache.hash != hash) {
+            *cache = Some(LuaCache {
+                hash,
+                tokens: lua::tokenize(code),
+                check: lua::check(code),
+            });
+        }
+        cache.as_ref().unwrap()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PetPickTarget {
+    Animation,
+    Icon,
+}
 
 pub struct GameConfigPanel {
     pub selected_mode: Option<usize>,
@@ -16,6 +51,11 @@ pub struct GameConfigPanel {
     pub new_level: String,
     pub preview_gfx: bool,
     preview_scroll_offset: f32,
+    selected_pet: usize,
+    pet_animation: usize,
+    pet_pick: Option<(usize, PetPickTarget)>,
+    lua_jump_line: Option<usize>,
+    lua_cache: Option<LuaCache>,
 }
 
 impl GameConfigPanel {
@@ -27,6 +67,11 @@ impl GameConfigPanel {
             new_level: "".to_string(),
             preview_gfx: false,
             preview_scroll_offset: 0.0,
+            selected_pet: 0,
+            pet_animation: 0,
+            pet_pick: None,
+            lua_jump_line: None,
+            lua_cache: None,
         }
     }
 
@@ -37,7 +82,7 @@ impl GameConfigPanel {
                 let (rect, response) = ui.allocate_exact_size(
                     ui.available_size(),
                     //egui::vec2(size.0 as f32 * 2.0, size.1 as f32 * 2.0),
-                    egui::Sense::hover()
+                    egui::Sense::click()
                 );
                 let cw = editor_data.gfx_size.0 as f32;
                 let ch = (rect.height() / rect.width()) * editor_data.gfx_size.0 as f32;
@@ -70,11 +115,28 @@ impl GameConfigPanel {
                         (pos.x / 16.0).floor() as i32, (pos.y / 16.0).floor() as i32,
                         (pos.x / 8.0).floor() as i32, (pos.y / 8.0).floor() as i32,
                     );
+                    if let Some((pet_idx, target)) = self.pet_pick {
+                        if response.clicked() {
+                            let tile = ((pos.x / 32.0).floor().max(0.0) as u16, (pos.y / 32.0).floor().max(0.0) as u16);
+                            let mode = self.selected_mode.and_then(|idx| editor_data.game_config_file.modes.get_mut(idx));
+                            if let Some(Mode::PetDefs(defs)) = mode {
+                                if let Some(def) = defs.get_mut(pet_idx) {
+                                    match target {
+                                        PetPickTarget::Animation => def.animbase = tile,
+                                        PetPickTarget::Icon => def.iconbase = tile,
+                                    }
+                                }
+                            }
+                            self.pet_pick = None;
+                            self.preview_gfx = false;
+                        }
+                    }
                 }
             });
             return;
         }
 
+        self.pet_pick = None;
         editor_data.info_bar = "Game config editor".to_string();
         egui::ScrollArea::vertical()
             .auto_shrink([false, false])
@@ -93,6 +155,8 @@ impl GameConfigPanel {
                     return;
                 }
 
+                let max_powers: Vec<MaxPowerDef> = editor_data.game_config_file.get_max_powers().into_iter().cloned().collect();
+                let max_power_problems = editor_data.game_config_file.check_max_powers();
                 match &mut editor_data.game_config_file.modes[mode_idx] {
                     Mode::MusicIds(resources) | Mode::SoundIds(resources) => {
                         let mut delete_idx = None;
@@ -244,16 +308,10 @@ impl GameConfigPanel {
                                 def.ability = Some(MaxPowerAbility::DoubleJump);
                             }
                         }
+                        ui.separator();
+                        show_max_power_preview(ui, def, &max_powers, &max_power_problems);
                     }
-                    Mode::LuaAutorunCode(code) => {
-                        ui.add(
-                            egui::TextEdit::multiline(code)
-                                .font(egui::TextStyle::Monospace)
-                                .code_editor()
-                                .lock_focus(true)
-                                .desired_width(f32::INFINITY),
-                        );
-                    }
+                    Mode::LuaAutorunCode(code) => self.show_lua_editor(ui, code),
                     Mode::PetDefs(ref mut defs) => {
                         let mut ui_id = 0;
                         for ref mut def in defs.iter_mut() {
@@ -261,6 +319,22 @@ impl GameConfigPanel {
                                 ui.label("Name: ");
                                 ui.add(egui::TextEdit::singleline(&mut def.name));
                                 ui.end_row();
+                                ui.label("Preview: ");
+                                ui.horizontal(|ui| {
+                                    if ui.selectable_label(self.selected_pet == ui_id, "Show").clicked() {
+                                        self.selected_pet = ui_id;
+                                        self.pet_animation = 0;
+                                    }
+                                    if ui.button("Pick Animation").on_hover_text("Click on the graphics file to set the animation base").clicked() {
+                                        self.pet_pick = Some((ui_id, PetPickTarget::Animation));
+                                        self.preview_gfx = true;
+                                    }
+                                    if ui.button("Pick Icon").on_hover_text("Click on the graphics file to set the icon base").clicked() {
+                                        self.pet_pick = Some((ui_id, PetPickTarget::Icon));
+                                        self.preview_gfx = true;
+                                    }
+                                });
+                                ui.end_row();
                                 ui.label("Animation Base X: ").on_hover_text("This is what tile (32x32) in the graphics file it is");
                                 ui.add(egui::DragValue::new(&mut def.animbase.0).clamp_range(0..=15));
                                 ui.end_row();
@@ -348,6 +422,9 @@ impl GameConfigPanel {
                                     },
                                 }
                             });
+                            if self.selected_pet == ui_id {
+                                self.show_pet_preview(ui, def);
+                            }
                             ui.separator();
                             ui_id += 1;
                         }
@@ -365,6 +442,128 @@ impl GameConfigPanel {
     }
 }
 
+impl GameConfigPanel {
+    /// Draws the pet's icon next to its current animation frame, cycling through
+    /// the frames of the picked animation.
+    fn show_pet_preview(&mut self, ui: &mut egui::Ui, def: &PetDef) {
+        let animations = def.get_animations();
+        ui.horizontal(|ui| {
+            for (idx, (name, _)) in animations.iter().enumerate() {
+                ui.selectable_value(&mut self.pet_animation, idx, *name);
+            }
+        });
+        let frames = animations.get(self.pet_animation).map_or(0..0, |(_, frames)| frames.clone());
+        let frame = if frames.is_empty() {
+            None
+        } else {
+            let time = ui.input().time;
+            Some(frames.start + (time * 8.0) as u16 % frames.len() as u16)
+        };
+
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(136.0, 64.0), egui::Sense::hover());
+        let mut sprites = vec![Sprite::new(
+            (0.0, 0.0, 0.0),
+            (32.0, 32.0),
+            (def.iconbase.0 as f32 * 32.0, def.iconbase.1 as f32 * 32.0, 32.0, 32.0),
+        )];
+        if let Some(frame) = frame {
+            let pos = def.get_frame_pos(frame);
+            sprites.push(Sprite::new((36.0, 0.0, 0.0), (32.0, 32.0), (pos.0 as f32, pos.1 as f32, 32.0, 32.0)));
+        }
+        InstancedSprites::new()
+            .with_camera(Camera::new().with_projection(68.0, 32.0, None, true))
+            .with_sprites(sprites)
+            .paint::<PetPreviewResource>(ui, rect);
+        if frame.is_some() {
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+impl GameConfigPanel {
+    /// A code editor with lua highlighting, the first syntax error and an outline
+    /// of the functions that jumps to them when clicked.
+    fn show_lua_editor(&mut self, ui: &mut egui::Ui, code: &mut String) {
+        let check = &LuaCache::get(&mut self.lua_cache, code).check;
+        ui.horizontal(|ui| match &check.error {
+            Some(error) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, format!("Line {}: {}", error.line, error.message))
+                    .on_hover_text(egui::RichText::new(error.to_string()).monospace());
+                if ui.button("Go To Error").clicked() {
+                    self.lua_jump_line = Some(error.line);
+                }
+            }
+            None => {
+                ui.label("No syntax errors");
+            }
+        });
+        ui.collapsing("Outline", |ui| {
+            for function in check.functions.iter() {
+                let mut text = egui::RichText::new(format!("{}: {}", function.line, function.name)).monospace();
+                if function.is_hook() {
+                    text = text.strong();
+                }
+                let response = ui.selectable_label(false, text);
+                let response = if function.is_hook() {
+                    response.on_hover_text("Called by the game for lua objects")
+                } else {
+                    response
+                };
+                if response.clicked() {
+                    self.lua_jump_line = Some(function.line);
+                }
+            }
+            if check.functions.is_empty() {
+                ui.label("No functions defined");
+            }
+        });
+
+        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+            let mut job = lua_layout_job(ui, text, &LuaCache::get(&mut self.lua_cache, text).tokens);
+            job.wrap.max_width = wrap_width;
+            ui.fonts().layout_job(job)
+        };
+        let output = egui::TextEdit::multiline(code)
+            .font(egui::TextStyle::Monospace)
+            .code_editor()
+            .lock_focus(true)
+            .desired_width(f32::INFINITY)
+            .layouter(&mut layouter)
+            .show(ui);
+        if let Some(line) = self.lua_jump_line.take() {
+            let line_start = code.split_inclusive('\n').take(line.saturating_sub(1)).map(|line| line.chars().count()).sum();
+            let ccursor = egui::text::CCursor::new(line_start);
+            let mut state = output.state;
+            state.set_ccursor_range(Some(egui::text::CCursorRange::one(ccursor)));
+            state.store(ui.ctx(), output.response.id);
+            output.response.request_focus();
+            let cursor_rect = output.galley.pos_from_cursor(&output.galley.from_ccursor(ccursor));
+            ui.scroll_to_rect(cursor_rect.translate(output.text_draw_pos.to_vec2()), Some(egui::Align::Center));
+        }
+    }
+}
+
+fn lua_layout_job(ui: &egui::Ui, code: &str, tokens: &[Token]) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let dark_mode = ui.visuals().dark_mode;
+    let mut job = egui::text::LayoutJob::default();
+    for token in tokens {
+        let color = match (token.kind, dark_mode) {
+            (TokenKind::Keyword, true) => egui::Color32::from_rgb(255, 120, 200),
+            (TokenKind::Keyword, false) => egui::Color32::from_rgb(160, 0, 120),
+            (TokenKind::String, true) => egui::Color32::from_rgb(150, 220, 110),
+            (TokenKind::String, false) => egui::Color32::from_rgb(30, 130, 0),
+            (TokenKind::Number, true) => egui::Color32::from_rgb(240, 170, 80),
+            (TokenKind::Number, false) => egui::Color32::from_rgb(180, 90, 0),
+            (TokenKind::Comment, _) => egui::Color32::GRAY,
+            (TokenKind::Unknown, _) => egui::Color32::RED,
+            (TokenKind::Name | TokenKind::Symbol | TokenKind::Whitespace, _) => ui.visuals().text_color(),
+        };
+        job.append(&code[token.span.clone()], 0.0, egui::text::TextFormat::simple(font_id.clone(), color));
+    }
+    job
+}
+
 fn get_ability_name(ability: &MaxPowerAbility) -> &str {
     match ability {
         &MaxPowerAbility::DoubleJump => "Double Jump",
@@ -373,3 +572,73 @@ fn get_ability_name(ability: &MaxPowerAbility) -> &str {
         &MaxPowerAbility::MarioBounce => "Mario Enemy Bounce",
     }
 }
+
+/// Shows the problems with a max power, a jump arc compared to no power and
+/// a table of every max power in the file.
+fn show_max_power_preview(ui: &mut egui::Ui, def: &MaxPowerDef, max_powers: &[MaxPowerDef], problems: &[(u8, MaxPowerProblem)]) {
+    for problem in def.validate().iter().chain(
+        problems
+            .iter()
+            .filter(|(id, problem)| *id == def.id && matches!(problem, MaxPowerProblem::DuplicateSkinId(_)))
+            .map(|(_, problem)| problem),
+    ) {
+        ui.colored_label(egui::Color32::YELLOW, problem.to_string());
+    }
+
+    ui.label("Jump Arc").on_hover_text("A running jump using rough player physics, gray is a jump without any power");
+    let neutral_arc = MaxPowerDef::new_neutral(def.id).jump_arc(120);
+    let arc = def.jump_arc(120);
+    let (max_x, max_y) = neutral_arc
+        .iter()
+        .chain(arc.iter())
+        .fold((1.0f32, 1.0f32), |(mx, my), (x, y)| (mx.max(*x), my.max(*y)));
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width().min(400.0), 150.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::from_gray(20));
+    let scale = ((rect.width() - 8.0) / max_x).min((rect.height() - 8.0) / max_y);
+    let to_screen = |(x, y): &(f32, f32)| egui::pos2(rect.left() + 4.0 + x * scale, rect.bottom() - 4.0 - y * scale);
+    painter.add(egui::Shape::line(neutral_arc.iter().map(to_screen).collect(), egui::Stroke::new(1.0, egui::Color32::GRAY)));
+    painter.add(egui::Shape::line(arc.iter().map(to_screen).collect(), egui::Stroke::new(2.0, egui::Color32::LIGHT_GREEN)));
+    let height = arc.iter().map(|(_, y)| *y).fold(0.0, f32::max);
+    let distance = arc.last().map_or(0.0, |(x, _)| *x);
+    response.on_hover_text(format!(
+        "Height: {:.0}px ({:.1} tiles)\nDistance: {:.0}px ({:.1} tiles)\nAir time: {:.2}s",
+        height,
+        height / 32.0,
+        distance,
+        distance / 32.0,
+        (arc.len() - 1) as f32 / FRAME_RATE as f32,
+    ));
+    ui.separator();
+
+    ui.label("All Max Powers");
+    egui::Grid::new("max_power_comparison_grid").num_columns(7).striped(true).show(ui, |ui| {
+        for header in ["Skin", "Speed", "Jump", "Gravity", "Strength", "Hp Cost", "Ability"] {
+            ui.label(header);
+        }
+        ui.end_row();
+        let mut sorted: Vec<&MaxPowerDef> = max_powers.iter().collect();
+        sorted.sort_by_key(|other| other.id);
+        for other in sorted {
+            let text = |s: String| {
+                let text = egui::RichText::new(s);
+                if other.id == def.id {
+                    text.strong()
+                } else {
+                    text
+                }
+            };
+            let skin = ui.label(text(other.id.to_string()));
+            if problems.iter().any(|(id, _)| *id == other.id) {
+                skin.on_hover_text("This max power has problems");
+            }
+            ui.label(text(other.speed.to_string()));
+            ui.label(text(other.jump.to_string()));
+            ui.label(text(other.gravity.to_string()));
+            ui.label(text(other.strength.to_string()));
+            ui.label(text(other.hpcost.to_string()));
+            ui.label(text(other.ability.as_ref().map_or("None", get_ability_name).to_string()));
+            ui.end_row();
+        }
+    });
+}
@@ -0,0 +1,321 @@
+use cnmo_parse::cnma::{Cnma, Mode};
+use cnmo_parse::lparse::level_data::{
+    cnmb_types::{BackgroundLayer, Cell, Cells, TileProperties},
+    cnms_types::Spawner,
+    LevelData, LevelMetaData,
+};
+
+const MAX_ENTRIES: usize = 512;
+
+/// The part of a list that changed. Everything before `start` and after the
+/// replaced items is the same before and after.
+struct Splice<T> {
+    start: usize,
+    before: Vec<T>,
+    after: Vec<T>,
+}
+
+impl<T: Clone + PartialEq> Splice<T> {
+    fn diff(before: &[T], after: &[T]) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+        let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+        let suffix = before[prefix..]
+            .iter()
+            .rev()
+            .zip(after[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        Some(Self {
+            start: prefix,
+            before: before[prefix..before.len() - suffix].to_vec(),
+            after: after[prefix..after.len() - suffix].to_vec(),
+        })
+    }
+
+    fn apply(&self, list: &mut Vec<T>, undo: bool) {
+        let (from, to) = if undo { (&self.after, &self.before) } else { (&self.before, &self.after) };
+        list.splice(self.start..self.start + from.len(), to.iter().cloned());
+    }
+
+    fn size(&self) -> usize {
+        (self.before.len() + self.after.len()) * std::mem::size_of::<T>()
+    }
+}
+
+enum Change {
+    /// Cells that changed in a grid that kept its size, by index
+    Cells(Vec<(usize, Cell, Cell)>),
+    /// The grid was resized so all of it is kept
+    ResizeCells(Box<(Cells, Cells)>),
+    Spawners(Splice<Spawner>),
+    TileProperties(Splice<TileProperties>),
+    BackgroundLayers(Splice<BackgroundLayer>),
+    Metadata(Box<(LevelMetaData, LevelMetaData)>),
+    GameConfig(Splice<Mode>),
+}
+
+impl Change {
+    fn get_name(&self) -> &'static str {
+        match self {
+            Change::Cells(_) | Change::ResizeCells(_) => "Edit Cells",
+            Change::Spawners(_) => "Edit Spawners",
+            Change::TileProperties(_) => "Edit Tile Properties",
+            Change::BackgroundLayers(_) => "Edit Backgrounds",
+            Change::Metadata(_) => "Edit Level Info",
+            Change::GameConfig(_) => "Edit Game Config",
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Change::Cells(cells) => cells.len() * std::mem::size_of::<(usize, Cell, Cell)>(),
+            Change::ResizeCells(cells) => (cells.0.cells().len() + cells.1.cells().len()) * std::mem::size_of::<Cell>(),
+            Change::Spawners(splice) => splice.size(),
+            Change::TileProperties(splice) => splice.size(),
+            Change::BackgroundLayers(splice) => splice.size(),
+            Change::Metadata(_) => std::mem::size_of::<(LevelMetaData, LevelMetaData)>(),
+            Change::GameConfig(splice) => {
+                // Lua code is usually the biggest part of a section
+                splice.size()
+                    + splice
+                        .before
+                        .iter()
+                        .chain(splice.after.iter())
+                        .map(|mode| match mode {
+                            Mode::LuaAutorunCode(code) => code.len(),
+                            _ => 0,
+                        })
+                        .sum::<usize>()
+            }
+        }
+    }
+
+    fn apply(&self, level_data: &mut LevelData, game_config: &mut Cnma, undo: bool) {
+        match self {
+            Change::Cells(cells) => {
+                let grid = level_data.cells.cells_mut();
+                for (idx, before, after) in cells.iter() {
+                    grid[*idx] = if undo { *before } else { *after };
+                }
+            }
+            Change::ResizeCells(cells) => level_data.cells = if undo { cells.0.clone() } else { cells.1.clone() },
+            Change::Spawners(splice) => splice.apply(&mut level_data.spawners, undo),
+            Change::TileProperties(splice) => splice.apply(&mut level_data.tile_properties, undo),
+            Change::BackgroundLayers(splice) => splice.apply(&mut level_data.background_layers, undo),
+            Change::Metadata(metadata) => level_data.metadata = if undo { metadata.0.clone() } else { metadata.1.clone() },
+            Change::GameConfig(splice) => splice.apply(&mut game_config.modes, undo),
+        }
+    }
+}
+
+/// One undoable step, made of everything that changed in it
+pub struct HistoryEntry {
+    pub label: String,
+    changes: Vec<Change>,
+}
+
+impl HistoryEntry {
+    /// Roughly how many bytes the entry keeps around
+    pub fn size(&self) -> usize {
+        self.changes.iter().map(Change::size).sum()
+    }
+}
+
+/// The state the last entry was made from, to compare edits against
+struct Snapshot {
+    cells: Cells,
+    spawners: Vec<Spawner>,
+    tile_properties: Vec<TileProperties>,
+    background_layers: Vec<BackgroundLayer>,
+    metadata: LevelMetaData,
+    game_config: Vec<Mode>,
+}
+
+impl Snapshot {
+    fn new(level_data: &LevelData, game_config: &Cnma) -> Self {
+        Self {
+            cells: level_data.cells.clone(),
+            spawners: level_data.spawners.clone(),
+            tile_properties: level_data.tile_properties.clone(),
+            background_layers: level_data.background_layers.clone(),
+            metadata: level_data.metadata.clone(),
+            game_config: game_config.modes.clone(),
+        }
+    }
+
+    /// Finds what changed since the snapshot and updates it to match
+    fn take_changes(&mut self, level_data: &LevelData, game_config: &Cnma) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if self.cells != level_data.cells {
+            if self.cells.width() == level_data.cells.width() && self.cells.height() == level_data.cells.height() {
+                let cells = self
+                    .cells
+                    .cells()
+                    .iter()
+                    .zip(level_data.cells.cells())
+                    .enumerate()
+                    .filter(|(_, (before, after))| before != after)
+                    .map(|(idx, (before, after))| (idx, *before, *after))
+                    .collect::<Vec<_>>();
+                for (idx, _, after) in cells.iter() {
+                    self.cells.cells_mut()[*idx] = *after;
+                }
+                changes.push(Change::Cells(cells));
+            } else {
+                let before = std::mem::replace(&mut self.cells, level_data.cells.clone());
+                changes.push(Change::ResizeCells(Box::new((before, level_data.cells.clone()))));
+            }
+        }
+        if let Some(splice) = Splice::diff(&self.spawners, &level_data.spawners) {
+            self.spawners = level_data.spawners.clone();
+            changes.push(Change::Spawners(splice));
+        }
+        if let Some(splice) = Splice::diff(&self.tile_properties, &level_data.tile_properties) {
+            self.tile_properties = level_data.tile_properties.clone();
+            changes.push(Change::TileProperties(splice));
+        }
+        if let Some(splice) = Splice::diff(&self.background_layers, &level_data.background_layers) {
+            self.background_layers = level_data.background_layers.clone();
+            changes.push(Change::BackgroundLayers(splice));
+        }
+        if self.metadata != level_data.metadata {
+            let before = std::mem::replace(&mut self.metadata, level_data.metadata.clone());
+            changes.push(Change::Metadata(Box::new((before, level_data.metadata.clone()))));
+        }
+        if let Some(splice) = Splice::diff(&self.game_config, &game_config.modes) {
+            self.game_config = game_config.modes.clone();
+            changes.push(Change::GameConfig(splice));
+        }
+        changes
+    }
+}
+
+/// Undo and redo history for everything in the level and the game config.
+/// Edits are found by comparing against a snapshot when [`History::commit`]
+/// is called, so only what changed is kept in each entry.
+///
+/// Comparing is only done after input or a labeled edit, so edits made
+/// without any input (like a file being reloaded) aren't seen until the next
+/// edit and end up in its entry.
+pub struct History {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    snapshot: Option<Snapshot>,
+    pending_label: Option<String>,
+    had_input: bool,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+            snapshot: None,
+            pending_label: None,
+            had_input: false,
+        }
+    }
+
+    /// Forgets all history, for when a different level is loaded
+    pub fn reset(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.snapshot = None;
+        self.pending_label = None;
+        self.had_input = false;
+    }
+
+    /// Names the edit that is about to happen. The first name given before
+    /// the next commit is used.
+    pub fn label(&mut self, label: &str) {
+        if self.pending_label.is_none() {
+            self.pending_label = Some(label.to_string());
+        }
+    }
+
+    /// Marks that there was input that might have edited something, so the
+    /// next commit looks for changes
+    pub fn note_input(&mut self) {
+        self.had_input = true;
+    }
+
+    /// Records everything that changed since the last commit as one entry.
+    /// Returns if there were any changes. Does nothing unless there was input
+    /// or a label since the last commit.
+    pub fn commit(&mut self, level_data: &LevelData, game_config: &Cnma) -> bool {
+        if self.snapshot.is_some() && self.pending_label.is_none() && !self.had_input {
+            return false;
+        }
+        self.commit_changes(level_data, game_config)
+    }
+
+    /// Same as [`History::commit`] but always compares against the snapshot
+    fn commit_changes(&mut self, level_data: &LevelData, game_config: &Cnma) -> bool {
+        self.had_input = false;
+        let label = self.pending_label.take();
+        let snapshot = match &mut self.snapshot {
+            Some(snapshot) => snapshot,
+            None => {
+                self.snapshot = Some(Snapshot::new(level_data, game_config));
+                return false;
+            }
+        };
+        let changes = snapshot.take_changes(level_data, game_config);
+        if changes.is_empty() {
+            return false;
+        }
+        let label = label.unwrap_or_else(|| {
+            let mut names: Vec<&str> = changes.iter().map(Change::get_name).collect();
+            names.dedup();
+            names.join(", ")
+        });
+        self.undo.push(HistoryEntry { label, changes });
+        if self.undo.len() > MAX_ENTRIES {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// Undoes the last entry, returning its label
+    pub fn undo(&mut self, level_data: &mut LevelData, game_config: &mut Cnma) -> Option<&str> {
+        self.commit_changes(level_data, game_config);
+        let entry = self.undo.pop()?;
+        for change in entry.changes.iter().rev() {
+            change.apply(level_data, game_config, true);
+        }
+        self.snapshot = Some(Snapshot::new(level_data, game_config));
+        self.redo.push(entry);
+        self.redo.last().map(|entry| entry.label.as_str())
+    }
+
+    /// Redoes the last undone entry, returning its label
+    pub fn redo(&mut self, level_data: &mut LevelData, game_config: &mut Cnma) -> Option<&str> {
+        self.commit_changes(level_data, game_config);
+        let entry = self.redo.pop()?;
+        for change in entry.changes.iter() {
+            change.apply(level_data, game_config, false);
+        }
+        self.snapshot = Some(Snapshot::new(level_data, game_config));
+        self.undo.push(entry);
+        self.undo.last().map(|entry| entry.label.as_str())
+    }
+
+    /// Undoes or redoes until `count` entries are applied
+    pub fn jump_to(&mut self, count: usize, level_data: &mut LevelData, game_config: &mut Cnma) {
+        while self.undo.len() > count && self.undo(level_data, game_config).is_some() {}
+        while self.undo.len() < count && self.redo(level_data, game_config).is_some() {}
+    }
+
+    /// The entries that can be undone, oldest first
+    pub fn get_undo_entries(&self) -> &[HistoryEntry] {
+        &self.undo
+    }
+
+    /// The entries that can be redone, the next one to redo last
+    pub fn get_redo_entries(&self) -> &[HistoryEntry] {
+        &self.redo
+    }
+}
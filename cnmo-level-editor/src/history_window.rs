@@ -0,0 +1,63 @@
+use cnmo_parse::lparse::level_data::LevelData;
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+
+pub struct HistoryWindow {
+    pub open: bool,
+}
+
+impl HistoryWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    /// Shows the undo history oldest first with the undone entries after it.
+    /// Returns true if the history was moved so selections can be cleared.
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &mut LevelData, editor_data: &mut EditorData) -> bool {
+        if !self.open {
+            return false;
+        }
+        let mut jump_to = None;
+        let mut open = self.open;
+        egui::Window::new("History")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            let undo_entries = editor_data.history.get_undo_entries();
+            let redo_entries = editor_data.history.get_redo_entries();
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!undo_entries.is_empty(), egui::Button::new("Undo")).on_hover_text("Ctrl+Z").clicked() {
+                    jump_to = Some(undo_entries.len() - 1);
+                }
+                if ui.add_enabled(!redo_entries.is_empty(), egui::Button::new("Redo")).on_hover_text("Ctrl+Shift+Z").clicked() {
+                    jump_to = Some(undo_entries.len() + 1);
+                }
+                let size: usize = undo_entries.iter().chain(redo_entries.iter()).map(|entry| entry.size()).sum();
+                ui.label(format!("{} steps, about {} KB", undo_entries.len() + redo_entries.len(), size / 1024));
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(400.0).stick_to_bottom(true).show(ui, |ui| {
+                if ui.selectable_label(undo_entries.is_empty(), "Start").clicked() {
+                    jump_to = Some(0);
+                }
+                for (idx, entry) in undo_entries.iter().enumerate() {
+                    if ui.selectable_label(idx + 1 == undo_entries.len(), entry.label.as_str()).clicked() {
+                        jump_to = Some(idx + 1);
+                    }
+                }
+                for (idx, entry) in redo_entries.iter().rev().enumerate() {
+                    let text = egui::RichText::new(entry.label.as_str()).weak();
+                    if ui.selectable_label(false, text).on_hover_text("Undone, click to redo up to here").clicked() {
+                        jump_to = Some(undo_entries.len() + idx + 1);
+                    }
+                }
+            });
+        });
+        self.open = open;
+        if let Some(count) = jump_to {
+            editor_data.history.jump_to(count, level_data, &mut editor_data.game_config_file);
+        }
+        jump_to.is_some()
+    }
+}
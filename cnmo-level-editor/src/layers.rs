@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::mem::{discriminant, Discriminant};
+use std::sync::OnceLock;
+
+use cnmo_parse::lparse::level_data::cnms_types::wobj_type::WobjType;
+
+use crate::level_panel::WobjIter;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SpawnerCategory {
+    Enemies,
+    Environmental,
+    Triggers,
+    Collectables,
+}
+
+impl SpawnerCategory {
+    pub const ALL: [SpawnerCategory; 4] = [
+        SpawnerCategory::Enemies,
+        SpawnerCategory::Environmental,
+        SpawnerCategory::Triggers,
+        SpawnerCategory::Collectables,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SpawnerCategory::Enemies => "Enemies",
+            SpawnerCategory::Environmental => "Environmental",
+            SpawnerCategory::Triggers => "Triggers",
+            SpawnerCategory::Collectables => "Collectables",
+        }
+    }
+
+    /// The category the type is listed under in the spawner list
+    pub fn of(wobj_type: &WobjType) -> Self {
+        get_categories()
+            .get(&std::mem::discriminant(wobj_type))
+            .copied()
+            .unwrap_or(SpawnerCategory::Environmental)
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// The category of every type, worked out once from where each category
+/// starts in the order of [`WobjIter`]
+fn get_categories() -> &'static HashMap<Discriminant<WobjType>, SpawnerCategory> {
+    static CATEGORIES: OnceLock<HashMap<Discriminant<WobjType>, SpawnerCategory>> = OnceLock::new();
+    CATEGORIES.get_or_init(|| {
+        let environmental_start = discriminant(&WobjType::BreakablePlatform {
+            time_till_fall: Default::default(),
+        });
+        let triggers_start = discriminant(&WobjType::BackgroundSwitcher {
+            shape: Default::default(),
+            enabled_layers: Default::default(),
+        });
+        let collectables_start = discriminant(&WobjType::DroppedItem {
+            item: Default::default(),
+        });
+        let mut category = SpawnerCategory::Enemies;
+        let mut categories = HashMap::new();
+        for ty in WobjIter::new() {
+            let ty = discriminant(&ty);
+            if ty == environmental_start {
+                category = SpawnerCategory::Environmental;
+            } else if ty == triggers_start {
+                category = SpawnerCategory::Triggers;
+            } else if ty == collectables_start {
+                category = SpawnerCategory::Collectables;
+            }
+            categories.insert(ty, category);
+        }
+        categories
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Foreground,
+    Background,
+    Light,
+    Spawners,
+    SpawnerCategory(SpawnerCategory),
+    /// One of the level's background layers, by index
+    Backdrop(usize),
+}
+
+#[derive(Clone, Copy)]
+pub struct LayerState {
+    pub visible: bool,
+    pub locked: bool,
+}
+
+impl Default for LayerState {
+    fn default() -> Self {
+        Self { visible: true, locked: false }
+    }
+}
+
+/// What parts of the level are drawn and can be edited in the world view
+pub struct Layers {
+    foreground: LayerState,
+    background: LayerState,
+    light: LayerState,
+    spawners: LayerState,
+    spawner_categories: [LayerState; 4],
+    background_layers: Vec<LayerState>,
+    /// Only this layer is shown when set. Soloing the spawners keeps every category.
+    pub solo: Option<Layer>,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Self {
+            foreground: Default::default(),
+            background: Default::default(),
+            light: Default::default(),
+            spawners: Default::default(),
+            spawner_categories: Default::default(),
+            background_layers: vec![],
+            solo: None,
+        }
+    }
+
+    pub fn get(&self, layer: Layer) -> LayerState {
+        match layer {
+            Layer::Foreground => self.foreground,
+            Layer::Background => self.background,
+            Layer::Light => self.light,
+            Layer::Spawners => self.spawners,
+            Layer::SpawnerCategory(category) => self.spawner_categories[category.index()],
+            Layer::Backdrop(idx) => self.background_layers.get(idx).copied().unwrap_or_default(),
+        }
+    }
+
+    pub fn get_mut(&mut self, layer: Layer) -> &mut LayerState {
+        match layer {
+            Layer::Foreground => &mut self.foreground,
+            Layer::Background => &mut self.background,
+            Layer::Light => &mut self.light,
+            Layer::Spawners => &mut self.spawners,
+            Layer::SpawnerCategory(category) => &mut self.spawner_categories[category.index()],
+            Layer::Backdrop(idx) => {
+                if idx >= self.background_layers.len() {
+                    self.background_layers.resize(idx + 1, Default::default());
+                }
+                &mut self.background_layers[idx]
+            }
+        }
+    }
+
+    fn in_solo(&self, layer: Layer) -> bool {
+        match self.solo {
+            None => true,
+            Some(Layer::Spawners) => matches!(layer, Layer::Spawners | Layer::SpawnerCategory(_)),
+            Some(solo) => solo == layer,
+        }
+    }
+
+    pub fn is_visible(&self, layer: Layer) -> bool {
+        self.get(layer).visible && self.in_solo(layer)
+    }
+
+    /// Hidden layers can't be edited either, so nothing changes out of view
+    pub fn is_editable(&self, layer: Layer) -> bool {
+        self.is_visible(layer) && !self.get(layer).locked
+    }
+
+    pub fn is_spawner_visible(&self, wobj_type: &WobjType) -> bool {
+        let category = Layer::SpawnerCategory(SpawnerCategory::of(wobj_type));
+        self.spawners.visible
+            && self.get(category).visible
+            && (self.in_solo(Layer::Spawners) || self.in_solo(category))
+    }
+
+    pub fn is_spawner_editable(&self, wobj_type: &WobjType) -> bool {
+        let category = Layer::SpawnerCategory(SpawnerCategory::of(wobj_type));
+        self.is_spawner_visible(wobj_type) && !self.spawners.locked && !self.get(category).locked
+    }
+}
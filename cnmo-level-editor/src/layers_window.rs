@@ -0,0 +1,89 @@
+use cnmo_parse::lparse::level_data::{cnmb_types::BackgroundImage, LevelData};
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+use crate::layers::{Layer, Layers, SpawnerCategory};
+
+pub struct LayersWindow {
+    pub open: bool,
+}
+
+impl LayersWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &LevelData, editor_data: &mut EditorData) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Layers")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            let layers = &mut editor_data.layers;
+            ui.horizontal(|ui| {
+                if ui.button("Show All").clicked() {
+                    *layers = Layers::new();
+                }
+                if let Some(solo) = layers.solo {
+                    ui.label(format!("Solo: {}", get_layer_name(solo, level_data)));
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("layers_grid").num_columns(4).striped(true).show(ui, |ui| {
+                    ui.label("Layer");
+                    ui.label("Show");
+                    ui.label("Lock");
+                    ui.label("Solo");
+                    ui.end_row();
+                    let mut rows = vec![Layer::Foreground, Layer::Background, Layer::Light, Layer::Spawners];
+                    rows.extend(SpawnerCategory::ALL.map(Layer::SpawnerCategory));
+                    rows.extend((0..level_data.background_layers.len()).map(Layer::Backdrop));
+                    for layer in rows {
+                        show_layer_row(ui, layers, layer, level_data);
+                    }
+                });
+            });
+        });
+        self.open = open;
+    }
+}
+
+fn show_layer_row(ui: &mut egui::Ui, layers: &mut Layers, layer: Layer, level_data: &LevelData) {
+    let name = get_layer_name(layer, level_data);
+    if matches!(layer, Layer::SpawnerCategory(_)) {
+        ui.label(format!("    {name}"));
+    } else {
+        ui.label(name);
+    }
+    let state = layers.get_mut(layer);
+    ui.checkbox(&mut state.visible, "");
+    ui.checkbox(&mut state.locked, "");
+    let solo = layers.solo == Some(layer);
+    if ui.selectable_label(solo, "S").on_hover_text("Only show this layer").clicked() {
+        layers.solo = if solo { None } else { Some(layer) };
+    }
+    ui.end_row();
+}
+
+fn get_layer_name(layer: Layer, level_data: &LevelData) -> String {
+    match layer {
+        Layer::Foreground => "Foreground Tiles".to_string(),
+        Layer::Background => "Background Tiles".to_string(),
+        Layer::Light => "Lights".to_string(),
+        Layer::Spawners => "Spawners".to_string(),
+        Layer::SpawnerCategory(category) => category.get_name().to_string(),
+        Layer::Backdrop(idx) => {
+            let kind = match level_data.background_layers.get(idx).map(|layer| (&layer.image, layer.in_foreground)) {
+                Some((BackgroundImage::Color(_), _)) => "Color",
+                Some((BackgroundImage::Bitmap(_), true)) => "Image, in front",
+                Some((BackgroundImage::Bitmap(_), false)) => "Image",
+                None => "Unused",
+            };
+            format!("Background {idx} ({kind})")
+        }
+    }
+}
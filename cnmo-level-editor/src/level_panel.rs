@@ -1,4 +1,5 @@
 use cnmo_parse::lparse::level_data;
+use cnmo_parse::cnma::ResourceId;
 use cnmo_parse::lparse::level_data::cnmb_types::{BackgroundImage, BackgroundFlags};
 use cnmo_parse::lparse::level_data::cnms_types::item_type::ItemType;
 use cnmo_parse::lparse::level_data::cnms_types::wobj_type::{WobjType, CustomizableMovingPlatformType};
@@ -6,14 +7,42 @@ use cnmo_parse::lparse::level_data::cnms_types::{Spawner, SpawnerMode};
 use eframe::egui;
 use std::env;
 
+use crate::drop_table::DropTable;
 use crate::editor_data::{EditorData, Tool};
 use crate::game_config_panel::GameConfigPanel;
+use crate::preferences;
+use crate::layers::{Layer, SpawnerCategory};
+use crate::light_tools::{self, LightMode};
 use crate::tile_viewer;
 use crate::world_panel::WorldPanel;
 //use crate::common_gfx;
 
 crate::create_instance_resource!(LevelIconPreviewSpriteInstances);
 
+/// Loads `levels/<level_file_name>.json` into the editor. Returns if it loaded.
+pub fn load_level(level_data: &mut level_data::LevelData, editor_data: &mut EditorData, force_gfx_reload: &mut bool) -> bool {
+    let path = std::path::Path::new("levels/").join(editor_data.level_file_name.clone() + ".json");
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => {
+            log::warn!("Can't open the file!");
+            return false;
+        }
+    };
+    if let Ok(data) = serde_json::from_reader(file) {
+        *level_data = data;
+        editor_data.reset_selected_tiles();
+        editor_data.history.reset();
+        *force_gfx_reload |= editor_data.set_gfx_file(&editor_data.level_file_name.clone());
+        preferences::add_recent_level(&mut editor_data.recent_levels, &editor_data.level_file_name);
+        log::info!("Loaded the level!");
+        true
+    } else {
+        log::error!("Invalid editor project file!");
+        false
+    }
+}
+
 pub fn show_metadata_panel(
     world_panel: &mut crate::world_panel::WorldPanel,
     editor_data: &mut EditorData,
@@ -49,28 +78,7 @@ pub fn show_metadata_panel(
         //     .set_title("Load an editor project file")
         //     .add_filter("Level Editor Files", &["json"])
         //     .pick_file();
-        let path = Some(
-            std::path::Path::new("levels/").join(editor_data.level_file_name.clone() + ".json"),
-        );
-        if let Some(path) = path {
-            let file = std::fs::File::open(path);
-            if let Ok(file) = file {
-                if let Ok(data) = serde_json::from_reader(file) {
-                    *level_data = data;
-                    editor_data.reset_selected_tiles();
-                    editor_data.cells_history =
-                        vec![(level_data.cells.clone(), level_data.spawners.clone())];
-                    *force_gfx_reload |= editor_data.set_gfx_file(&editor_data.level_file_name.clone());
-                    log::info!("Loaded the level!");
-                } else {
-                    log::error!("Invalid editor project file!");
-                }
-            } else {
-                log::warn!("Can't open the file!");
-            }
-        } else {
-            log::warn!("File open dialog didn't return path!");
-        }
+        load_level(level_data, editor_data, force_gfx_reload);
     }
     if ui.button("Save Level").clicked() || (ui.ctx().input().key_pressed(egui::Key::S) && (ui.ctx().input().modifiers.mac_cmd || ui.ctx().input().modifiers.ctrl)) {
         // let path = rfd::FileDialog::new()
@@ -86,6 +94,7 @@ pub fn show_metadata_panel(
             if let Ok(file) = file {
                 let _ = serde_json::to_writer_pretty(file, level_data);
                 *force_gfx_reload |= editor_data.set_gfx_file(&editor_data.level_file_name.clone());
+                preferences::add_recent_level(&mut editor_data.recent_levels, &editor_data.level_file_name);
                 log::info!("Saved the level!");
             } else {
                 log::error!("Can't open the file for writing!");
@@ -128,8 +137,7 @@ pub fn show_metadata_panel(
                     Ok(data) => {
                         *level_data = data;
                         editor_data.reset_selected_tiles();
-                        editor_data.cells_history =
-                            vec![(level_data.cells.clone(), level_data.spawners.clone())];
+                        editor_data.history.reset();
                         *force_gfx_reload |= editor_data.set_gfx_file(level_name.as_str());
                         log::info!("Successfully decompiled the level files!");
                     }
@@ -317,11 +325,9 @@ pub fn show_metadata_panel(
             }
             if ui.selectable_value(editor_mode, super::EditorMode::Tile, "Tile").clicked() {
                 editor_data.reset_selected_tiles();
-                editor_data.cells_history = vec![];
             }
             if ui.selectable_value(editor_mode, super::EditorMode::GameConfig, "Game Config").clicked() {
                 editor_data.reset_selected_tiles();
-                editor_data.cells_history = vec![];
             }
         });
         ui.end_row();
@@ -385,6 +391,26 @@ pub fn show_metadata_panel(
             {
                 world_panel.show_grid = !world_panel.show_grid;
             }
+            if ui
+                .selectable_label(world_panel.show_collision, "Show collision")
+                .on_hover_text("Shows the collision of solid tiles and tints tiles with a damage type")
+                .clicked()
+            {
+                world_panel.show_collision = !world_panel.show_collision;
+            }
+            if ui
+                .selectable_label(editor_data.preview_paused, "Pause animations")
+                .on_hover_text("Freezes animated tiles and moving spawners")
+                .clicked()
+            {
+                editor_data.preview_paused = !editor_data.preview_paused;
+            }
+            if editor_data.preview_paused && ui.button("Step").on_hover_text("Advance one game frame").clicked() {
+                editor_data.step_preview();
+            }
+            if ui.button("Restart").on_hover_text("Restart animations from the first frame").clicked() {
+                editor_data.preview_time = std::time::Duration::ZERO;
+            }
             if matches!(editor_data.tool, Tool::Spawners) {
                 egui::ComboBox::new("spawner_grid_size", "Spawner Grid Size")
                     .selected_text(if editor_data.spawner_grid_size < 1.0 {
@@ -401,6 +427,17 @@ pub fn show_metadata_panel(
                         ui.selectable_value(&mut editor_data.spawner_grid_size, 64.0, "2 tiles");
                     });
             }
+            if editor_data.tool.can_paint_light() {
+                ui.checkbox(&mut editor_data.shape_on_light, "Paint light")
+                    .on_hover_text("Use the light level instead of the selected tile");
+            }
+            if matches!(editor_data.tool, Tool::Rectangle | Tool::Ellipse) {
+                ui.checkbox(&mut editor_data.shape_filled, "Filled");
+            }
+            if matches!(editor_data.tool, Tool::Brush) {
+                ui.checkbox(&mut editor_data.copy_spawners_with_tiles, "Copy spawners with tiles")
+                    .on_hover_text("Shift dragging tiles also copies the spawners in them, which are pasted with each click");
+            }
             if ui
                 .selectable_label(
                     world_panel.show_original_screen_size,
@@ -411,53 +448,84 @@ pub fn show_metadata_panel(
                 world_panel.show_original_screen_size = !world_panel.show_original_screen_size;
             }
             ui.separator();
+            show_spawn_preview(ui, level_data, world_panel);
+            ui.separator();
             show_background_list(ui, world_panel);
             ui.label("Tools");
             if ui
-                .selectable_label(matches!(editor_data.tool, Tool::Brush), "Brush (B)")
+                .selectable_label(matches!(editor_data.tool, Tool::Brush), format!("Brush ({})", editor_data.keybinds.brush))
                 .clicked()
-                || (ui.ctx().input().key_pressed(egui::Key::B) && editor_data.editing_text == None)
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.brush) && editor_data.editing_text.is_none())
             {
                 editor_data.tool = Tool::Brush;
             }
             if ui
-                .selectable_label(matches!(editor_data.tool, Tool::Light), "Light (T)")
+                .selectable_label(matches!(editor_data.tool, Tool::Light), format!("Light ({})", editor_data.keybinds.light))
                 .clicked()
-                || (ui.ctx().input().key_pressed(egui::Key::T) && editor_data.editing_text == None)
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.light) && editor_data.editing_text.is_none())
             {
                 editor_data.tool = Tool::Light;
             }
             if ui
-                .selectable_label(matches!(editor_data.tool, Tool::Eraser), "Eraser (E)")
+                .selectable_label(matches!(editor_data.tool, Tool::Eraser), format!("Eraser ({})", editor_data.keybinds.eraser))
                 .clicked()
-                || (ui.ctx().input().key_pressed(egui::Key::E) && !(ui.ctx().input().modifiers.mac_cmd || ui.ctx().input().modifiers.ctrl) && editor_data.editing_text == None)
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.eraser) && editor_data.editing_text.is_none())
             {
                 editor_data.tool = Tool::Eraser;
             }
             if ui
-                .selectable_label(matches!(editor_data.tool, Tool::Fill), "Fill (F)")
+                .selectable_label(matches!(editor_data.tool, Tool::Fill), format!("Fill ({})", editor_data.keybinds.fill))
                 .clicked()
-                || (ui.ctx().input().key_pressed(egui::Key::F) && editor_data.editing_text == None)
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.fill) && editor_data.editing_text.is_none())
             {
                 editor_data.tool = Tool::Fill;
             }
             if ui
                 .selectable_label(
                     matches!(editor_data.tool, Tool::TilePicker),
-                    "Tile Picker (R)",
+                    format!("Tile Picker ({})", editor_data.keybinds.tile_picker),
                 )
                 .clicked()
-                || (ui.ctx().input().key_pressed(egui::Key::R) && editor_data.editing_text == None)
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.tile_picker) && editor_data.editing_text.is_none())
             {
                 editor_data.tool = Tool::TilePicker;
             }
             if ui
-                .selectable_label(matches!(editor_data.tool, Tool::Spawners), "Spawners (S)")
+                .selectable_label(matches!(editor_data.tool, Tool::Spawners), format!("Spawners ({})", editor_data.keybinds.spawners))
                 .clicked()
-                || (ui.ctx().input().key_pressed(egui::Key::S) && !(ui.ctx().input().modifiers.mac_cmd || ui.ctx().input().modifiers.ctrl) && editor_data.editing_text == None)
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.spawners) && editor_data.editing_text.is_none())
             {
                 editor_data.tool = Tool::Spawners;
             }
+            if ui
+                .selectable_label(matches!(editor_data.tool, Tool::Rectangle), format!("Rectangle ({})", editor_data.keybinds.rectangle))
+                .clicked()
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.rectangle) && editor_data.editing_text.is_none())
+            {
+                editor_data.tool = Tool::Rectangle;
+            }
+            if ui
+                .selectable_label(matches!(editor_data.tool, Tool::Line), format!("Line ({})", editor_data.keybinds.line))
+                .clicked()
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.line) && editor_data.editing_text.is_none())
+            {
+                editor_data.tool = Tool::Line;
+            }
+            if ui
+                .selectable_label(matches!(editor_data.tool, Tool::Ellipse), format!("Ellipse ({})", editor_data.keybinds.ellipse))
+                .clicked()
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.ellipse) && editor_data.editing_text.is_none())
+            {
+                editor_data.tool = Tool::Ellipse;
+            }
+            if ui
+                .selectable_label(matches!(editor_data.tool, Tool::Replace), format!("Replace Everywhere ({})", editor_data.keybinds.replace))
+                .on_hover_text("Click a cell to change every cell in the level with the same tile or light level")
+                .clicked()
+                || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.replace) && editor_data.editing_text.is_none())
+            {
+                editor_data.tool = Tool::Replace;
+            }
         }
         super::EditorMode::Background => {
             show_background_list(ui, world_panel);
@@ -480,27 +548,48 @@ pub fn show_metadata_panel(
         super::EditorMode::Tile => {}
         super::EditorMode::GameConfig => {
             if ui.button("Save Game Config").clicked() {
+                for mode in editor_data.game_config_file.modes.iter() {
+                    if let cnmo_parse::cnma::Mode::LuaAutorunCode(code) = mode {
+                        if let Some(error) = cnmo_parse::cnma::lua::check(code).error {
+                            log::warn!("The lua autorun code has a syntax error, the game won't run it!\n{}", error);
+                        }
+                    }
+                }
                 let _ = std::fs::copy("audio.cnma", "audio.cnma.backup");
-                match editor_data.game_config_file.save("audio.cnma") {
+                match editor_data.game_config_document.save(&editor_data.game_config_file, "audio.cnma") {
                     Ok(_) => log::info!("Successfully saved the game config file!"),
                     Err(err) => log::error!("Couldn't save config due to {}", err),
                 };
             }
             if ui.button("Restore Last Load").clicked() {
-                match cnmo_parse::cnma::Cnma::from_file("audio.cnma.backup") {
-                    Ok(file) => {
-                        editor_data.game_config_file = file;
+                match cnmo_parse::cnma::cst::CnmaDocument::from_file("audio.cnma.backup") {
+                    Ok(document) => {
+                        editor_data.game_config_file = document.cnma();
+                        editor_data.game_config_document = document;
                         log::info!("Successfully reloaded audio.cnma.backup!");
                     }
-                    Err(_) => match cnmo_parse::cnma::Cnma::from_file("audio.cnma") {
-                        Ok(file) => {
-                            editor_data.game_config_file = file;
+                    Err(_) => match cnmo_parse::cnma::cst::CnmaDocument::from_file("audio.cnma") {
+                        Ok(document) => {
+                            editor_data.game_config_file = document.cnma();
+                            editor_data.game_config_document = document;
                             log::info!("Successfully reloaded audio.cnma!");
                         }
                         Err(err) => log::error!("Couldn't load config due to {}", err),
                     },
                 };
             }
+            if ui.button("Check Config").on_hover_text("Parse audio.cnma and log every problem").clicked() {
+                match cnmo_parse::cnma::Cnma::from_file_with_options("audio.cnma", Default::default()) {
+                    Ok((_, warnings)) if warnings.is_empty() => log::info!("audio.cnma has no problems!"),
+                    Ok((_, warnings)) => {
+                        for warning in warnings.iter() {
+                            log::warn!("audio.cnma:\n{}", warning);
+                        }
+                        log::info!("audio.cnma has {} problems", warnings.len());
+                    }
+                    Err(err) => log::error!("audio.cnma:\n{}", err),
+                };
+            }
             ui.label("");
             ui.label("");
             if cfg_panel.preview_gfx {
@@ -520,6 +609,7 @@ pub struct PropertiesPanel {
     tile_viewer: tile_viewer::TileViewer,
     dragging_bg: usize,
     dragging_bg_source: Option<usize>,
+    drop_table: DropTable,
 }
 
 impl PropertiesPanel {
@@ -528,6 +618,7 @@ impl PropertiesPanel {
             tile_viewer: tile_viewer::TileViewer::new(None, Some(50.0), None, false),
             dragging_bg: 0,
             dragging_bg_source: None,
+            drop_table: DropTable::new(),
         }
     }
 
@@ -650,7 +741,7 @@ impl PropertiesPanel {
         ui: &mut egui::Ui,
         world_panel: &mut WorldPanel,
     ) {
-        if matches!(editor_data.tool, Tool::Light) {
+        if matches!(editor_data.tool, Tool::Light) || (editor_data.tool.can_paint_light() && editor_data.shape_on_light) {
             // Light Shortcuts
             if ui.ctx().input().key_pressed(egui::Key::Num0) && editor_data.editing_text == None { editor_data.light_tool_level = 0; }
             if ui.ctx().input().key_pressed(egui::Key::Num1) && editor_data.editing_text == None { editor_data.light_tool_level = 1; }
@@ -679,6 +770,9 @@ impl PropertiesPanel {
                         }
                     });
             });
+            if matches!(editor_data.tool, Tool::Light) {
+                show_light_tool_options(ui, editor_data, level_data);
+            }
         } else if !matches!(editor_data.tool, Tool::Spawners) {
             ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
                 ui.heading("Tiles");
@@ -690,11 +784,11 @@ impl PropertiesPanel {
                 } else {
                     "Background"
                 });
-                if response.clicked() || (ui.ctx().input().key_pressed(egui::Key::Q) && editor_data.editing_text == None) {
+                if response.clicked() || (preferences::is_letter_pressed(ui.ctx(), editor_data.keybinds.swap_layer) && editor_data.editing_text.is_none()) {
                     editor_data.foreground_placing = !editor_data.foreground_placing;
                     //editor_data.light_placing = None;
                 }
-                response.on_hover_text("Press (Q) to switch between foreground and background");
+                response.on_hover_text(format!("Press ({}) to switch between foreground and background", editor_data.keybinds.swap_layer));
             });
             self.tile_viewer.edit_tiles = false;
             self.tile_viewer.max_height = Some(ui.available_height() / 2.4);
@@ -704,7 +798,10 @@ impl PropertiesPanel {
                 ui.heading("Spawner Properties");
             });
             ui.separator();
-            if let Some(idx) = editor_data.selected_spawner {
+            if editor_data.selected_spawners.len() > 1 {
+                ui.label(format!("{} spawners selected, showing the last one clicked", editor_data.selected_spawners.len()));
+            }
+            if let Some(idx) = editor_data.get_selected_spawner() {
                 let spawner = &mut level_data.spawners[idx];
 
                 ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
@@ -764,6 +861,10 @@ impl PropertiesPanel {
                     show_spawner_properties(spawner, ui, editor_data, world_panel);
                 });
             }
+            if ui.button("Drop Table").clicked() {
+                self.drop_table.open = !self.drop_table.open;
+            }
+            self.drop_table.show(ui.ctx(), level_data, editor_data);
             ui.separator();
             ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
                 ui.heading("Spawners");
@@ -826,6 +927,7 @@ impl PropertiesPanel {
                                     .clicked()
                                 {
                                     editor_data.spawner_template.type_data = spawner_type;
+                                    editor_data.copied_spawners.clear();
                                 }
                                 ui.end_row();
                             }
@@ -895,6 +997,7 @@ impl PropertiesPanel {
                 .memory()
                 .is_being_dragged(egui::Id::new("bglayer_item").with(source_idx))
             {
+                editor_data.history.label("Move Background Layer");
                 level_data.background_layers.insert(
                     self.dragging_bg,
                     level_data.background_layers[source_idx].clone(),
@@ -1112,6 +1215,25 @@ impl PropertiesPanel {
     }
 }
 
+/// Picks a music or sound id by its file name, ids that aren't in the game config are shown in red.
+/// Typed in ids are kept inside of `range`.
+fn show_resource_id_combobox(id_source: &str, id: &mut u32, range: std::ops::RangeInclusive<u32>, resources: &[&ResourceId], ui: &mut egui::Ui) {
+    let selected_text = match resources.iter().rev().find(|res| res.id == *id) {
+        Some(res) => egui::RichText::new(format!("{}: {}", res.id, res.path)),
+        None => egui::RichText::new(format!("{}: missing", id)).color(egui::Color32::LIGHT_RED),
+    };
+    ui.horizontal(|ui| {
+        egui::ComboBox::new(id_source, "")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for res in resources.iter() {
+                    ui.selectable_value(id, res.id, format!("{}: {}", res.id, res.path));
+                }
+            });
+        ui.add(egui::DragValue::new(id).clamp_range(range));
+    });
+}
+
 fn show_item_combobox(item: &mut ItemType, ui: &mut egui::Ui) {
     egui::ComboBox::new("item_combo_box", "")
         .selected_text(get_item_type_name(item))
@@ -1283,7 +1405,7 @@ fn show_spawner_properties(
                 });
             ui.end_row();
             ui.label("Music ID: ");
-            ui.add(egui::DragValue::new(music_id));
+            show_resource_id_combobox("music_id_combo_box", music_id, 0..=u32::MAX, &editor_data.game_config_file.get_music_ids(), ui);
             ui.end_row();
         }
         &mut WobjType::PlayerSpawn {
@@ -1550,7 +1672,7 @@ fn show_spawner_properties(
         }
         &mut WobjType::SfxPoint { ref mut sound_id } => {
             ui.label("Sound Effect ID: ");
-            ui.add(egui::DragValue::new(sound_id).clamp_range(0..=255));
+            show_resource_id_combobox("sound_id_combo_box", sound_id, 0..=255, &editor_data.game_config_file.get_sound_ids(), ui);
             ui.end_row();
         }
         &mut WobjType::BreakableWall {
@@ -2022,6 +2144,77 @@ fn show_spawner_properties(
     }
 }
 
+fn show_spawn_preview(ui: &mut egui::Ui, level_data: &level_data::LevelData, world_panel: &mut WorldPanel) {
+    let preview_name = |players: Option<u32>| match players {
+        None => "Everything".to_string(),
+        Some(1) => "Singleplayer".to_string(),
+        Some(players) => format!("{players} Player Multiplayer"),
+    };
+    egui::ComboBox::new("spawn_preview_combobox", "Spawn Preview")
+        .selected_text(preview_name(world_panel.spawn_preview_players))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut world_panel.spawn_preview_players, None, preview_name(None));
+            for players in 1..=8 {
+                ui.selectable_value(&mut world_panel.spawn_preview_players, Some(players), preview_name(Some(players)));
+            }
+        });
+    if world_panel.spawn_preview_players.is_some() {
+        ui.checkbox(&mut world_panel.hide_unspawned, "Hide objects that won't spawn");
+    }
+
+    let modes = [
+        SpawnerMode::MultiAndSingleplayer,
+        SpawnerMode::SingleplayerOnly,
+        SpawnerMode::MultiplayerOnly,
+        SpawnerMode::PlayerCountBased,
+        SpawnerMode::NoSpawn,
+    ];
+    // Enemies, items, checkpoints
+    let mut counts = [(0, 0, 0); 5];
+    let mut preview_counts = (0, 0, 0);
+    for spawner in level_data.spawners.iter() {
+        let enemies = is_enemy(&spawner.type_data) as u32;
+        let items = matches!(spawner.type_data, WobjType::DroppedItem { .. }) as u32 + spawner.dropped_item.is_some() as u32;
+        let checkpoints = matches!(spawner.type_data, WobjType::Checkpoint { .. }) as u32;
+        if let Some(idx) = modes.iter().position(|mode| *mode == spawner.spawning_criteria.mode) {
+            counts[idx].0 += enemies;
+            counts[idx].1 += items;
+            counts[idx].2 += checkpoints;
+        }
+        if let Some(players) = world_panel.spawn_preview_players {
+            let spawn_count = spawner.spawning_criteria.mode.get_spawn_count(players);
+            preview_counts.0 += enemies * spawn_count;
+            preview_counts.1 += items * spawn_count;
+            preview_counts.2 += checkpoints * spawn_count;
+        }
+    }
+    egui::Grid::new("spawn_mode_counts_grid").num_columns(4).striped(true).show(ui, |ui| {
+        ui.label("Mode");
+        ui.label("Enemies");
+        ui.label("Items");
+        ui.label("Checkpoints");
+        ui.end_row();
+        for (mode, count) in modes.iter().zip(counts.iter()) {
+            ui.label(get_spawner_mode_name(mode));
+            ui.label(count.0.to_string());
+            ui.label(count.1.to_string());
+            ui.label(count.2.to_string());
+            ui.end_row();
+        }
+        if let Some(players) = world_panel.spawn_preview_players {
+            ui.label(preview_name(Some(players))).on_hover_text("Player count based objects are counted once per player");
+            ui.label(preview_counts.0.to_string());
+            ui.label(preview_counts.1.to_string());
+            ui.label(preview_counts.2.to_string());
+            ui.end_row();
+        }
+    });
+}
+
+fn is_enemy(wobj_type: &WobjType) -> bool {
+    SpawnerCategory::of(wobj_type) == SpawnerCategory::Enemies
+}
+
 fn get_cnma_mode_name(mode: &cnmo_parse::cnma::Mode) -> &str {
     use cnmo_parse::cnma::Mode;
     match mode {
@@ -2035,7 +2228,7 @@ fn get_cnma_mode_name(mode: &cnmo_parse::cnma::Mode) -> &str {
     }
 }
 
-fn get_spawner_mode_name(mode: &SpawnerMode) -> &str {
+pub fn get_spawner_mode_name(mode: &SpawnerMode) -> &str {
     match mode {
         &SpawnerMode::MultiAndSingleplayer => "Multi and Singleplayer",
         &SpawnerMode::MultiplayerOnly => "Multiplayer Only",
@@ -2045,12 +2238,12 @@ fn get_spawner_mode_name(mode: &SpawnerMode) -> &str {
     }
 }
 
-struct ItemIter {
+pub struct ItemIter {
     index: u8,
 }
 
 impl ItemIter {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self { index: 0 }
     }
 }
@@ -2111,7 +2304,7 @@ impl Iterator for ItemIter {
     }
 }
 
-fn get_item_type_name(item_type: &ItemType) -> &str {
+pub fn get_item_type_name(item_type: &ItemType) -> &str {
     use ItemType::*;
 
     match item_type {
@@ -2160,12 +2353,12 @@ fn get_item_type_name(item_type: &ItemType) -> &str {
     }
 }
 
-struct WobjIter {
+pub struct WobjIter {
     index: u8,
 }
 
 impl WobjIter {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self { index: 0 }
     }
 }
@@ -2407,7 +2600,7 @@ impl Iterator for WobjIter {
     }
 }
 
-fn get_wobj_type_name(wobj_type: &WobjType) -> &str {
+pub fn get_wobj_type_name(wobj_type: &WobjType) -> &str {
     use WobjType::*;
     match &wobj_type {
         &Teleport(_) => "Teleport",
@@ -2479,3 +2672,50 @@ fn get_wobj_type_name(wobj_type: &WobjType) -> &str {
         &LensFlare { .. } => "Lens Flare",
     }
 }
+
+fn show_light_tool_options(ui: &mut egui::Ui, editor_data: &mut EditorData, level_data: &mut level_data::LevelData) {
+    let options = &mut editor_data.light_options;
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut options.mode, LightMode::Paint, "Paint");
+        ui.selectable_value(&mut options.mode, LightMode::Source, "Light Source")
+            .on_hover_text("Click to place a round light of the color level");
+        ui.selectable_value(&mut options.mode, LightMode::Gradient, "Gradient")
+            .on_hover_text("Drag to fade from the color level to the end level");
+    });
+    match options.mode {
+        LightMode::Paint => {}
+        LightMode::Source => {
+            ui.add(egui::Slider::new(&mut options.radius, 1.0..=32.0).text("Radius"));
+            ui.add(egui::Slider::new(&mut options.falloff, 0.25..=4.0).text("Falloff"))
+                .on_hover_text("Higher values keep the light bright further out");
+        }
+        LightMode::Gradient => {
+            ui.add(egui::Slider::new(&mut options.gradient_end_level, level_data::consts::LIGHT_WHITE..=level_data::consts::LIGHT_BLACK).text("End Level"));
+            ui.add(egui::Slider::new(&mut options.gradient_width, 1.0..=64.0).text("Width"));
+        }
+    }
+    ui.separator();
+    let light_editable = editor_data.layers.is_editable(Layer::Light);
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(light_editable, egui::Button::new("Smooth"))
+            .on_hover_text("Averages the light of every cell with its neighbors")
+            .clicked()
+        {
+            editor_data.history.label("Smooth Light");
+            let changes = light_tools::get_smoothed(&level_data.cells);
+            light_tools::apply_light_changes(&mut level_data.cells, &changes);
+        }
+        if ui
+            .add_enabled(light_editable, egui::Button::new("Darken Enclosed"))
+            .on_hover_text("Darkens solid tiles the further they're buried in other solid tiles")
+            .clicked()
+        {
+            editor_data.history.label("Darken Enclosed Tiles");
+            let changes = light_tools::get_enclosed_darkening(&level_data.cells, &level_data.tile_properties, editor_data.light_options.darken_level);
+            light_tools::apply_light_changes(&mut level_data.cells, &changes);
+        }
+        ui.add(egui::DragValue::new(&mut editor_data.light_options.darken_level).clamp_range(level_data::consts::LIGHT_NORMAL..=level_data::consts::LIGHT_BLACK))
+            .on_hover_text("The darkest level buried tiles get");
+    });
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use cnmo_parse::cnma::level_select::{load_level_metadata, scan_levels, LevelFiles, LevelSelectProblem};
+use cnmo_parse::cnma::Mode;
+use cnmo_parse::lparse::level_data::LevelMetaData;
+use eframe::egui;
+
+use crate::editor_data::{get_gfx_file, EditorData};
+
+pub struct LevelSelectWindow {
+    pub open: bool,
+    levels: Option<Vec<LevelFiles>>,
+    metadata: HashMap<String, Result<LevelMetaData, String>>,
+    /// Level select icons cut out of each level's own graphics file
+    icons: HashMap<String, Option<egui::TextureHandle>>,
+    selected: Option<String>,
+    drag: usize,
+    drag_source: Option<usize>,
+    new_level: String,
+}
+
+impl LevelSelectWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            levels: None,
+            metadata: HashMap::new(),
+            icons: HashMap::new(),
+            selected: None,
+            drag: 0,
+            drag_source: None,
+            new_level: "".to_string(),
+        }
+    }
+
+    fn get_metadata(&mut self, name: &str) -> &Result<LevelMetaData, String> {
+        self.metadata
+            .entry(name.to_string())
+            .or_insert_with(|| load_level_metadata("levels", name).map_err(|err| err.to_string()))
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, editor_data: &mut EditorData) {
+        if !self.open {
+            return;
+        }
+        if self.levels.is_none() {
+            self.levels = Some(scan_levels("levels"));
+            self.metadata.clear();
+            self.icons.clear();
+        }
+        let mut open = self.open;
+        egui::Window::new("Level Select Order")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            let levels = self.levels.clone().unwrap_or_default();
+            let problems = editor_data.game_config_file.check_level_select(&levels);
+            ui.horizontal(|ui| {
+                if ui.button("Rescan").on_hover_text("Look for levels in the levels folder again").clicked() {
+                    self.levels = None;
+                }
+                ui.label(format!("{} levels in the levels folder", levels.len()));
+            });
+
+            let mode_idx = editor_data
+                .game_config_file
+                .modes
+                .iter()
+                .position(|mode| matches!(mode, Mode::LevelSelectOrder(_)));
+            let order = match mode_idx {
+                Some(idx) => match &mut editor_data.game_config_file.modes[idx] {
+                    Mode::LevelSelectOrder(order) => order,
+                    _ => unreachable!(),
+                },
+                None => {
+                    ui.label("The game config has no level select order");
+                    if ui.button("Add Level Select Order").clicked() {
+                        editor_data.game_config_file.modes.push(Mode::LevelSelectOrder(vec![]));
+                    }
+                    return;
+                }
+            };
+            ui.separator();
+
+            ui.heading("Menu Order");
+            let mut delete_idx = None;
+            egui::ScrollArea::vertical().id_source("level_select_order_scroll").max_height(300.0).show(ui, |ui| {
+                for (idx, level) in order.iter_mut().enumerate() {
+                    if self.drag == idx && Some(self.drag) != self.drag_source && self.drag_source.is_some() {
+                        ui.label("Move here");
+                    }
+                    ui.horizontal(|ui| {
+                        let problem = problems.iter().find(|problem| match problem {
+                            LevelSelectProblem::MissingLevel { name }
+                            | LevelSelectProblem::IncompleteLevel { name, .. }
+                            | LevelSelectProblem::DuplicateLevel { name } => *name == level.0,
+                            LevelSelectProblem::UnlistedLevel { .. } => false,
+                        });
+                        let mut text = egui::RichText::new(level.0.as_str());
+                        if let Some(problem) = problem {
+                            text = text.color(if problem.is_error() { egui::Color32::LIGHT_RED } else { egui::Color32::YELLOW });
+                        }
+                        let response = ui.selectable_label(self.selected.as_ref() == Some(&level.0), text);
+                        let response = match problem {
+                            Some(problem) => response.on_hover_text(problem.to_string()),
+                            None => response,
+                        };
+                        if response.clicked() {
+                            self.selected = Some(level.0.clone());
+                        }
+                        if problem.is_none() {
+                            if let Ok(metadata) = self.get_metadata(&level.0) {
+                                ui.label(metadata.get_full_title());
+                            }
+                        }
+                        ui.add(egui::DragValue::new(&mut level.1));
+                        if ui.button("Remove").clicked() {
+                            delete_idx = Some(idx);
+                        }
+                        if ui.rect_contains_pointer(response.rect) {
+                            if response.ctx.input().pointer.primary_clicked() {
+                                self.drag_source = Some(idx);
+                            }
+                            self.drag = idx;
+                        }
+                    });
+                }
+            });
+
+            if let Some(idx) = delete_idx {
+                order.remove(idx);
+                self.drag = 0;
+                self.drag_source = None;
+            }
+            if let Some(src) = self.drag_source {
+                if self.drag != src {
+                    egui::Area::new("level_select_window_dragging")
+                        .interactable(false)
+                        .fixed_pos(ui.ctx().pointer_interact_pos().unwrap_or_default())
+                        .show(ui.ctx(), |ui| {
+                            ui.label(order[src].0.as_str());
+                        });
+                }
+            }
+            if ui.ctx().input().pointer.any_released() {
+                if let Some(src) = self.drag_source {
+                    let temp = order.remove(src);
+                    if self.drag <= src {
+                        order.insert(self.drag, temp);
+                    } else {
+                        order.insert(self.drag - 1, temp);
+                    }
+                }
+                self.drag = 0;
+                self.drag_source = None;
+            }
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::new("level_select_new_level", "")
+                    .selected_text(self.new_level.as_str())
+                    .show_ui(ui, |ui| {
+                    for level in levels.iter().filter(|level| level.is_complete()) {
+                        ui.selectable_value(&mut self.new_level, level.name.clone(), level.name.as_str());
+                    }
+                });
+                if ui.button("Insert").on_hover_text("Insert after the selected level, or at the end").clicked() && !self.new_level.is_empty() {
+                    let idx = self
+                        .selected
+                        .as_ref()
+                        .and_then(|selected| order.iter().position(|level| level.0 == *selected))
+                        .map_or(order.len(), |idx| idx + 1);
+                    order.insert(idx, (self.new_level.clone(), 0));
+                    self.selected = Some(self.new_level.clone());
+                }
+            });
+
+            let unlisted: Vec<&String> = problems
+                .iter()
+                .filter_map(|problem| match problem {
+                    LevelSelectProblem::UnlistedLevel { name } => Some(name),
+                    _ => None,
+                })
+                .collect();
+            if !unlisted.is_empty() {
+                ui.separator();
+                ui.heading("Not On The Menu");
+                for name in unlisted {
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(self.selected.as_ref() == Some(name), name.as_str()).clicked() {
+                            self.selected = Some(name.clone());
+                        }
+                        if ui.button("Add").clicked() {
+                            order.push((name.clone(), 0));
+                        }
+                    });
+                }
+            }
+
+            if let Some(selected) = self.selected.clone() {
+                ui.separator();
+                ui.heading(selected.as_str());
+                match self.get_metadata(&selected) {
+                    Ok(metadata) => {
+                        let preview_loc = metadata.preview_loc;
+                        egui::Grid::new("level_select_metadata_grid").num_columns(2).show(ui, |ui| {
+                            ui.label("Title: ");
+                            ui.label(metadata.title.as_str());
+                            ui.end_row();
+                            ui.label("Subtitle: ");
+                            ui.label(metadata.subtitle.as_deref().unwrap_or(""));
+                            ui.end_row();
+                            ui.label("Difficulty: ");
+                            ui.label(metadata.difficulty_rating.to_string_pretty());
+                            ui.end_row();
+                            ui.label("Type: ");
+                            ui.label(metadata.level_type.to_string_pretty());
+                            ui.end_row();
+                        });
+                        let icon = self
+                            .icons
+                            .entry(selected.clone())
+                            .or_insert_with(|| load_icon(ui.ctx(), &selected, preview_loc));
+                        match icon {
+                            Some(icon) => {
+                                ui.image(icon.id(), egui::Vec2::new(96.0, 64.0))
+                                    .on_hover_text(format!("From {}", get_gfx_file(&selected)));
+                            }
+                            None => {
+                                ui.colored_label(egui::Color32::YELLOW, format!("The icon isn't inside of {}", get_gfx_file(&selected)));
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        ui.colored_label(egui::Color32::LIGHT_RED, format!("Can't load the level: {err}"));
+                    }
+                }
+            }
+        });
+        self.open = open;
+        if !self.open {
+            self.levels = None;
+        }
+    }
+}
+
+/// Cuts the 96x64 level select icon at `preview_loc` (in tiles) out of the
+/// graphics file the level uses
+fn load_icon(ctx: &egui::Context, name: &str, preview_loc: (u32, u32)) -> Option<egui::TextureHandle> {
+    let gfx_file = get_gfx_file(name);
+    let image = match image::open(&gfx_file) {
+        Ok(image) => image.to_rgba8(),
+        Err(err) => {
+            log::warn!("Can't load {gfx_file} for the level select icon due to {err}");
+            return None;
+        }
+    };
+    let (x, y) = (preview_loc.0.saturating_mul(32), preview_loc.1.saturating_mul(32));
+    if x.saturating_add(96) > image.width() || y.saturating_add(64) > image.height() {
+        return None;
+    }
+    let pixels = (y..y + 64)
+        .flat_map(|py| (x..x + 96).map(move |px| (px, py)))
+        .map(|(px, py)| match image.get_pixel(px, py).0 {
+            // The transparent color of CNM graphics
+            [0, 255, 255, _] => egui::Color32::TRANSPARENT,
+            [r, g, b, _] => egui::Color32::from_rgb(r, g, b),
+        })
+        .collect();
+    let image = egui::ColorImage { size: [96, 64], pixels };
+    Some(ctx.load_texture(format!("level_select_icon_{name}"), image, egui::TextureOptions::NEAREST))
+}
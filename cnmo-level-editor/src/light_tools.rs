@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+
+use cnmo_parse::lparse::level_data::brushes::in_bounds;
+use cnmo_parse::lparse::level_data::cnmb_types::{Cells, CollisionType, TileProperties};
+use cnmo_parse::lparse::level_data::consts::{LIGHT_BLACK, LIGHT_NORMAL, LIGHT_WHITE};
+
+/// What dragging or clicking with the light tool does
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LightMode {
+    /// Paints the light level cell by cell
+    Paint,
+    /// A round light that fades out to normal lighting
+    Source,
+    /// Fades between two light levels along the dragged line
+    Gradient,
+}
+
+/// Settings for the light tool's modes and whole level passes
+pub struct LightToolOptions {
+    pub mode: LightMode,
+    /// In cells
+    pub radius: f32,
+    pub falloff: f32,
+    /// The gradient goes from the light tool's level to this one
+    pub gradient_end_level: u8,
+    /// In cells
+    pub gradient_width: f32,
+    /// The darkest buried tiles can get
+    pub darken_level: u8,
+}
+
+impl LightToolOptions {
+    pub fn new() -> Self {
+        Self {
+            mode: LightMode::Paint,
+            radius: 4.0,
+            falloff: 1.0,
+            gradient_end_level: LIGHT_NORMAL,
+            gradient_width: 8.0,
+            darken_level: LIGHT_BLACK,
+        }
+    }
+}
+
+/// A new light level for a cell
+pub type LightChange = ((i32, i32), u8);
+
+/// The cells lit by a light at `center` of the `level`, fading to normal light
+/// at `radius` cells away. Higher `falloff` keeps the light strong for longer
+/// before it fades. Cells already lit more strongly by another source are kept.
+pub fn get_light_source(cells: &Cells, center: (i32, i32), radius: f32, falloff: f32, level: u8) -> Vec<LightChange> {
+    let reach = radius.ceil() as i32;
+    let mut changes = vec![];
+    for y in center.1 - reach..=center.1 + reach {
+        for x in center.0 - reach..=center.0 + reach {
+            if !in_bounds(cells, x, y) {
+                continue;
+            }
+            let distance = (((x - center.0).pow(2) + (y - center.1).pow(2)) as f32).sqrt();
+            if distance > radius {
+                continue;
+            }
+            let t = (distance / radius.max(f32::EPSILON)).powf(falloff.max(f32::EPSILON));
+            let new_level = mix_levels(level, LIGHT_NORMAL, t);
+            let old_level = cells.get_cell(x, y).light;
+            if new_level.abs_diff(level) < old_level.abs_diff(level) {
+                changes.push(((x, y), new_level));
+            }
+        }
+    }
+    changes
+}
+
+/// Fades from `start_level` to `end_level` along the line from `start` to
+/// `end`, over a band `width` cells wide centered on the line.
+pub fn get_gradient(cells: &Cells, start: (i32, i32), end: (i32, i32), width: f32, start_level: u8, end_level: u8) -> Vec<LightChange> {
+    let dir = ((end.0 - start.0) as f32, (end.1 - start.1) as f32);
+    let length = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+    if length < 1.0 {
+        return if in_bounds(cells, start.0, start.1) { vec![(start, start_level)] } else { vec![] };
+    }
+    let dir = (dir.0 / length, dir.1 / length);
+    let half_width = width / 2.0;
+    // Every cell the band could reach
+    let pad = half_width.ceil() as i32;
+    let (min_x, max_x) = (start.0.min(end.0) - pad, start.0.max(end.0) + pad);
+    let (min_y, max_y) = (start.1.min(end.1) - pad, start.1.max(end.1) + pad);
+    let mut changes = vec![];
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if !in_bounds(cells, x, y) {
+                continue;
+            }
+            let offset = ((x - start.0) as f32, (y - start.1) as f32);
+            let along = offset.0 * dir.0 + offset.1 * dir.1;
+            let across = offset.0 * dir.1 - offset.1 * dir.0;
+            if along < 0.0 || along > length || across.abs() > half_width {
+                continue;
+            }
+            changes.push(((x, y), mix_levels(start_level, end_level, along / length)));
+        }
+    }
+    changes
+}
+
+/// One pass of averaging every cell's light with its 8 neighbors, so hard
+/// edges between light levels turn into steps
+pub fn get_smoothed(cells: &Cells) -> Vec<LightChange> {
+    let mut changes = vec![];
+    for y in 0..cells.height() as i32 {
+        for x in 0..cells.width() as i32 {
+            let (mut total, mut count) = (0.0, 0.0);
+            for ny in y - 1..=y + 1 {
+                for nx in x - 1..=x + 1 {
+                    if in_bounds(cells, nx, ny) {
+                        total += cells.get_cell(nx, ny).light as f32;
+                        count += 1.0;
+                    }
+                }
+            }
+            let level = (total / count).round() as u8;
+            if level != cells.get_cell(x, y).light {
+                changes.push(((x, y), level));
+            }
+        }
+    }
+    changes
+}
+
+/// Darkens solid foreground tiles that are buried in other solid tiles, one
+/// light level per cell deeper than the surface down to `max_level`. The edge
+/// of the level counts as solid. Cells are never made lighter.
+pub fn get_enclosed_darkening(cells: &Cells, tile_properties: &[TileProperties], max_level: u8) -> Vec<LightChange> {
+    let (width, height) = (cells.width() as i32, cells.height() as i32);
+    let is_solid = |x: i32, y: i32| {
+        cells
+            .get_cell(x, y)
+            .foreground
+            .0
+            .and_then(|id| tile_properties.get(id as usize))
+            .is_some_and(|props| props.solid && matches!(props.collision_data, CollisionType::Box(_)))
+    };
+
+    // How many cells away each cell is from the nearest open one
+    let mut depth = vec![u32::MAX; (width * height) as usize];
+    let mut queue = VecDeque::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !is_solid(x, y) {
+                depth[(y * width + x) as usize] = 0;
+                queue.push_back((x, y));
+            }
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let next = depth[(y * width + x) as usize] + 1;
+        for ny in y - 1..=y + 1 {
+            for nx in x - 1..=x + 1 {
+                if in_bounds(cells, nx, ny) && depth[(ny * width + nx) as usize] > next {
+                    depth[(ny * width + nx) as usize] = next;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+    }
+
+    let mut changes = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            // Depth 1 is the surface that can be seen, so it keeps its light
+            let depth = depth[(y * width + x) as usize];
+            if depth < 2 {
+                continue;
+            }
+            let level = (LIGHT_NORMAL as u32).saturating_add(depth - 1).min(max_level as u32) as u8;
+            if level > cells.get_cell(x, y).light {
+                changes.push(((x, y), level));
+            }
+        }
+    }
+    changes
+}
+
+pub fn apply_light_changes(cells: &mut Cells, changes: &[LightChange]) {
+    for ((x, y), level) in changes.iter().copied() {
+        cells.get_cell_mut(x, y).light = level;
+    }
+}
+
+fn mix_levels(from: u8, to: u8, t: f32) -> u8 {
+    let level = from as f32 + (to as f32 - from as f32) * t.clamp(0.0, 1.0);
+    (level.round() as u8).clamp(LIGHT_WHITE, LIGHT_BLACK)
+}
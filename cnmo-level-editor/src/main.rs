@@ -20,6 +20,22 @@ mod world_panel;
 mod tile_panel;
 mod bgpanel;
 mod game_config_panel;
+mod drop_table;
+mod stats_window;
+mod search_window;
+mod replace_window;
+mod resources_window;
+mod level_select_window;
+mod history;
+mod history_window;
+mod layers;
+mod layers_window;
+mod minimap_window;
+mod brush_library_window;
+mod shapes;
+mod light_tools;
+mod preferences;
+mod preferences_window;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum EditorMode {
@@ -39,6 +55,18 @@ struct LevelEditorApp {
     tile_panel: tile_panel::TilePanel,
     bg_panel: bgpanel::BgPanel,
     game_config_panel: game_config_panel::GameConfigPanel,
+    stats_window: stats_window::StatsWindow,
+    search_window: search_window::SearchWindow,
+    replace_window: replace_window::ReplaceWindow,
+    resources_window: resources_window::ResourcesWindow,
+    level_select_window: level_select_window::LevelSelectWindow,
+    history_window: history_window::HistoryWindow,
+    layers_window: layers_window::LayersWindow,
+    minimap_window: minimap_window::MinimapWindow,
+    brush_library_window: brush_library_window::BrushLibraryWindow,
+    preferences_window: preferences_window::PreferencesWindow,
+    /// Loaded on start, and kept up to date with the panel sizes
+    preferences: preferences::Preferences,
     render_state: eframe::egui_wgpu::RenderState,
     file_receiver: Receiver<notify_debouncer_mini::DebounceEventResult>,
     _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
@@ -48,7 +76,7 @@ struct LevelEditorApp {
 impl LevelEditorApp {
     fn new(cc: &eframe::CreationContext, logs: logger::Logs) -> Self {
         let render_state = cc.wgpu_render_state.as_ref().expect("Need a WGPU rendering context for editor....").clone();
-        let (palette, dimensions, opaques) = common_gfx::GfxCommonResources::insert_resource(&render_state, "gfx.bmp");
+        let (palette, dimensions, opaques, tile_colors) = common_gfx::GfxCommonResources::insert_resource(&render_state, "gfx.bmp");
         instanced_sprites::InstancedSpritesResources::<tile_viewer::TileViewerSpriteInstances>::insert_resource(cc);
         instanced_sprites::InstancedSpritesResources::<tile_viewer::TileViewerDraggingSpriteInstances>::insert_resource(cc);
         instanced_sprites::InstancedSpritesResources::<world_panel::WorldPanelSpriteInstances>::insert_resource(cc);
@@ -57,6 +85,7 @@ impl LevelEditorApp {
         instanced_sprites::InstancedSpritesResources::<tile_panel::TilePanelPreviewSpriteInstances>::insert_resource(cc);
         instanced_sprites::InstancedSpritesResources::<bgpanel::BgPanelSpriteInstances>::insert_resource(cc);
         instanced_sprites::InstancedSpritesResources::<game_config_panel::GfxPreviewResource>::insert_resource(cc);
+        instanced_sprites::InstancedSpritesResources::<game_config_panel::PetPreviewResource>::insert_resource(cc);
         instanced_sprites::InstancedSpritesResources::<level_panel::LevelIconPreviewSpriteInstances>::insert_resource(cc);
 
         let _config = notify::Config::default();
@@ -66,21 +95,34 @@ impl LevelEditorApp {
             .expect("Need hot-realoading file watching for editor to boot");
         debouncer.watcher().watch(&std::path::Path::new("./"), RecursiveMode::Recursive).expect("Can't open working directory for hot-reloading");
 
-        Self {
+        let mut app = Self {
             level_data: level_data::LevelData::from_version(1).expect("Can't create empty level!"),
             mode: EditorMode::Level,
             logs,
-            editor_data: editor_data::EditorData::new(palette, dimensions, opaques),
+            editor_data: editor_data::EditorData::new(palette, dimensions, opaques, tile_colors),
             properties_panel: level_panel::PropertiesPanel::new(),
             world_panel: world_panel::WorldPanel::new(),
             tile_panel: tile_panel::TilePanel::new(),
             bg_panel: bgpanel::BgPanel::new(),
             game_config_panel: game_config_panel::GameConfigPanel::new(),
+            stats_window: stats_window::StatsWindow::new(),
+            search_window: search_window::SearchWindow::new(),
+            replace_window: replace_window::ReplaceWindow::new(),
+            resources_window: resources_window::ResourcesWindow::new(),
+            level_select_window: level_select_window::LevelSelectWindow::new(),
+            history_window: history_window::HistoryWindow::new(),
+            layers_window: layers_window::LayersWindow::new(),
+            minimap_window: minimap_window::MinimapWindow::new(),
+            brush_library_window: brush_library_window::BrushLibraryWindow::new(),
+            preferences_window: preferences_window::PreferencesWindow::new(),
+            preferences: preferences::Preferences::load(),
             render_state,
             file_receiver,
             _debouncer: debouncer,
             watch_timer: std::time::Duration::from_secs(0),
-        }
+        };
+        app.restore_preferences();
+        app
     }
 }
 
@@ -98,10 +140,7 @@ impl eframe::App for LevelEditorApp {
                                 if event.path.ends_with(self.editor_data.level_gfx_file.as_str()) &&
                                     std::path::Path::new(("./".to_string() + self.editor_data.level_gfx_file.as_str()).as_str()).exists() {
                                     log::info!("hot-reloading GRAPHICS");
-                                    let (palette, dimensions, opaques) = common_gfx::GfxCommonResources::insert_resource(&self.render_state, self.editor_data.level_gfx_file.as_str());
-                                    self.editor_data.palette = palette;
-                                    self.editor_data.gfx_size = dimensions;
-                                    self.editor_data.opaques = opaques;
+                                    self.reload_gfx();
                                 }
                             }
                         },
@@ -114,26 +153,61 @@ impl eframe::App for LevelEditorApp {
 
         ctx.request_repaint();
         self.editor_data.update_delta_time();
-        egui::SidePanel::right("editor_options").resizable(true).max_width(500.0).show(ctx, |ui| {
+        let right_panel = egui::SidePanel::right("editor_options").resizable(true).max_width(500.0).default_width(self.preferences.right_panel_width).show(ctx, |ui| {
             let mut force_gfx_reload = false;
             level_panel::show_metadata_panel(&mut self.world_panel, &mut self.editor_data, &mut self.mode, &mut self.level_data, ui, &mut self.bg_panel, &mut self.game_config_panel, &mut force_gfx_reload);
             if force_gfx_reload {
                 log::info!("loading GRAPHICS");
-                let (palette, dimensions, opaques) = common_gfx::GfxCommonResources::insert_resource(&self.render_state, self.editor_data.level_gfx_file.as_str());
-                self.editor_data.palette = palette;
-                self.editor_data.gfx_size = dimensions;
-                self.editor_data.opaques = opaques;
+                self.reload_gfx();
             }
         });
-        egui::SidePanel::left("editor_properties").resizable(true).max_width(500.0).show(ctx, |ui| {
+        self.preferences.right_panel_width = right_panel.response.rect.width();
+        let left_panel = egui::SidePanel::left("editor_properties").resizable(true).max_width(500.0).default_width(self.preferences.left_panel_width).show(ctx, |ui| {
             self.properties_panel.show_propeties_panel(&mut self.editor_data, &mut self.mode, &mut self.level_data, ui, &mut self.world_panel, &mut self.game_config_panel);
         });
-        egui::TopBottomPanel::bottom("info_log").resizable(true).default_height(100.0).min_height(50.0).show(ctx, |ui| {
+        self.preferences.left_panel_width = left_panel.response.rect.width();
+        let log_panel = egui::TopBottomPanel::bottom("info_log").resizable(true).default_height(self.preferences.log_height).min_height(50.0).show(ctx, |ui| {
             logger::show_logs(&self.logs, ui);
         });
+        self.preferences.log_height = log_panel.response.rect.height();
+        let mut recent_level = None;
         egui::TopBottomPanel::top("info_bar").resizable(false).show(ctx, |ui| {
-            ui.label(self.editor_data.info_bar.as_str());
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("Windows", |ui| {
+                    for (name, open) in self.get_windows() {
+                        ui.checkbox(open, name);
+                    }
+                });
+                ui.menu_button("Recent Levels", |ui| {
+                    if self.editor_data.recent_levels.is_empty() {
+                        ui.label("No recent levels");
+                    }
+                    for name in self.editor_data.recent_levels.iter() {
+                        if ui.button(name).clicked() {
+                            recent_level = Some(name.clone());
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.separator();
+                ui.label(self.editor_data.info_bar.as_str());
+            });
         });
+        if let Some(name) = recent_level {
+            self.open_level(name);
+        }
+        self.stats_window.show(ctx, &self.level_data, &self.editor_data);
+        self.search_window.show(ctx, &self.level_data, &mut self.editor_data, &mut self.world_panel);
+        self.replace_window.show(ctx, &mut self.level_data, &mut self.editor_data, &self.world_panel);
+        self.resources_window.show(ctx, &self.level_data, &self.editor_data);
+        self.level_select_window.show(ctx, &mut self.editor_data);
+        self.layers_window.show(ctx, &self.level_data, &mut self.editor_data);
+        self.minimap_window.show(ctx, &self.level_data, &mut self.editor_data, &mut self.world_panel);
+        self.brush_library_window.show(ctx, &mut self.editor_data);
+        self.preferences_window.show(ctx, &mut self.editor_data);
+        if self.history_window.show(ctx, &mut self.level_data, &mut self.editor_data) {
+            self.clear_stale_selections();
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.mode {
                 EditorMode::Background => {
@@ -154,6 +228,151 @@ impl eframe::App for LevelEditorApp {
                 },
             }
         });
+        self.update_history(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_preferences();
+    }
+}
+
+impl LevelEditorApp {
+    fn update_history(&mut self, ctx: &egui::Context) {
+        // Comparing the whole level for edits is slow, so it's only done after
+        // input that could have changed something
+        let had_input = ctx.input().events.iter().any(|event| matches!(event,
+            egui::Event::PointerButton { .. }
+            | egui::Event::Key { .. }
+            | egui::Event::Text(_)
+            | egui::Event::Paste(_)
+            | egui::Event::Cut
+            | egui::Event::CompositionEnd(_)
+        ));
+        if had_input {
+            self.editor_data.history.note_input();
+        }
+        // Text boxes have their own undo, and edits are grouped until the mouse
+        // is let go. Typing in a text box that keeps focus is only recorded once
+        // it loses focus, all in one entry.
+        if ctx.wants_keyboard_input() || ctx.input().pointer.any_down() {
+            return;
+        }
+        let history = &mut self.editor_data.history;
+        let game_config = &mut self.editor_data.game_config_file;
+        history.commit(&self.level_data, game_config);
+        let input = ctx.input();
+        if !(input.modifiers.ctrl || input.modifiers.mac_cmd) || !input.key_pressed(egui::Key::Z) {
+            return;
+        }
+        let label = if input.modifiers.shift {
+            history.redo(&mut self.level_data, game_config).map(|label| format!("Redid {label}"))
+        } else {
+            history.undo(&mut self.level_data, game_config).map(|label| format!("Undid {label}"))
+        };
+        drop(input);
+        match label {
+            Some(label) => log::info!("{label}"),
+            None => log::info!("There is nothing to undo or redo!"),
+        }
+        self.clear_stale_selections();
+    }
+
+    fn reload_gfx(&mut self) {
+        let (palette, dimensions, opaques, tile_colors) = common_gfx::GfxCommonResources::insert_resource(&self.render_state, self.editor_data.level_gfx_file.as_str());
+        self.editor_data.palette = palette;
+        self.editor_data.gfx_size = dimensions;
+        self.editor_data.opaques = opaques;
+        self.editor_data.tile_colors = tile_colors;
+    }
+
+    /// Loads a level from the levels folder, keeping the current one if it can't
+    fn open_level(&mut self, name: String) -> bool {
+        let current_name = std::mem::replace(&mut self.editor_data.level_file_name, name);
+        let mut force_gfx_reload = false;
+        let loaded = level_panel::load_level(&mut self.level_data, &mut self.editor_data, &mut force_gfx_reload);
+        if !loaded {
+            self.editor_data.level_file_name = current_name;
+        }
+        if force_gfx_reload {
+            log::info!("loading GRAPHICS");
+            self.reload_gfx();
+        }
+        loaded
+    }
+
+    /// The windows in the windows menu, by the names the preferences remember them by
+    fn get_windows(&mut self) -> [(&'static str, &mut bool); 10] {
+        [
+            ("Level Statistics", &mut self.stats_window.open),
+            ("Search Spawners", &mut self.search_window.open),
+            ("Replace Spawners", &mut self.replace_window.open),
+            ("Check Resources", &mut self.resources_window.open),
+            ("Level Select Order", &mut self.level_select_window.open),
+            ("History", &mut self.history_window.open),
+            ("Layers", &mut self.layers_window.open),
+            ("Minimap", &mut self.minimap_window.open),
+            ("Brush Library", &mut self.brush_library_window.open),
+            ("Preferences", &mut self.preferences_window.open),
+        ]
+    }
+
+    /// Puts back the settings, windows and level from the last session
+    fn restore_preferences(&mut self) {
+        self.editor_data.keybinds = self.preferences.keybinds.clone();
+        self.editor_data.spawner_grid_size = self.preferences.spawner_grid_size;
+        self.editor_data.recent_levels = self.preferences.recent_levels.clone();
+        self.bg_panel.grid_size = self.preferences.background_grid_size;
+        self.world_panel.show_grid = self.preferences.show_grid;
+        self.world_panel.show_original_screen_size = self.preferences.show_original_screen_size;
+        let open_windows = self.preferences.open_windows.clone();
+        for (name, open) in self.get_windows() {
+            *open = open_windows.iter().any(|open_name| open_name == name);
+        }
+        if let Some(name) = self.preferences.last_level.clone() {
+            if self.open_level(name) {
+                let (x, y) = self.preferences.camera_pos;
+                self.world_panel.camera.pos = cgmath::vec2(x, y);
+                self.world_panel.camera.zoom = self.preferences.camera_zoom;
+            }
+        }
+    }
+
+    fn save_preferences(&mut self) {
+        let open_windows = self
+            .get_windows()
+            .into_iter()
+            .filter(|(_, open)| **open)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        // Unsaved levels can't be opened next time
+        let level_saved = std::path::Path::new("levels/").join(self.editor_data.level_file_name.clone() + ".json").exists();
+        let preferences = &mut self.preferences;
+        preferences.keybinds = self.editor_data.keybinds.clone();
+        preferences.spawner_grid_size = self.editor_data.spawner_grid_size;
+        preferences.recent_levels = self.editor_data.recent_levels.clone();
+        preferences.background_grid_size = self.bg_panel.grid_size;
+        preferences.show_grid = self.world_panel.show_grid;
+        preferences.show_original_screen_size = self.world_panel.show_original_screen_size;
+        preferences.open_windows = open_windows;
+        preferences.last_level = level_saved.then(|| self.editor_data.level_file_name.clone());
+        preferences.camera_pos = (self.world_panel.camera.pos.x, self.world_panel.camera.pos.y);
+        preferences.camera_zoom = self.world_panel.camera.zoom;
+        preferences.save();
+    }
+
+    /// Undoing can remove the things that are selected
+    fn clear_stale_selections(&mut self) {
+        let spawner_count = self.level_data.spawners.len();
+        self.editor_data.selected_spawners.retain(|idx| *idx < spawner_count);
+        self.editor_data.reset_selected_tiles();
+        if self.editor_data.current_background >= self.level_data.background_layers.len() {
+            self.editor_data.current_background = 0;
+        }
+        if let Some(idx) = self.game_config_panel.selected_mode {
+            if idx >= self.editor_data.game_config_file.modes.len() {
+                self.game_config_panel.selected_mode = None;
+            }
+        }
     }
 }
 
@@ -0,0 +1,171 @@
+use cnmo_parse::lparse::level_data::{cnms_types::wobj_type::WobjType, LevelData};
+use eframe::egui;
+
+use crate::editor_data::{EditorData, Tool};
+use crate::layers::{Layer, SpawnerCategory};
+use crate::world_panel::WorldPanel;
+
+/// How often the overview image is rebuilt from the level cells
+const REFRESH_SECS: f32 = 0.5;
+
+pub struct MinimapWindow {
+    pub open: bool,
+    texture: Option<egui::TextureHandle>,
+    texture_age: f32,
+    checkpoint: u8,
+}
+
+impl MinimapWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            texture: None,
+            texture_age: 0.0,
+            checkpoint: 0,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &LevelData, editor_data: &mut EditorData, world_panel: &mut WorldPanel) {
+        if !self.open {
+            self.texture = None;
+            return;
+        }
+        self.texture_age += editor_data.dt.as_secs_f32();
+        if self.texture.is_none() || self.texture_age > REFRESH_SECS {
+            let image = build_overview(level_data, editor_data);
+            match &mut self.texture {
+                Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+                None => self.texture = Some(ctx.load_texture("minimap", image, egui::TextureOptions::NEAREST)),
+            }
+            self.texture_age = 0.0;
+        }
+
+        let mut open = self.open;
+        let mut go_to = None;
+        egui::Window::new("Minimap")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Go to:");
+                if ui.button("Player Spawn").clicked() {
+                    go_to = find_spawner(level_data, |ty| matches!(ty, WobjType::PlayerSpawn { special_entrance: false }))
+                        .or_else(|| find_spawner(level_data, |ty| matches!(ty, WobjType::PlayerSpawn { .. })));
+                    if go_to.is_none() {
+                        log::warn!("The level has no player spawn");
+                    }
+                }
+                ui.add(egui::DragValue::new(&mut self.checkpoint).prefix("Checkpoint "));
+                if ui.button("Go").clicked() {
+                    let checkpoint = self.checkpoint;
+                    go_to = find_spawner(level_data, |ty| matches!(ty, WobjType::Checkpoint { checkpoint_num } if *checkpoint_num == checkpoint));
+                    if go_to.is_none() {
+                        log::warn!("The level has no checkpoint {checkpoint}");
+                    }
+                }
+                if ui.add_enabled(editor_data.get_selected_spawner().is_some(), egui::Button::new("Selected Spawner")).clicked() {
+                    go_to = editor_data.get_selected_spawner();
+                }
+            });
+            ui.separator();
+
+            let Some(texture) = &self.texture else {
+                return;
+            };
+            let map_size = egui::vec2(level_data.cells.width() as f32, level_data.cells.height() as f32);
+            let scale = (ui.available_width() / map_size.x).max(f32::EPSILON);
+            let (rect, response) = ui.allocate_exact_size(map_size * scale, egui::Sense::click_and_drag());
+            let painter = ui.painter_at(rect);
+            painter.image(
+                texture.id(),
+                rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+            // One cell is one pixel of the overview before scaling
+            let to_map = |x: f32, y: f32| rect.min + egui::vec2(x / 32.0, y / 32.0) * scale;
+
+            for (idx, spawner) in level_data.spawners.iter().enumerate() {
+                if !editor_data.layers.is_spawner_visible(&spawner.type_data) {
+                    continue;
+                }
+                let color = if editor_data.selected_spawners.contains(&idx) {
+                    egui::Color32::WHITE
+                } else {
+                    match SpawnerCategory::of(&spawner.type_data) {
+                        SpawnerCategory::Enemies => egui::Color32::RED,
+                        SpawnerCategory::Environmental => egui::Color32::GREEN,
+                        SpawnerCategory::Triggers => egui::Color32::YELLOW,
+                        SpawnerCategory::Collectables => egui::Color32::LIGHT_BLUE,
+                    }
+                };
+                painter.circle_filled(to_map(spawner.pos.0 + 16.0, spawner.pos.1 + 16.0), 1.5, color);
+            }
+
+            let top_left = world_panel.camera.get_top_left_world_space();
+            let bottom_right = world_panel.camera.get_bottom_right_world_space();
+            painter.rect_stroke(
+                egui::Rect::from_min_max(to_map(top_left.x, top_left.y), to_map(bottom_right.x, bottom_right.y)),
+                0.0,
+                egui::Stroke::new(1.5, egui::Color32::WHITE),
+            );
+
+            // Clicking or dragging moves the view there
+            if response.clicked() || response.dragged_by(egui::PointerButton::Primary) {
+                if let Some(pos) = response.interact_pointer_pos() {
+                    let pos = (pos - rect.min) / scale * 32.0;
+                    world_panel.camera.pos = cgmath::vec2(pos.x, pos.y);
+                }
+            }
+        });
+        self.open = open;
+
+        if let Some(idx) = go_to {
+            let spawner = &level_data.spawners[idx];
+            world_panel.camera.pos = cgmath::vec2(spawner.pos.0, spawner.pos.1);
+            editor_data.select_spawner(idx);
+            editor_data.tool = Tool::Spawners;
+        }
+    }
+}
+
+fn find_spawner(level_data: &LevelData, is_target: impl Fn(&WobjType) -> bool) -> Option<usize> {
+    level_data.spawners.iter().position(|spawner| is_target(&spawner.type_data))
+}
+
+/// One pixel per cell, colored by the foreground tile or else the background tile
+fn build_overview(level_data: &LevelData, editor_data: &EditorData) -> egui::ColorImage {
+    let (width, height) = (level_data.cells.width(), level_data.cells.height());
+    let show_foreground = editor_data.layers.is_visible(Layer::Foreground);
+    let show_background = editor_data.layers.is_visible(Layer::Background);
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let cell = level_data.cells.get_cell(x, y);
+            let tile = cell
+                .foreground
+                .0
+                .filter(|_| show_foreground)
+                .or(cell.background.0.filter(|_| show_background));
+            let color = tile
+                .and_then(|id| level_data.tile_properties.get(id as usize))
+                .and_then(|props| props.frames.first())
+                .and_then(|frame| editor_data.tile_colors.get(frame.0 as usize)?.get(frame.1 as usize))
+                .map(|color| {
+                    // Background tiles are darker like in the world view
+                    if cell.foreground.0.is_some() && show_foreground {
+                        egui::Color32::from_rgb(color[0], color[1], color[2])
+                    } else {
+                        egui::Color32::from_rgb(color[0] / 2, color[1] / 2, color[2] / 2)
+                    }
+                })
+                .unwrap_or(egui::Color32::from_gray(24));
+            pixels.push(color);
+        }
+    }
+    egui::ColorImage {
+        size: [width, height],
+        pixels,
+    }
+}
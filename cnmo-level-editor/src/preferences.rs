@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+const MAX_RECENT_LEVELS: usize = 10;
+
+/// Letters the editor uses itself without ctrl, so they can't switch tools
+const RESERVED_LETTERS: [(char, &str); 2] = [('G', "toggles the grid"), ('V', "pastes spawners with shift")];
+
+/// The letters that switch tools. Letters are stored instead of egui keys so
+/// the preferences file stays readable and can be edited by hand.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybinds {
+    pub brush: char,
+    pub light: char,
+    pub eraser: char,
+    pub fill: char,
+    pub tile_picker: char,
+    pub spawners: char,
+    pub rectangle: char,
+    pub line: char,
+    pub ellipse: char,
+    pub replace: char,
+    /// Switches between placing on the foreground and background
+    pub swap_layer: char,
+}
+
+impl Default for Keybinds {
+    fn default() -> Self {
+        Self {
+            brush: 'B',
+            light: 'T',
+            eraser: 'E',
+            fill: 'F',
+            tile_picker: 'R',
+            spawners: 'S',
+            rectangle: 'U',
+            line: 'L',
+            ellipse: 'O',
+            replace: 'H',
+            swap_layer: 'Q',
+        }
+    }
+}
+
+impl Keybinds {
+    /// Every keybind with the name it's shown with, for editing them
+    pub fn get_all_mut(&mut self) -> [(&'static str, &mut char); 11] {
+        [
+            ("Brush", &mut self.brush),
+            ("Light", &mut self.light),
+            ("Eraser", &mut self.eraser),
+            ("Fill", &mut self.fill),
+            ("Tile Picker", &mut self.tile_picker),
+            ("Spawners", &mut self.spawners),
+            ("Rectangle", &mut self.rectangle),
+            ("Line", &mut self.line),
+            ("Ellipse", &mut self.ellipse),
+            ("Replace Everywhere", &mut self.replace),
+            ("Swap Foreground/Background", &mut self.swap_layer),
+        ]
+    }
+}
+
+/// If the letter's key was pressed this frame without ctrl, cmd or alt, which
+/// are kept for shortcuts like saving or deleting spawners
+pub fn is_letter_pressed(ctx: &egui::Context, letter: char) -> bool {
+    let input = ctx.input();
+    !(input.modifiers.ctrl || input.modifiers.mac_cmd || input.modifiers.alt)
+        && get_letter_key(letter).is_some_and(|key| input.key_pressed(key))
+}
+
+/// What the letter already does in the editor if it can't be used as a tool key
+pub fn get_reserved_use(letter: char) -> Option<&'static str> {
+    RESERVED_LETTERS
+        .iter()
+        .find(|(reserved, _)| *reserved == letter.to_ascii_uppercase())
+        .map(|(_, reason)| *reason)
+}
+
+fn get_letter_key(letter: char) -> Option<egui::Key> {
+    use egui::Key;
+    Some(match letter.to_ascii_uppercase() {
+        'A' => Key::A, 'B' => Key::B, 'C' => Key::C, 'D' => Key::D, 'E' => Key::E,
+        'F' => Key::F, 'G' => Key::G, 'H' => Key::H, 'I' => Key::I, 'J' => Key::J,
+        'K' => Key::K, 'L' => Key::L, 'M' => Key::M, 'N' => Key::N, 'O' => Key::O,
+        'P' => Key::P, 'Q' => Key::Q, 'R' => Key::R, 'S' => Key::S, 'T' => Key::T,
+        'U' => Key::U, 'V' => Key::V, 'W' => Key::W, 'X' => Key::X, 'Y' => Key::Y,
+        'Z' => Key::Z,
+        _ => return None,
+    })
+}
+
+/// Editor settings kept between launches. Missing fields use the defaults so
+/// files from older versions still load.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub keybinds: Keybinds,
+    pub spawner_grid_size: f32,
+    pub background_grid_size: i32,
+    pub show_grid: bool,
+    pub show_original_screen_size: bool,
+    pub left_panel_width: f32,
+    pub right_panel_width: f32,
+    pub log_height: f32,
+    /// Names of the windows from the windows menu that were open
+    pub open_windows: Vec<String>,
+    /// Level file names, most recent first
+    pub recent_levels: Vec<String>,
+    pub last_level: Option<String>,
+    pub camera_pos: (f32, f32),
+    pub camera_zoom: f32,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            keybinds: Keybinds::default(),
+            spawner_grid_size: 8.0,
+            background_grid_size: 8,
+            show_grid: true,
+            show_original_screen_size: false,
+            left_panel_width: 250.0,
+            right_panel_width: 250.0,
+            log_height: 100.0,
+            open_windows: vec![],
+            recent_levels: vec![],
+            last_level: None,
+            camera_pos: (0.0, 0.0),
+            camera_zoom: 1.0,
+        }
+    }
+}
+
+impl Preferences {
+    pub fn load() -> Self {
+        let Some(path) = get_preferences_path() else {
+            return Self::default();
+        };
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(_) => return Self::default(),
+        };
+        match serde_json::from_reader(file) {
+            Ok(preferences) => preferences,
+            Err(err) => {
+                log::error!("Can't load the preferences at {} due to {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = get_preferences_path() else {
+            log::warn!("Can't find a config directory to save the preferences in");
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            if let Err(err) = std::fs::create_dir_all(dir) {
+                log::error!("Can't create {} due to {err}", dir.display());
+                return;
+            }
+        }
+        match std::fs::File::create(&path) {
+            Ok(file) => {
+                if let Err(err) = serde_json::to_writer_pretty(file, self) {
+                    log::error!("Can't save the preferences due to {err}");
+                }
+            }
+            Err(err) => log::error!("Can't open {} for writing due to {err}", path.display()),
+        }
+    }
+}
+
+/// Moves the level to the front of the recent levels
+pub fn add_recent_level(recent_levels: &mut Vec<String>, level_name: &str) {
+    recent_levels.retain(|name| name != level_name);
+    recent_levels.insert(0, level_name.to_string());
+    recent_levels.truncate(MAX_RECENT_LEVELS);
+}
+
+/// The user config directory of the platform, like `~/.config` on Linux
+fn get_preferences_path() -> Option<PathBuf> {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+    let config_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(".config")))
+    };
+    Some(config_dir?.join("cnmo-level-editor").join("preferences.json"))
+}
@@ -0,0 +1,72 @@
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+use crate::preferences::{self, Keybinds};
+
+pub struct PreferencesWindow {
+    pub open: bool,
+}
+
+impl PreferencesWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, editor_data: &mut EditorData) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Preferences")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            ui.heading("Tool Keys");
+            let letters = editor_data.keybinds.get_all_mut().map(|(name, letter)| (name, letter.to_ascii_uppercase()));
+            egui::Grid::new("keybinds_grid").num_columns(3).striped(true).show(ui, |ui| {
+                for (name, letter) in editor_data.keybinds.get_all_mut() {
+                    ui.label(name);
+                    egui::ComboBox::new(("keybind_combo_box", name), "")
+                        .selected_text(letter.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in 'A'..='Z' {
+                                let reserved = preferences::get_reserved_use(option);
+                                let response = ui.add_enabled(reserved.is_none(), egui::SelectableLabel::new(*letter == option, option.to_string()));
+                                if response.clicked() {
+                                    *letter = option;
+                                }
+                                if let Some(reason) = reserved {
+                                    response.on_disabled_hover_text(format!("{option} {reason}"));
+                                }
+                            }
+                        });
+                    let others = letters
+                        .iter()
+                        .filter(|(other, other_letter)| *other != name && *other_letter == letter.to_ascii_uppercase())
+                        .map(|(other, _)| *other)
+                        .collect::<Vec<_>>();
+                    if let Some(reason) = preferences::get_reserved_use(*letter) {
+                        ui.colored_label(egui::Color32::LIGHT_RED, format!("Doesn't work, {letter} {reason}"));
+                    } else if !others.is_empty() {
+                        ui.colored_label(egui::Color32::YELLOW, format!("Also used by {}", others.join(", ")));
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+            if ui.button("Reset Keys").clicked() {
+                editor_data.keybinds = Keybinds::default();
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(format!("{} recent levels", editor_data.recent_levels.len()));
+                if ui.button("Clear").clicked() {
+                    editor_data.recent_levels.clear();
+                }
+            });
+            ui.label("Preferences are saved when the editor closes.");
+        });
+        self.open = open;
+    }
+}
@@ -0,0 +1,226 @@
+use cnmo_parse::lparse::level_data::cnms_types::wobj_type::WobjType;
+use cnmo_parse::lparse::level_data::query::SpawnerQuery;
+use cnmo_parse::lparse::level_data::replace::{FieldAction, FieldRule, SpawnerReplace};
+use cnmo_parse::lparse::level_data::LevelData;
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+use crate::level_panel::{get_wobj_type_name, WobjIter};
+use crate::search_window::{get_view_rect, parse_field_value};
+use crate::world_panel::WorldPanel;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ReplaceScope {
+    Selected,
+    Region,
+    Group,
+    Level,
+}
+
+pub struct ReplaceWindow {
+    pub open: bool,
+    scope: ReplaceScope,
+    query: SpawnerQuery,
+    find_type: Option<WobjType>,
+    replace: SpawnerReplace,
+    rule_values: Vec<String>,
+}
+
+impl ReplaceWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            scope: ReplaceScope::Level,
+            query: SpawnerQuery::default(),
+            find_type: None,
+            replace: SpawnerReplace {
+                keep_matching_fields: true,
+                ..Default::default()
+            },
+            rule_values: vec![],
+        }
+    }
+
+    fn get_targets(&self, level_data: &LevelData, editor_data: &EditorData) -> Vec<usize> {
+        let mut query = self.query.clone();
+        query.type_name = self.find_type.as_ref().map(|ty| ty.get_type_name().to_string());
+        if self.scope != ReplaceScope::Region {
+            query.region = None;
+        }
+        if self.scope != ReplaceScope::Group {
+            query.group = None;
+        }
+        let mut targets = level_data.query_spawners(&query);
+        if self.scope == ReplaceScope::Selected {
+            targets.retain(|idx| editor_data.selected_spawners.contains(idx));
+        }
+        targets
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &mut LevelData, editor_data: &mut EditorData, world_panel: &WorldPanel) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Replace Spawners")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            ui.heading("Find");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.scope, ReplaceScope::Selected, "Selected");
+                ui.selectable_value(&mut self.scope, ReplaceScope::Region, "Region");
+                ui.selectable_value(&mut self.scope, ReplaceScope::Group, "Group");
+                ui.selectable_value(&mut self.scope, ReplaceScope::Level, "Whole Level");
+            });
+            match self.scope {
+                ReplaceScope::Region => {
+                    let region = self.query.region.get_or_insert_with(|| get_view_rect(world_panel));
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut region.x).prefix("x: "));
+                        ui.add(egui::DragValue::new(&mut region.y).prefix("y: "));
+                        ui.add(egui::DragValue::new(&mut region.w).prefix("w: "));
+                        ui.add(egui::DragValue::new(&mut region.h).prefix("h: "));
+                        if ui.button("View").on_hover_text("Use the area currently on screen").clicked() {
+                            *region = get_view_rect(world_panel);
+                        }
+                    });
+                },
+                ReplaceScope::Group => {
+                    let group = self.query.group.get_or_insert(0);
+                    ui.horizontal(|ui| {
+                        ui.label("Group ID: ");
+                        ui.add(egui::DragValue::new(group).clamp_range(0..=31));
+                    });
+                },
+                _ => {},
+            }
+            show_type_combobox(ui, "replace_find_type_combobox", "Type: ", &mut self.find_type);
+            ui.separator();
+
+            ui.heading("Replace With");
+            show_type_combobox(ui, "replace_new_type_combobox", "Type: ", &mut self.replace.new_type);
+            if self.replace.new_type.is_some() {
+                ui.checkbox(&mut self.replace.keep_matching_fields, "Keep fields with the same name");
+            }
+            let fields = self.replace.new_type.as_ref()
+                .or(self.find_type.as_ref())
+                .map(|ty| ty.get_fields())
+                .unwrap_or_default();
+            let old_fields = self.find_type.as_ref().map(|ty| ty.get_fields()).unwrap_or_default();
+            let mut remove_idx = None;
+            egui::Grid::new("replace_rules_grid").num_columns(4).show(ui, |ui| {
+                for (idx, (rule, value)) in self.replace.rules.iter_mut().zip(self.rule_values.iter_mut()).enumerate() {
+                    egui::ComboBox::new(("replace_rule_field_combobox", idx), "")
+                        .selected_text(rule.field.as_str())
+                        .show_ui(ui, |ui| {
+                        for (name, _) in fields.iter() {
+                            ui.selectable_value(&mut rule.field, name.to_string(), *name);
+                        }
+                    });
+                    let action_name = match rule.action {
+                        FieldAction::Set(_) => "Set to",
+                        FieldAction::CopyFrom(_) => "Copy from",
+                        FieldAction::Add(_) => "Add",
+                        FieldAction::Multiply(_) => "Multiply by",
+                    };
+                    egui::ComboBox::new(("replace_rule_action_combobox", idx), "")
+                        .selected_text(action_name)
+                        .show_ui(ui, |ui| {
+                        if ui.selectable_label(matches!(rule.action, FieldAction::Set(_)), "Set to").clicked() {
+                            rule.action = FieldAction::Set(parse_field_value(value));
+                        }
+                        if ui.selectable_label(matches!(rule.action, FieldAction::CopyFrom(_)), "Copy from").clicked() {
+                            rule.action = FieldAction::CopyFrom(old_fields.first().map(|(name, _)| name.to_string()).unwrap_or_default());
+                        }
+                        if ui.selectable_label(matches!(rule.action, FieldAction::Add(_)), "Add").clicked() {
+                            rule.action = FieldAction::Add(value.parse().unwrap_or(0.0));
+                        }
+                        if ui.selectable_label(matches!(rule.action, FieldAction::Multiply(_)), "Multiply by").clicked() {
+                            rule.action = FieldAction::Multiply(value.parse().unwrap_or(1.0));
+                        }
+                    });
+                    if let FieldAction::CopyFrom(from) = &mut rule.action {
+                        egui::ComboBox::new(("replace_rule_from_combobox", idx), "")
+                            .selected_text(from.as_str())
+                            .show_ui(ui, |ui| {
+                            for (name, _) in old_fields.iter() {
+                                ui.selectable_value(from, name.to_string(), *name);
+                            }
+                        });
+                    } else if ui.text_edit_singleline(value).changed() {
+                        match &mut rule.action {
+                            FieldAction::Set(v) => *v = parse_field_value(value),
+                            FieldAction::Add(v) | FieldAction::Multiply(v) => *v = value.parse().unwrap_or(*v),
+                            _ => {},
+                        }
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove_idx = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(idx) = remove_idx {
+                self.replace.rules.remove(idx);
+                self.rule_values.remove(idx);
+            }
+            if ui.button("Add Field Rule").clicked() {
+                self.replace.rules.push(FieldRule {
+                    field: fields.first().map(|(name, _)| name.to_string()).unwrap_or_default(),
+                    action: FieldAction::Set(parse_field_value("0")),
+                });
+                self.rule_values.push("0".to_string());
+            }
+            ui.separator();
+
+            let targets = self.get_targets(level_data, editor_data);
+            ui.heading(format!("Preview ({} spawners)", targets.len()));
+            egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                for idx in targets.iter() {
+                    let old = &level_data.spawners[*idx].type_data;
+                    let (new, failed) = self.replace.replace_type_checked(old);
+                    let old_fields = old.get_fields();
+                    let changes = new
+                        .get_fields()
+                        .into_iter()
+                        .filter(|field| !old_fields.contains(field))
+                        .map(|(name, value)| format!("{name}: {value}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(format!("#{idx} {} -> {} {}", get_wobj_type_name(old), get_wobj_type_name(&new), changes));
+                        if !failed.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, format!("(can't set {})", failed.join(", ")));
+                        }
+                    });
+                }
+            });
+            if ui.button("Replace").clicked() && !targets.is_empty() {
+                editor_data.history.label("Replace Spawners");
+                level_data.replace_spawners(&targets, &self.replace);
+                log::info!("Replaced {} spawners", targets.len());
+            }
+        });
+        self.open = open;
+    }
+}
+
+fn show_type_combobox(ui: &mut egui::Ui, id: &str, label: &str, wobj_type: &mut Option<WobjType>) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        egui::ComboBox::new(id, "")
+            .selected_text(wobj_type.as_ref().map_or("Any", get_wobj_type_name))
+            .show_ui(ui, |ui| {
+            if ui.selectable_label(wobj_type.is_none(), "Any").clicked() {
+                *wobj_type = None;
+            }
+            for ty in WobjIter::new() {
+                let selected = wobj_type.as_ref().is_some_and(|current| current.get_type_name() == ty.get_type_name());
+                if ui.selectable_label(selected, get_wobj_type_name(&ty)).clicked() {
+                    *wobj_type = Some(ty);
+                }
+            }
+        });
+    });
+}
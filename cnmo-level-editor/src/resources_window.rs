@@ -0,0 +1,109 @@
+use cnmo_parse::cnma::references::ReferenceProblem;
+use cnmo_parse::cnma::resources::ResourceProblem;
+use cnmo_parse::lparse::level_data::LevelData;
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+
+pub struct ResourcesWindow {
+    pub open: bool,
+    problems: Option<Vec<ResourceProblem>>,
+    level_problems: Option<Vec<ReferenceProblem>>,
+    show_missing: bool,
+    show_duplicates: bool,
+    show_gaps: bool,
+    show_unreferenced: bool,
+}
+
+impl ResourcesWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            problems: None,
+            level_problems: None,
+            show_missing: true,
+            show_duplicates: true,
+            show_gaps: false,
+            show_unreferenced: true,
+        }
+    }
+
+    fn is_shown(&self, problem: &ResourceProblem) -> bool {
+        match problem {
+            ResourceProblem::MissingFile { .. } | ResourceProblem::CaseMismatch { .. } => self.show_missing,
+            ResourceProblem::DuplicateId { .. } => self.show_duplicates,
+            ResourceProblem::IdGap { .. } => self.show_gaps,
+            ResourceProblem::UnreferencedFile { .. } => self.show_unreferenced,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &LevelData, editor_data: &EditorData) {
+        if !self.open {
+            return;
+        }
+        if self.problems.is_none() {
+            self.problems = Some(editor_data.game_config_file.check_resources("."));
+        }
+        let mut open = self.open;
+        egui::Window::new("Check Resources")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Recheck").on_hover_text("Check the music and sound ids of the game config again").clicked() {
+                    self.problems = Some(editor_data.game_config_file.check_resources("."));
+                }
+                let problems = self.problems.as_deref().unwrap_or_default();
+                let errors = problems.iter().filter(|problem| problem.is_error()).count();
+                ui.label(format!("{} errors, {} warnings", errors, problems.len() - errors));
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_missing, "Missing Files");
+                ui.checkbox(&mut self.show_duplicates, "Duplicate Ids");
+                ui.checkbox(&mut self.show_gaps, "Id Gaps");
+                ui.checkbox(&mut self.show_unreferenced, "Unused Files");
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                for problem in self.problems.iter().flatten().filter(|problem| self.is_shown(problem)) {
+                    let color = if problem.is_error() {
+                        egui::Color32::LIGHT_RED
+                    } else {
+                        egui::Color32::YELLOW
+                    };
+                    ui.colored_label(color, problem.to_string());
+                }
+            });
+            ui.separator();
+
+            ui.heading("Level References");
+            let current = editor_data
+                .game_config_file
+                .check_level_references(&editor_data.level_file_name, level_data);
+            for problem in current.iter() {
+                ui.colored_label(egui::Color32::LIGHT_RED, problem.to_string());
+            }
+            if current.is_empty() {
+                ui.label("The current level only uses music and sound ids that exist");
+            }
+            if ui.button("Check All Levels").on_hover_text("Check every level on the level select menu and the pets").clicked() {
+                self.level_problems = Some(editor_data.game_config_file.check_references("levels"));
+            }
+            if let Some(problems) = &self.level_problems {
+                egui::ScrollArea::vertical().id_source("level_references_scroll").max_height(200.0).show(ui, |ui| {
+                    for problem in problems.iter() {
+                        ui.colored_label(egui::Color32::LIGHT_RED, problem.to_string());
+                    }
+                    if problems.is_empty() {
+                        ui.label("No problems found");
+                    }
+                });
+            }
+        });
+        self.open = open;
+        if !self.open {
+            self.problems = None;
+            self.level_problems = None;
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use cnmo_parse::lparse::level_data::cnms_types::wobj_type::{FieldValue, WobjType};
+use cnmo_parse::lparse::level_data::cnms_types::SpawnerMode;
+use cnmo_parse::lparse::level_data::query::{Comparison, FieldPredicate, SpawnerQuery};
+use cnmo_parse::lparse::level_data::LevelData;
+use eframe::egui;
+
+use crate::editor_data::{EditorData, Tool};
+use crate::level_panel::{get_spawner_mode_name, get_wobj_type_name, WobjIter};
+use crate::world_panel::WorldPanel;
+
+pub struct SearchWindow {
+    pub open: bool,
+    pub query: SpawnerQuery,
+    type_filter: Option<WobjType>,
+    predicate_values: Vec<String>,
+    pub results: Vec<usize>,
+}
+
+impl SearchWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: SpawnerQuery::default(),
+            type_filter: None,
+            predicate_values: vec![],
+            results: vec![],
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &LevelData, editor_data: &mut EditorData, world_panel: &mut WorldPanel) {
+        if !self.open {
+            world_panel.highlighted_spawners.clear();
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Search Spawners")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            egui::Grid::new("search_query_grid").num_columns(2).striped(true).show(ui, |ui| {
+                ui.label("Type: ");
+                egui::ComboBox::new("search_type_combobox", "")
+                    .selected_text(self.type_filter.as_ref().map_or("Any", get_wobj_type_name))
+                    .show_ui(ui, |ui| {
+                    if ui.selectable_label(self.type_filter.is_none(), "Any").clicked() {
+                        self.type_filter = None;
+                    }
+                    for wobj_type in WobjIter::new() {
+                        let selected = self.type_filter.as_ref().is_some_and(|ty| ty.get_type_name() == wobj_type.get_type_name());
+                        if ui.selectable_label(selected, get_wobj_type_name(&wobj_type)).clicked() {
+                            self.type_filter = Some(wobj_type);
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Group: ");
+                ui.horizontal(|ui| {
+                    let mut grouped = self.query.group.is_some();
+                    if ui.checkbox(&mut grouped, "").changed() {
+                        self.query.group = if grouped { Some(0) } else { None };
+                    }
+                    if let Some(group) = &mut self.query.group {
+                        ui.add(egui::DragValue::new(group).clamp_range(0..=31));
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Spawning Mode: ");
+                egui::ComboBox::new("search_mode_combobox", "")
+                    .selected_text(self.query.mode.as_ref().map_or("Any", get_spawner_mode_name))
+                    .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.query.mode, None, "Any");
+                    for mode in [
+                        SpawnerMode::MultiAndSingleplayer,
+                        SpawnerMode::SingleplayerOnly,
+                        SpawnerMode::MultiplayerOnly,
+                        SpawnerMode::PlayerCountBased,
+                        SpawnerMode::NoSpawn,
+                    ] {
+                        ui.selectable_value(&mut self.query.mode, Some(mode), get_spawner_mode_name(&mode));
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Region: ");
+                ui.horizontal(|ui| {
+                    let mut in_region = self.query.region.is_some();
+                    if ui.checkbox(&mut in_region, "").changed() {
+                        self.query.region = if in_region { Some(get_view_rect(world_panel)) } else { None };
+                    }
+                    if let Some(region) = &mut self.query.region {
+                        ui.add(egui::DragValue::new(&mut region.x).prefix("x: "));
+                        ui.add(egui::DragValue::new(&mut region.y).prefix("y: "));
+                        ui.add(egui::DragValue::new(&mut region.w).prefix("w: "));
+                        ui.add(egui::DragValue::new(&mut region.h).prefix("h: "));
+                        if ui.button("View").on_hover_text("Use the area currently on screen").clicked() {
+                            *region = get_view_rect(world_panel);
+                        }
+                    }
+                });
+                ui.end_row();
+            });
+
+            ui.label("Fields");
+            let fields = self.type_filter.as_ref().map(|ty| ty.get_fields()).unwrap_or_default();
+            let mut remove_idx = None;
+            egui::Grid::new("search_predicates_grid").num_columns(4).show(ui, |ui| {
+                for (idx, (predicate, value)) in self.query.predicates.iter_mut().zip(self.predicate_values.iter_mut()).enumerate() {
+                    if fields.is_empty() {
+                        ui.text_edit_singleline(&mut predicate.field);
+                    } else {
+                        egui::ComboBox::new(("search_field_combobox", idx), "")
+                            .selected_text(predicate.field.as_str())
+                            .show_ui(ui, |ui| {
+                            for (name, _) in fields.iter() {
+                                ui.selectable_value(&mut predicate.field, name.to_string(), *name);
+                            }
+                        });
+                    }
+                    show_comparison_combobox(&mut predicate.comparison, ui, idx);
+                    if ui.text_edit_singleline(value).changed() {
+                        predicate.value = parse_field_value(value);
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove_idx = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(idx) = remove_idx {
+                self.query.predicates.remove(idx);
+                self.predicate_values.remove(idx);
+            }
+            if ui.button("Add Field Check").clicked() {
+                self.query.predicates.push(FieldPredicate {
+                    field: fields.first().map(|(name, _)| name.to_string()).unwrap_or_default(),
+                    comparison: Comparison::Equal,
+                    value: FieldValue::Int(0),
+                });
+                self.predicate_values.push("0".to_string());
+            }
+            ui.separator();
+
+            self.query.type_name = self.type_filter.as_ref().map(|ty| ty.get_type_name().to_string());
+            self.results = level_data.query_spawners(&self.query);
+            ui.label(format!("{} spawners found", self.results.len()));
+            egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                for idx in self.results.iter() {
+                    let spawner = &level_data.spawners[*idx];
+                    let text = format!("#{idx} {} at ({}, {})", get_wobj_type_name(&spawner.type_data), spawner.pos.0, spawner.pos.1);
+                    if ui.selectable_label(editor_data.selected_spawners.contains(idx), text).clicked() {
+                        editor_data.select_spawner(*idx);
+                        editor_data.tool = Tool::Spawners;
+                        world_panel.camera.pos = cgmath::vec2(spawner.pos.0, spawner.pos.1);
+                    }
+                }
+            });
+        });
+        self.open = open;
+        world_panel.highlighted_spawners = self.results.clone();
+    }
+}
+
+pub fn get_view_rect(world_panel: &WorldPanel) -> cnmo_parse::Rect {
+    let top_left = world_panel.camera.get_top_left_world_space();
+    let size = world_panel.camera.get_proj_size_world_space();
+    cnmo_parse::Rect {
+        x: top_left.x as i32,
+        y: top_left.y as i32,
+        w: size.x as i32,
+        h: size.y as i32,
+    }
+}
+
+fn show_comparison_combobox(comparison: &mut Comparison, ui: &mut egui::Ui, idx: usize) {
+    let name = |comparison: &Comparison| match comparison {
+        Comparison::Equal => "=",
+        Comparison::NotEqual => "!=",
+        Comparison::Less => "<",
+        Comparison::LessOrEqual => "<=",
+        Comparison::Greater => ">",
+        Comparison::GreaterOrEqual => ">=",
+    };
+    egui::ComboBox::new(("search_comparison_combobox", idx), "")
+        .width(40.0)
+        .selected_text(name(comparison))
+        .show_ui(ui, |ui| {
+        for option in [
+            Comparison::Equal,
+            Comparison::NotEqual,
+            Comparison::Less,
+            Comparison::LessOrEqual,
+            Comparison::Greater,
+            Comparison::GreaterOrEqual,
+        ] {
+            ui.selectable_value(comparison, option, name(&option));
+        }
+    });
+}
+
+pub fn parse_field_value(s: &str) -> FieldValue {
+    let s = s.trim();
+    if let Ok(v) = s.parse::<bool>() {
+        FieldValue::Bool(v)
+    } else if let Ok(v) = s.parse::<i64>() {
+        FieldValue::Int(v)
+    } else if let Ok(v) = s.parse::<f32>() {
+        FieldValue::Float(v)
+    } else if s == "none" {
+        FieldValue::None
+    } else {
+        FieldValue::Text(s.to_string())
+    }
+}
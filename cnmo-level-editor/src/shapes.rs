@@ -0,0 +1,83 @@
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rectangle,
+    Line,
+    Ellipse,
+}
+
+impl Shape {
+    /// The cells covered by the shape dragged from `start` to `end`, both
+    /// inclusive. Lines are always one cell thick and ignore `filled`.
+    pub fn get_cells(&self, start: (i32, i32), end: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+        match self {
+            Shape::Rectangle => rectangle_cells(start, end, filled),
+            Shape::Line => line_cells(start, end),
+            Shape::Ellipse => ellipse_cells(start, end, filled),
+        }
+    }
+}
+
+fn rectangle_cells(start: (i32, i32), end: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+    let (min_x, max_x) = (start.0.min(end.0), start.0.max(end.0));
+    let (min_y, max_y) = (start.1.min(end.1), start.1.max(end.1));
+    let mut cells = vec![];
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if filled || x == min_x || x == max_x || y == min_y || y == max_y {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// Bresenham's line, so diagonal steps don't leave gaps or doubled corners
+fn line_cells(start: (i32, i32), end: (i32, i32)) -> Vec<(i32, i32)> {
+    let (dx, dy) = ((end.0 - start.0).abs(), -(end.1 - start.1).abs());
+    let (sx, sy) = ((end.0 - start.0).signum(), (end.1 - start.1).signum());
+    let (mut x, mut y) = start;
+    let mut error = dx + dy;
+    let mut cells = vec![];
+    loop {
+        cells.push((x, y));
+        if (x, y) == end {
+            return cells;
+        }
+        let error2 = error * 2;
+        if error2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if error2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+/// The ellipse inscribed in the dragged rectangle. The outline is every cell
+/// inside the ellipse that touches a cell outside of it.
+fn ellipse_cells(start: (i32, i32), end: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+    let (min_x, max_x) = (start.0.min(end.0), start.0.max(end.0));
+    let (min_y, max_y) = (start.1.min(end.1), start.1.max(end.1));
+    let center = ((min_x + max_x) as f32 / 2.0, (min_y + max_y) as f32 / 2.0);
+    let radius = ((max_x - min_x) as f32 / 2.0 + 0.5, (max_y - min_y) as f32 / 2.0 + 0.5);
+    let inside = |x: i32, y: i32| {
+        let nx = (x as f32 - center.0) / radius.0;
+        let ny = (y as f32 - center.1) / radius.1;
+        nx * nx + ny * ny <= 1.0
+    };
+    let mut cells = vec![];
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if !inside(x, y) {
+                continue;
+            }
+            let edge = !inside(x - 1, y) || !inside(x + 1, y) || !inside(x, y - 1) || !inside(x, y + 1);
+            if filled || edge {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
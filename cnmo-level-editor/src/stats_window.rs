@@ -0,0 +1,101 @@
+use cnmo_parse::lparse::level_data::LevelData;
+use eframe::egui;
+
+use crate::editor_data::EditorData;
+
+pub struct StatsWindow {
+    pub open: bool,
+}
+
+impl StatsWindow {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, level_data: &LevelData, editor_data: &EditorData) {
+        if !self.open {
+            return;
+        }
+        let stats = level_data.get_stats();
+        egui::Window::new("Level Statistics")
+            .open(&mut self.open)
+            .resizable(true)
+            .show(ctx, |ui| {
+            egui::Grid::new("level_stats_grid").num_columns(2).striped(true).show(ui, |ui| {
+                ui.label("Spawners: ");
+                ui.label(level_data.spawners.len().to_string());
+                ui.end_row();
+                ui.label("Known health: ").on_hover_text("Health of breakable walls and lava dragons");
+                ui.label(stats.known_health.to_string());
+                ui.end_row();
+                ui.label("Total money: ");
+                ui.label(stats.total_money.to_string());
+                ui.end_row();
+                ui.label("Checkpoints: ");
+                ui.label(stats.checkpoints.to_string());
+                ui.end_row();
+                ui.label("Player spawns: ");
+                ui.label(stats.player_spawns.to_string());
+                ui.end_row();
+                ui.label("Max objects alive: ");
+                ui.label(stats.max_concurrent_objects.to_string());
+                ui.end_row();
+                ui.label("Respawns per second: ");
+                ui.label(format!("{:.2}", stats.respawns_per_second));
+                ui.end_row();
+            });
+            ui.separator();
+
+            ui.heading("Density");
+            let max_density = stats.density.iter().cloned().max().unwrap_or(0).max(1);
+            let cell_size = (ui.available_width() / stats.density_size.0 as f32).clamp(2.0, 16.0);
+            let (rect, response) = ui.allocate_exact_size(
+                egui::vec2(cell_size * stats.density_size.0 as f32, cell_size * stats.density_size.1 as f32),
+                egui::Sense::hover(),
+            );
+            let painter = ui.painter_at(rect);
+            for y in 0..stats.density_size.1 {
+                for x in 0..stats.density_size.0 {
+                    let amount = stats.get_density(x, y) as f32 / max_density as f32;
+                    let min = rect.min + egui::vec2(x as f32 * cell_size, y as f32 * cell_size);
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(min, egui::Vec2::splat(cell_size)),
+                        0.0,
+                        egui::Color32::from_rgb((amount * 255.0) as u8, 0, ((1.0 - amount) * 64.0) as u8),
+                    );
+                }
+            }
+            if let Some(pos) = response.hover_pos() {
+                let x = ((pos.x - rect.min.x) / cell_size) as usize;
+                let y = ((pos.y - rect.min.y) / cell_size) as usize;
+                if x < stats.density_size.0 && y < stats.density_size.1 {
+                    response.on_hover_text(format!("Screen ({x}, {y}): {} spawners", stats.get_density(x, y)));
+                }
+            }
+            ui.separator();
+
+            ui.heading("Object Types");
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("level_stats_wobj_grid").num_columns(2).striped(true).show(ui, |ui| {
+                    for (name, count) in stats.wobj_counts.iter() {
+                        ui.label(name);
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+            ui.separator();
+
+            if ui.button("Export JSON").clicked() {
+                let path = std::path::Path::new("levels/").join(editor_data.level_file_name.clone() + ".stats.json");
+                match std::fs::File::create(&path) {
+                    Ok(file) => match serde_json::to_writer_pretty(file, &stats) {
+                        Ok(_) => log::info!("Exported level statistics to {}", path.to_string_lossy()),
+                        Err(err) => log::error!("Can't export level statistics due to {}", err),
+                    },
+                    Err(_) => log::error!("Can't open the file for writing!"),
+                }
+            }
+        });
+    }
+}
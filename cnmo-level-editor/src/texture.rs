@@ -9,6 +9,7 @@ pub struct Texture {
     pub palette: Vec<[u8; 3]>,
     pub dimensions: (u32, u32),
     pub opaques: Vec<Vec<bool>>,
+    pub tile_colors: Vec<Vec<[u8; 3]>>,
 }
 
 struct ImageData {
@@ -16,6 +17,7 @@ struct ImageData {
     pub image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
     pub size: (u32, u32),
     pub opaques: Vec<Vec<bool>>,
+    pub tile_colors: Vec<Vec<[u8; 3]>>,
 }
 
 static EDITOR_BASE: &'static [u8] = include_bytes!("editorbase.bmp");
@@ -50,6 +52,32 @@ impl ImageData {
         return opaques;
     }
 
+    /// The average color of each 32x32 tile in the image, leaving out the
+    /// transparent color. Indexed the same way as the tile grid.
+    fn compute_tile_colors(rgba: &image::ImageBuffer<image::Rgba<u8>, Vec<u8>>) -> Vec<Vec<[u8; 3]>> {
+        let (width, height) = rgba.dimensions();
+        (0..width / 32).map(|tx| {
+            (0..height / 32).map(|ty| {
+                let mut sum = [0u32; 3];
+                let mut count = 0;
+                for y in ty * 32..ty * 32 + 32 {
+                    for x in tx * 32..tx * 32 + 32 {
+                        let rgba = rgba.get_pixel(x, y).0;
+                        if rgba[0] == 0 && rgba[1] == 255 && rgba[2] == 255 {
+                            continue;
+                        }
+                        sum[0] += rgba[0] as u32;
+                        sum[1] += rgba[1] as u32;
+                        sum[2] += rgba[2] as u32;
+                        count += 1;
+                    }
+                }
+                let count = u32::max(count, 1);
+                [(sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8]
+            }).collect()
+        }).collect()
+    }
+
     fn get_image(buffer: &[u8]) -> Option<Self> {
         let decoder = match image::codecs::bmp::BmpDecoder::new(Cursor::new(buffer)) {
             Ok(decoder) => decoder,
@@ -66,6 +94,7 @@ impl ImageData {
                 None => Vec::new(),
             },
             opaques: Self::compute_opaques(&rgba),
+            tile_colors: Self::compute_tile_colors(&rgba),
             image: rgba,
             size: <image::DynamicImage as image::GenericImageView>::dimensions(&image),
         })
@@ -93,6 +122,7 @@ impl ImageData {
             Self {
                 palette: self.palette,
                 opaques: Self::compute_opaques(&newimage),
+                tile_colors: Self::compute_tile_colors(&newimage),
                 image: newimage,
                 size: self.size,
             },
@@ -132,6 +162,7 @@ impl Texture {
             view,
             sampler,
             opaques: vec![],
+            tile_colors: vec![],
             palette: Vec::new(),
             dimensions: (size.clone().into().0, size.clone().into().1),
         }
@@ -169,6 +200,7 @@ impl Texture {
         );
         texture.dimensions = image.0.size;
         texture.opaques = image.0.opaques;
+        texture.tile_colors = image.0.tile_colors;
         texture
     }
 }
@@ -282,7 +282,7 @@ impl TilePanel {
                     let camera = Camera::new().with_projection(32.0, 32.0, None, true);
                     let sprites = vec![
                         Sprite::new((0.0, 0.0, 0.0), (32.0, 32.0), (tile.frames[0].0 as f32 * 32.0, tile.frames[0].1 as f32 * 32.0, 32.0, 32.0)),
-                        Sprite::new_pure_color((rect.x as f32, rect.y as f32, 0.0), (rect.w as f32, rect.h as f32), (1.0, 0.0, 1.0, 0.3)),
+                        get_collision_rect_sprite(rect, (0.0, 0.0), COLLISION_COLOR),
                     ];
                     InstancedSprites::new()
                         .with_camera(camera)
@@ -310,13 +310,7 @@ impl TilePanel {
                     let mut sprites = vec![
                         Sprite::new((0.0, 0.0, 0.0), (32.0, 32.0), (tile.frames[0].0 as f32 * 32.0, tile.frames[0].1 as f32 * 32.0, 32.0, 32.0)),
                     ];
-                    for (idx, height) in heightmap.iter().enumerate() {
-                        sprites.push(Sprite::new_pure_color(
-                            (idx as f32, (32 - *height) as f32, 0.0),
-                            (1.0, *height as f32),
-                            (1.0, 0.0, 1.0, 0.3)
-                        ));
-                    }
+                    sprites.append(&mut get_heightmap_sprites(heightmap, (0.0, 0.0), COLLISION_COLOR));
                     InstancedSprites::new()
                         .with_camera(camera)
                         .with_sprites(sprites)
@@ -377,6 +371,7 @@ impl TilePanel {
                 }
 
                 // Delete the tile
+                editor_data.history.label("Delete Tile");
                 level_data.tile_properties.remove(tile_id as usize);
 
                 // Clear selections of tiles
@@ -386,6 +381,26 @@ impl TilePanel {
     }
 }
 
+pub const COLLISION_COLOR: (f32, f32, f32, f32) = (1.0, 0.0, 1.0, 0.3);
+
+/// The box of a box or jumpthrough collision for a tile at `pos`
+pub fn get_collision_rect_sprite(rect: &cnmo_parse::Rect, pos: (f32, f32), color: (f32, f32, f32, f32)) -> Sprite {
+    Sprite::new_pure_color((pos.0 + rect.x as f32, pos.1 + rect.y as f32, 0.0), (rect.w as f32, rect.h as f32), color)
+}
+
+/// One column per pixel of a heightmap collision for a tile at `pos`
+pub fn get_heightmap_sprites(heightmap: &[u8], pos: (f32, f32), color: (f32, f32, f32, f32)) -> Vec<Sprite> {
+    heightmap
+        .iter()
+        .enumerate()
+        .map(|(idx, height)| Sprite::new_pure_color(
+            (pos.0 + idx as f32, pos.1 + (32 - *height) as f32, 0.0),
+            (1.0, *height as f32),
+            color,
+        ))
+        .collect()
+}
+
 fn get_damage_type_name(dmg_type: &DamageType) -> &str {
     match dmg_type {
         DamageType::None => "None",
@@ -132,7 +132,7 @@ impl TileViewer {
                     self.drag_pos = Some(pos);
                 }
                 if response.drag_released() && self.drag_pos != None {
-                    editor_data.cells_history = vec![];
+                    editor_data.history.label("Move Tile");
 
                     // Complete the drag
                     let change_refrences = |old_id: u16, new_id: u16, cells: &mut Cells| {
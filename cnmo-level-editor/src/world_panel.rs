@@ -1,11 +1,14 @@
 use std::collections::VecDeque;
 
 use crate::editor_data::{EditorData, Tool};
+use crate::layers::Layer;
+use crate::light_tools::{apply_light_changes, get_gradient, get_light_source, LightChange, LightMode};
 use crate::instanced_sprites::{InstancedSprites, Sprite};
 use cnmo_parse::lparse::level_data;
-use cnmo_parse::lparse::level_data::cnmb_types::{BackgroundLayer, Cells, TileId, TileProperties};
+use cnmo_parse::lparse::level_data::brushes::in_bounds;
+use cnmo_parse::lparse::level_data::cnmb_types::{BackgroundLayer, Cell, Cells, TileId, TileProperties};
 use cnmo_parse::lparse::level_data::cnms_types::wobj_type::WobjType;
-use cnmo_parse::lparse::level_data::cnms_types::SpawningCriteria;
+use cnmo_parse::lparse::level_data::cnms_types::{Spawner, SpawningCriteria};
 use eframe::egui;
 use level_data::cnmb_types::BackgroundImage;
 //use crate::common_gfx::GfxCommonResources;
@@ -21,6 +24,7 @@ pub struct WorldPanel {
     pub resizing_bounds: (bool, bool, bool, bool),
     pub grabbing_resize: bool,
     pub show_grid: bool,
+    pub show_collision: bool,
     pub brush_origin: (i32, i32),
     pub copy_selection: Option<(i32, i32, i32, i32)>,
     pub editing_background: bool,
@@ -29,6 +33,13 @@ pub struct WorldPanel {
     pub right_clicked_spawner_idx: Option<usize>,
     pub close_context_menu: bool,
     pub hovered_on_context_menu: bool,
+    pub spawn_preview_players: Option<u32>,
+    pub hide_unspawned: bool,
+    pub highlighted_spawners: Vec<usize>,
+    spawner_drag: Option<SpawnerDrag>,
+    /// Where the shape being dragged started and if it's the primary button
+    shape_start: Option<((i32, i32), bool)>,
+    gradient_start: Option<(i32, i32)>,
 }
 
 impl WorldPanel {
@@ -41,6 +52,7 @@ impl WorldPanel {
             resizing_bounds: (false, false, false, false),
             grabbing_resize: false,
             show_grid: true,
+            show_collision: false,
             brush_origin: (0, 0),
             copy_selection: None,
             editing_background: false,
@@ -49,6 +61,12 @@ impl WorldPanel {
             right_clicked_spawner_idx: None,
             close_context_menu: false,
             hovered_on_context_menu: false,
+            spawn_preview_players: None,
+            hide_unspawned: false,
+            highlighted_spawners: vec![],
+            spawner_drag: None,
+            shape_start: None,
+            gradient_start: None,
         }
     }
 
@@ -117,6 +135,9 @@ impl WorldPanel {
             &response,
             &pointer_pos,
         );
+        if !self.editing_background && self.show_collision {
+            self.show_collision(&mut sprites, level_data, editor_data);
+        }
         if !self.editing_background {
             self.show_grid(
                 &mut sprites,
@@ -128,19 +149,6 @@ impl WorldPanel {
                 &pointer_pos,
             );
         }
-        if response.hovered()
-            && (response.ctx.input().modifiers.ctrl || response.ctx.input().modifiers.mac_cmd)
-            && response.ctx.input().key_pressed(egui::Key::Z)
-        {
-            if let Some(history) = editor_data.cells_history.pop() {
-                level_data.cells = history.0;
-                level_data.spawners = history.1;
-                editor_data.selected_spawner = None;
-                log::info!("Undid transformation");
-            } else {
-                log::info!("There are no transformations in the history buffer!");
-            }
-        }
         let grid_size = if matches!(editor_data.tool, Tool::Spawners) {
             editor_data.spawner_grid_size
         } else {
@@ -258,9 +266,10 @@ impl WorldPanel {
                 Some(layer) => layer,
                 None => continue,
             };
-            if layer.in_foreground != foreground {
+            if layer.in_foreground != foreground || !editor_data.layers.is_visible(Layer::Backdrop(idx)) {
                 continue;
             }
+            let editable = editor_data.layers.is_editable(Layer::Backdrop(idx));
             if let BackgroundImage::Color(color_index) = layer.image {
                 let pal = &editor_data.palette[color_index as usize];
                 sprites.push(Sprite::new_pure_color(
@@ -284,7 +293,7 @@ impl WorldPanel {
                     continue;
                 }
 
-                if self.editing_background && editor_data.current_background == idx {
+                if self.editing_background && editor_data.current_background == idx && editable {
                     if response.dragged_by(egui::PointerButton::Primary) {
                         let delta = response.drag_delta();
                         layer.origin.0 += (delta.x / main_rect.width()) * cam_size.x / scale;
@@ -346,6 +355,7 @@ impl WorldPanel {
                             && pointer_pos.x < real_pos.x + width
                             && pointer_pos.y < real_pos.y + height
                             && response.clicked()
+                            && editable
                         {
                             if cap_width {
                                 selected_layer = Some(idx);
@@ -462,8 +472,25 @@ impl WorldPanel {
                     );
                     editor_data.viewer_selection = Some(viewer_selection);
                     editor_data.selected_tiles = vec![];
+                    if editor_data.copy_spawners_with_tiles {
+                        let min = (copy_selection.0 as f32 * 32.0, copy_selection.1 as f32 * 32.0);
+                        let max = ((copy_selection.2 + 1) as f32 * 32.0, (copy_selection.3 + 1) as f32 * 32.0);
+                        editor_data.copied_spawners = level_data
+                            .spawners
+                            .iter()
+                            .filter(|spawner| spawner.pos.0 >= min.0 && spawner.pos.0 < max.0 && spawner.pos.1 >= min.1 && spawner.pos.1 < max.1)
+                            .map(|spawner| {
+                                let mut spawner = spawner.clone();
+                                spawner.pos.0 -= min.0;
+                                spawner.pos.1 -= min.1;
+                                spawner
+                            })
+                            .collect();
+                    }
                     self.copy_selection = None;
                     editor_data.has_copied_tiles = true;
+                    editor_data.paste_light = false;
+                    editor_data.auto_tile = None;
                 }
             }
         } else {
@@ -472,7 +499,13 @@ impl WorldPanel {
             editor_data.viewer_selection = None;
         }
 
-        if matches!(editor_data.tool, Tool::Light) {
+        let foreground_editable = editor_data.layers.is_editable(Layer::Foreground);
+        let background_editable = editor_data.layers.is_editable(Layer::Background);
+        let light_editable = editor_data.layers.is_editable(Layer::Light);
+        if matches!(editor_data.tool, Tool::Light)
+            && matches!(editor_data.light_options.mode, LightMode::Paint)
+            && light_editable
+        {
             let tile_ref = level_data.cells.get_cell_mut(mx, my);
             if response.ctx.input().pointer.primary_down() && response.hovered() {
                 tile_ref.light = editor_data.light_tool_level;
@@ -486,26 +519,25 @@ impl WorldPanel {
                 && (response.ctx.input().pointer.primary_clicked()
                     || response.ctx.input().pointer.secondary_clicked())
             {
-                editor_data
-                    .cells_history
-                    .push((level_data.cells.clone(), level_data.spawners.clone()));
-                if editor_data.cells_history.len() > 512 {
-                    editor_data.cells_history.remove(0);
-                }
+                editor_data.history.label("Erase");
             }
             let tile_ref = level_data.cells.get_cell_mut(mx, my);
             if matches!(editor_data.tool, Tool::Eraser) {
                 if response.ctx.input().pointer.primary_down() && response.hovered() {
                     if editor_data.foreground_placing {
-                        tile_ref.foreground = level_data::cnmb_types::TileId(None);
-                    } else {
+                        if foreground_editable {
+                            tile_ref.foreground = level_data::cnmb_types::TileId(None);
+                        }
+                    } else if background_editable {
                         tile_ref.background = level_data::cnmb_types::TileId(None);
                     }
                 }
                 if response.ctx.input().pointer.secondary_down() && response.hovered() {
                     if editor_data.foreground_placing {
-                        tile_ref.background = level_data::cnmb_types::TileId(None);
-                    } else {
+                        if background_editable {
+                            tile_ref.background = level_data::cnmb_types::TileId(None);
+                        }
+                    } else if foreground_editable {
                         tile_ref.foreground = level_data::cnmb_types::TileId(None);
                     }
                 }
@@ -514,18 +546,19 @@ impl WorldPanel {
             self.brush_origin = (mx, my);
         }
 
+        let show_foreground = editor_data.layers.is_visible(Layer::Foreground);
+        let show_background = editor_data.layers.is_visible(Layer::Background);
+        let show_light = editor_data.layers.is_visible(Layer::Light);
         for row in start.y..=end.y {
             for column in start.x..=end.x {
                 let tile = level_data.cells.get_cell(column, row);
                 let pos = (column as f32 * 32.0, row as f32 * 32.0, 0.0);
                 let mut drawer = |props: &TileProperties, foreground: bool| {
-                    let idx = (editor_data.time_past.as_secs_f32()
-                        * (30.0 / props.anim_speed.0 as f32))
-                        as usize
-                        % props.frames.len();
+                    let idx = editor_data.get_tile_frame(props);
                     let tint = if self.gray_other
                         && editor_data.foreground_placing != foreground
-                        && !matches!(editor_data.tool, Tool::Light) 
+                        && !matches!(editor_data.tool, Tool::Light)
+                        && !(editor_data.tool.can_paint_light() && editor_data.shape_on_light)
                     {
                         0.5
                     } else {
@@ -551,26 +584,17 @@ impl WorldPanel {
                     sprites.push(sprite);
                 };
 
-                if let Some(background) = tile.background.0 {
+                if let Some(background) = tile.background.0.filter(|_| show_background) {
                     drawer(&level_data.tile_properties[background as usize], false);
                 }
-                if let Some(foreground) = tile.foreground.0 {
+                if let Some(foreground) = tile.foreground.0.filter(|_| show_foreground) {
                     drawer(&level_data.tile_properties[foreground as usize], true);
                 }
-                use level_data::consts::*;
-                if tile.light != LIGHT_NORMAL {
-                    let color = if tile.light < LIGHT_NORMAL { 1.0 } else { 0.0 };
-                    let percent = if tile.light < LIGHT_NORMAL {
-                        1.0 - (tile.light as f32 / LIGHT_NORMAL as f32)
-                    } else if tile.light > LIGHT_NORMAL {
-                        (tile.light - LIGHT_NORMAL) as f32 / (LIGHT_BLACK - LIGHT_NORMAL) as f32
-                    } else {
-                        0.0
-                    };
+                if tile.light != level_data::consts::LIGHT_NORMAL && show_light {
                     sprites.push(Sprite::new_pure_color(
                         pos,
                         (32.0, 32.0),
-                        (color, color, color, percent),
+                        get_light_tint(tile.light),
                     ));
                 }
             }
@@ -582,15 +606,15 @@ impl WorldPanel {
                 && !self.grabbing_resize
                 && response.hovered()
             {
-                editor_data
-                    .cells_history
-                    .push((level_data.cells.clone(), level_data.spawners.clone()));
-                if editor_data.cells_history.len() > 512 {
-                    editor_data.cells_history.remove(0);
-                }
+                editor_data.history.label("Fill");
 
                 // Fill in with the first selected thing
                 let primary_clicked = response.ctx.input().pointer.primary_clicked();
+                let fill_editable = if editor_data.foreground_placing == primary_clicked {
+                    foreground_editable
+                } else {
+                    background_editable
+                };
                 let set_tile = if editor_data.selected_tiles.len() > 0 {
                     TileId(Some(editor_data.selected_tiles[0] as u16))
                 } else {
@@ -612,7 +636,7 @@ impl WorldPanel {
 
                 let mut queue = VecDeque::new();
                 queue.push_back((mx, my));
-                while !queue.is_empty() && need_tile != set_tile {
+                while !queue.is_empty() && need_tile != set_tile && fill_editable {
                     let top = *queue.front().unwrap();
                     queue.pop_front();
 
@@ -663,9 +687,7 @@ impl WorldPanel {
 
             if let Some(tile) = editor_data.selected_tiles.get(0) {
                 let props = &level_data.tile_properties[*tile];
-                let idx = (editor_data.time_past.as_secs_f32() * (30.0 / props.anim_speed.0 as f32))
-                    as usize
-                    % props.frames.len();
+                let idx = editor_data.get_tile_frame(props);
                 let mut sprite = Sprite::new(
                     (mx as f32 * 32.0, my as f32 * 32.0, 0.0),
                     (32.0, 32.0),
@@ -716,9 +738,7 @@ impl WorldPanel {
 
             if let Some(tile) = editor_data.selected_tiles.get(0) {
                 let props = &level_data.tile_properties[*tile];
-                let idx = (editor_data.time_past.as_secs_f32() * (30.0 / props.anim_speed.0 as f32))
-                    as usize
-                    % props.frames.len();
+                let idx = editor_data.get_tile_frame(props);
                 let mut sprite = Sprite::new(
                     (mx as f32 * 32.0, my as f32 * 32.0, 0.0),
                     (32.0, 32.0),
@@ -739,15 +759,31 @@ impl WorldPanel {
                 && (response.ctx.input().pointer.primary_clicked()
                     || response.ctx.input().pointer.secondary_clicked())
             {
-                editor_data
-                    .cells_history
-                    .push((level_data.cells.clone(), level_data.spawners.clone()));
-                if editor_data.cells_history.len() > 512 {
-                    editor_data.cells_history.remove(0);
-                }
+                editor_data.history.label(if editor_data.auto_tile.is_some() { "Auto Tile" } else { "Brush" });
             }
 
-            if let Some(ref viewer_selection) = editor_data.viewer_selection.as_ref() {
+            let auto_tile = editor_data.auto_tile.and_then(|idx| editor_data.brush_library.auto_tiles.get(idx));
+            let stamp_origin = if let Some(rule) = auto_tile {
+                if self.copy_selection.is_none() {
+                    sprites.append(&mut Sprite::new_rect(
+                        (mx as f32 * 32.0, my as f32 * 32.0),
+                        ((mx + 1) as f32 * 32.0, (my + 1) as f32 * 32.0),
+                        2.0,
+                        (1.0, 1.0, 1.0, 0.7),
+                    ).to_vec());
+                }
+                let layer = if editor_data.foreground_placing { Layer::Foreground } else { Layer::Background };
+                if tile_placing_enabled
+                    && self.copy_selection.is_none()
+                    && response.hovered()
+                    && editor_data.layers.is_editable(layer)
+                {
+                    // Left click grows the terrain and right click cuts it away
+                    let place = response.ctx.input().pointer.primary_down();
+                    rule.paint(&mut level_data.cells, mx, my, editor_data.foreground_placing, place);
+                }
+                None
+            } else if let Some(ref viewer_selection) = editor_data.viewer_selection.as_ref() {
                 let ox = self.brush_origin.0
                     + ((mx - self.brush_origin.0) as f32 / viewer_selection.width() as f32).floor()
                         as i32
@@ -760,10 +796,7 @@ impl WorldPanel {
                 for y in 0..viewer_selection.height() as i32 {
                     for x in 0..viewer_selection.width() as i32 {
                         let mut drawer = |props: &TileProperties| {
-                            let idx = (editor_data.time_past.as_secs_f32()
-                                * (30.0 / props.anim_speed.0 as f32))
-                                as usize
-                                % props.frames.len();
+                            let idx = editor_data.get_tile_frame(props);
                             let mut sprite = Sprite::new(
                                 ((ox + x) as f32 * 32.0, (oy + y) as f32 * 32.0, 0.0),
                                 (32.0, 32.0),
@@ -794,8 +827,10 @@ impl WorldPanel {
                                 if response.ctx.input().pointer.primary_down() && response.hovered()
                                 {
                                     if editor_data.foreground_placing {
-                                        dst.foreground = src.foreground;
-                                    } else {
+                                        if foreground_editable {
+                                            dst.foreground = src.foreground;
+                                        }
+                                    } else if background_editable {
                                         dst.background = src.background;
                                     }
                                 }
@@ -803,19 +838,57 @@ impl WorldPanel {
                                     && response.hovered()
                                 {
                                     if editor_data.foreground_placing {
-                                        dst.background = src.background;
-                                    } else {
+                                        if background_editable {
+                                            dst.background = src.background;
+                                        }
+                                    } else if foreground_editable {
                                         dst.foreground = src.foreground;
                                     }
                                 }
                             }
                             if editor_data.has_copied_tiles && response.hovered() {
-                                dst.foreground = src.foreground;
-                                dst.background = src.background;
+                                if foreground_editable {
+                                    dst.foreground = src.foreground;
+                                }
+                                if background_editable {
+                                    dst.background = src.background;
+                                }
+                                if light_editable && editor_data.paste_light {
+                                    dst.light = src.light;
+                                }
                             }
                         }
                     }
                 }
+                Some((ox, oy))
+            } else {
+                None
+            };
+
+            // Spawners copied with the tiles are stamped once per click
+            if let Some((ox, oy)) = stamp_origin {
+                if editor_data.has_copied_tiles && editor_data.copy_spawners_with_tiles && self.copy_selection.is_none() {
+                    let origin = level_data::Point(ox as f32 * 32.0, oy as f32 * 32.0);
+                    for spawner in editor_data.copied_spawners.iter() {
+                        let mut spawner = spawner.clone();
+                        spawner.pos.0 += origin.0;
+                        spawner.pos.1 += origin.1;
+                        let first_sprite = sprites.len();
+                        draw_spawner(sprites, &spawner, &self.camera, editor_data, false);
+                        for sprite in sprites[first_sprite..].iter_mut() {
+                            sprite.tint[3] *= 0.5;
+                        }
+                    }
+                    let layers = &editor_data.layers;
+                    if tile_placing_enabled
+                        && response.hovered()
+                        && response.ctx.input().pointer.primary_clicked()
+                        && editor_data.copied_spawners.iter().all(|spawner| layers.is_spawner_editable(&spawner.type_data))
+                    {
+                        let copied = editor_data.copied_spawners.clone();
+                        paste_spawners(level_data, editor_data, &copied, origin);
+                    }
+                }
             }
 
             if let Some(copy_selection) = self.copy_selection {
@@ -833,6 +906,222 @@ impl WorldPanel {
                 ));
             }
         }
+
+        self.update_shape_tools(sprites, level_data, response, editor_data, (mx, my), (start, end));
+        self.update_light_tools(sprites, level_data, response, editor_data, (mx, my));
+    }
+
+    /// Light sources are put down with a click, and gradients are dragged from
+    /// the light tool's level to the end level. Both preview the new levels.
+    fn update_light_tools(
+        &mut self,
+        sprites: &mut Vec<Sprite>,
+        level_data: &mut level_data::LevelData,
+        response: &egui::Response,
+        editor_data: &mut EditorData,
+        (mx, my): (i32, i32),
+    ) {
+        let (primary_clicked, primary_down, escape) = {
+            let input = response.ctx.input();
+            (input.pointer.primary_clicked(), input.pointer.primary_down(), input.key_pressed(egui::Key::Escape))
+        };
+        let options = &editor_data.light_options;
+        if !matches!(editor_data.tool, Tool::Light) || options.mode != LightMode::Gradient || escape {
+            self.gradient_start = None;
+        }
+        if !matches!(editor_data.tool, Tool::Light) || !editor_data.layers.is_editable(Layer::Light) || self.grabbing_resize {
+            return;
+        }
+
+        match options.mode {
+            LightMode::Paint => {}
+            LightMode::Source => {
+                if !response.hovered() {
+                    return;
+                }
+                let changes = get_light_source(&level_data.cells, (mx, my), options.radius, options.falloff, editor_data.light_tool_level);
+                if primary_clicked {
+                    editor_data.history.label("Light Source");
+                    apply_light_changes(&mut level_data.cells, &changes);
+                } else {
+                    draw_light_preview(sprites, &changes);
+                }
+            }
+            LightMode::Gradient => {
+                if self.gradient_start.is_none() && primary_clicked && response.hovered() {
+                    self.gradient_start = Some((mx, my));
+                }
+                let Some(gradient_start) = self.gradient_start else {
+                    return;
+                };
+                let changes = get_gradient(
+                    &level_data.cells,
+                    gradient_start,
+                    (mx, my),
+                    options.gradient_width,
+                    editor_data.light_tool_level,
+                    options.gradient_end_level,
+                );
+                if primary_down {
+                    draw_light_preview(sprites, &changes);
+                    sprites.append(&mut Sprite::new_rect(
+                        (gradient_start.0 as f32 * 32.0, gradient_start.1 as f32 * 32.0),
+                        ((gradient_start.0 + 1) as f32 * 32.0, (gradient_start.1 + 1) as f32 * 32.0),
+                        2.0,
+                        (1.0, 1.0, 1.0, 0.7),
+                    ).to_vec());
+                } else {
+                    editor_data.history.label("Light Gradient");
+                    apply_light_changes(&mut level_data.cells, &changes);
+                    self.gradient_start = None;
+                }
+            }
+        }
+    }
+
+    /// Rectangles, lines and ellipses are dragged out with a preview and put
+    /// down when the mouse is let go. Replace changes every cell in the level
+    /// that matches the clicked one.
+    fn update_shape_tools(
+        &mut self,
+        sprites: &mut Vec<Sprite>,
+        level_data: &mut level_data::LevelData,
+        response: &egui::Response,
+        editor_data: &mut EditorData,
+        (mx, my): (i32, i32),
+        (start, end): (cgmath::Vector2<i32>, cgmath::Vector2<i32>),
+    ) {
+        let (primary_clicked, secondary_clicked, primary_down, secondary_down, escape) = {
+            let input = response.ctx.input();
+            (
+                input.pointer.primary_clicked(),
+                input.pointer.secondary_clicked(),
+                input.pointer.primary_down(),
+                input.pointer.secondary_down(),
+                input.key_pressed(egui::Key::Escape),
+            )
+        };
+
+        if let Some(shape) = editor_data.tool.get_shape() {
+            if self.shape_start.is_none()
+                && !self.grabbing_resize
+                && response.hovered()
+                && (primary_clicked || secondary_clicked)
+            {
+                self.shape_start = Some(((mx, my), primary_clicked));
+            }
+            if escape {
+                self.shape_start = None;
+            }
+            let primary = !matches!(self.shape_start, Some((_, false)));
+            let paint = get_paint(editor_data, primary);
+            let cells: Vec<(i32, i32)> = match self.shape_start {
+                Some((shape_start, _)) => shape.get_cells(shape_start, (mx, my), editor_data.shape_filled),
+                None => vec![(mx, my)],
+            }
+            .into_iter()
+            .filter(|pos| in_bounds(&level_data.cells, pos.0, pos.1))
+            .collect();
+            let released = self.shape_start.is_some() && if primary { !primary_down } else { !secondary_down };
+            if released {
+                if editor_data.layers.is_editable(paint.get_layer()) {
+                    editor_data.history.label(&editor_data.tool.to_string());
+                    for (x, y) in cells {
+                        paint.apply(level_data.cells.get_cell_mut(x, y));
+                    }
+                }
+                self.shape_start = None;
+            } else {
+                draw_paint_preview(sprites, level_data, editor_data, paint, &cells);
+            }
+        } else {
+            self.shape_start = None;
+        }
+
+        if let Tool::Replace = editor_data.tool {
+            if self.grabbing_resize || !response.hovered() || !in_bounds(&level_data.cells, mx, my) {
+                return;
+            }
+            let paint = get_paint(editor_data, !secondary_down);
+            let target = paint.read(level_data.cells.get_cell(mx, my));
+            if (primary_clicked || secondary_clicked) && target != paint && editor_data.layers.is_editable(paint.get_layer()) {
+                editor_data.history.label("Replace");
+                for y in 0..level_data.cells.height() as i32 {
+                    for x in 0..level_data.cells.width() as i32 {
+                        let cell = level_data.cells.get_cell_mut(x, y);
+                        if paint.read(cell) == target {
+                            paint.apply(cell);
+                        }
+                    }
+                }
+            }
+            // Only the matches in view are previewed
+            let matches: Vec<(i32, i32)> = (start.y..=end.y)
+                .flat_map(|y| (start.x..=end.x).map(move |x| (x, y)))
+                .filter(|pos| in_bounds(&level_data.cells, pos.0, pos.1) && paint.read(level_data.cells.get_cell(pos.0, pos.1)) == target)
+                .collect();
+            draw_paint_preview(sprites, level_data, editor_data, paint, &matches);
+        }
+    }
+
+    /// Draws the collision shapes of solid tiles and tints tiles that hurt or
+    /// change how the player moves
+    fn show_collision(
+        &self,
+        sprites: &mut Vec<Sprite>,
+        level_data: &level_data::LevelData,
+        editor_data: &EditorData,
+    ) {
+        use crate::tile_panel::{get_collision_rect_sprite, get_heightmap_sprites, COLLISION_COLOR};
+        use level_data::cnmb_types::{CollisionType, DamageType};
+
+        let top_left = self.camera.get_top_left_world_space();
+        let bottom_right = self.camera.get_bottom_right_world_space();
+        let show_foreground = editor_data.layers.is_visible(Layer::Foreground);
+        let show_background = editor_data.layers.is_visible(Layer::Background);
+        for row in (top_left.y / 32.0).floor() as i32..=(bottom_right.y / 32.0).ceil() as i32 {
+            for column in (top_left.x / 32.0).floor() as i32..=(bottom_right.x / 32.0).ceil() as i32 {
+                let cell = level_data.cells.get_cell(column, row);
+                let pos = (column as f32 * 32.0, row as f32 * 32.0);
+                let tiles = [
+                    cell.background.0.filter(|_| show_background),
+                    cell.foreground.0.filter(|_| show_foreground),
+                ];
+                for props in tiles.iter().flatten().filter_map(|id| level_data.tile_properties.get(*id as usize)) {
+                    let damage_color = match props.damage_type {
+                        DamageType::None => None,
+                        DamageType::Lava(_) => Some((1.0, 0.4, 0.0, 0.4)),
+                        DamageType::Spikes(_) => Some((1.0, 0.0, 0.0, 0.4)),
+                        DamageType::Quicksand(_) => Some((0.8, 0.6, 0.2, 0.4)),
+                        DamageType::Ice(_) => Some((0.4, 0.9, 1.0, 0.4)),
+                        DamageType::Splashes(_) => Some((0.0, 0.3, 1.0, 0.4)),
+                    };
+                    if let Some(color) = damage_color {
+                        sprites.push(Sprite::new_pure_color((pos.0, pos.1, 0.0), (32.0, 32.0), color));
+                    }
+                    if !props.solid {
+                        continue;
+                    }
+                    match &props.collision_data {
+                        CollisionType::Box(rect) => {
+                            sprites.push(get_collision_rect_sprite(rect, pos, COLLISION_COLOR));
+                        }
+                        CollisionType::Jumpthrough(rect) => {
+                            // Only the top of a jumpthrough stops the player
+                            sprites.push(get_collision_rect_sprite(rect, pos, (0.0, 1.0, 0.4, 0.2)));
+                            sprites.push(Sprite::new_pure_color(
+                                (pos.0 + rect.x as f32, pos.1 + rect.y as f32, 0.0),
+                                (rect.w as f32, 2.0),
+                                (0.0, 1.0, 0.4, 0.8),
+                            ));
+                        }
+                        CollisionType::Heightmap(heightmap) => {
+                            sprites.append(&mut get_heightmap_sprites(heightmap, pos, COLLISION_COLOR));
+                        }
+                    }
+                }
+            }
+        }
     }
 
     fn show_grid(
@@ -868,12 +1157,7 @@ impl WorldPanel {
                         max_height = min_y + 64.0;
                     }
                     if response.drag_released() {
-                        editor_data
-                            .cells_history
-                            .push((level_data.cells.clone(), level_data.spawners.clone()));
-                        if editor_data.cells_history.len() > 512 {
-                            editor_data.cells_history.remove(0);
-                        }
+                        editor_data.history.label("Resize Level");
                         level_data
                             .cells
                             .resize(level_data.cells.width(), max_height as usize / 32);
@@ -885,12 +1169,7 @@ impl WorldPanel {
                         min_y = max_height - 64.0;
                     }
                     if response.drag_released() {
-                        editor_data
-                            .cells_history
-                            .push((level_data.cells.clone(), level_data.spawners.clone()));
-                        if editor_data.cells_history.len() > 512 {
-                            editor_data.cells_history.remove(0);
-                        }
+                        editor_data.history.label("Resize Level");
                         let src_offset = min_y as i32 / 32;
 
                         for spawner in level_data.spawners.iter_mut() {
@@ -914,12 +1193,7 @@ impl WorldPanel {
                         min_x = max_width - 64.0;
                     }
                     if response.drag_released() {
-                        editor_data
-                            .cells_history
-                            .push((level_data.cells.clone(), level_data.spawners.clone()));
-                        if editor_data.cells_history.len() > 512 {
-                            editor_data.cells_history.remove(0);
-                        }
+                        editor_data.history.label("Resize Level");
                         let src_offset = min_x as i32 / 32;
 
                         for spawner in level_data.spawners.iter_mut() {
@@ -943,12 +1217,7 @@ impl WorldPanel {
                         max_width = min_x + 64.0;
                     }
                     if response.drag_released() {
-                        editor_data
-                            .cells_history
-                            .push((level_data.cells.clone(), level_data.spawners.clone()));
-                        if editor_data.cells_history.len() > 512 {
-                            editor_data.cells_history.remove(0);
-                        }
+                        editor_data.history.label("Resize Level");
                         level_data
                             .cells
                             .resize(max_width as usize / 32, level_data.cells.height());
@@ -1070,21 +1339,39 @@ impl WorldPanel {
             );
         }
     }
+    fn is_spawner_shown(&self, spawner: &Spawner) -> bool {
+        match self.spawn_preview_players {
+            Some(players) if self.hide_unspawned => spawner.spawning_criteria.mode.get_spawn_count(players) > 0,
+            _ => true,
+        }
+    }
+
     fn update_level_spawners(
         &mut self,
         sprites: &mut Vec<Sprite>,
         ui: &mut egui::Ui,
         level_data: &mut level_data::LevelData,
-        rect: &egui::Rect,
+        _rect: &egui::Rect,
         response: &egui::Response,
         editor_data: &mut EditorData,
         pointer_pos: &egui::Pos2,
     ) {
-        // let top_left = self.camera.get_top_left_world_space();
-        let cam_size = self.camera.get_proj_size_world_space();
-        let mut delete_idx = None;
         let mut hovered_spawners = Vec::new();
+        if matches!(editor_data.tool, Tool::Spawners) {
+            let layers = &editor_data.layers;
+            editor_data.selected_spawners.retain(|idx| {
+                level_data.spawners.get(*idx).is_some_and(|spawner| layers.is_spawner_editable(&spawner.type_data))
+            });
+        }
         for (idx, spawner) in level_data.spawners.iter_mut().enumerate() {
+            let spawns = match self.spawn_preview_players {
+                Some(players) => spawner.spawning_criteria.mode.get_spawn_count(players) > 0,
+                None => true,
+            };
+            if (!spawns && self.hide_unspawned) || !editor_data.layers.is_spawner_visible(&spawner.type_data) {
+                continue;
+            }
+            let first_sprite = sprites.len();
             draw_spawner(
                 sprites,
                 spawner,
@@ -1092,30 +1379,45 @@ impl WorldPanel {
                 editor_data,
                 matches!(editor_data.tool, Tool::Spawners),
             );
+            if !spawns {
+                for sprite in sprites[first_sprite..].iter_mut() {
+                    sprite.tint[3] *= 0.25;
+                }
+            }
             let spawner_rect = get_spawner_size(spawner);
             if pointer_pos.x > spawner.pos.0
                 && pointer_pos.x < spawner.pos.0 + spawner_rect.0
                 && pointer_pos.y > spawner.pos.1
                 && pointer_pos.y < spawner.pos.1 + spawner_rect.1
                 && matches!(editor_data.tool, Tool::Spawners)
+                && editor_data.layers.is_spawner_editable(&spawner.type_data)
             {
                 hovered_spawners.push(idx);
             }
-            if editor_data.selected_spawner == Some(idx)
+            if self.highlighted_spawners.contains(&idx) {
+                sprites.append(&mut Sprite::new_rect(
+                    (spawner.pos.0, spawner.pos.1),
+                    (spawner.pos.0 + spawner_rect.0, spawner.pos.1 + spawner_rect.1),
+                    2.0,
+                    (1.0, 1.0, 0.0, 0.8),
+                ).to_vec());
+            }
+            if editor_data.selected_spawners.contains(&idx)
                 && matches!(editor_data.tool, Tool::Spawners)
             {
+                let alpha = if editor_data.get_selected_spawner() == Some(idx) { 0.5 } else { 0.3 };
                 sprites.push(Sprite::new_pure_color(
                     (spawner.pos.0, spawner.pos.1, 0.0),
                     (spawner_rect.0, spawner_rect.1),
-                    (0.0, 0.2, 1.0, 0.5),
+                    (0.0, 0.2, 1.0, alpha),
                 ));
             }
         }
         if !matches!(editor_data.tool, Tool::Spawners) {
             self.close_context_menu = false;
+            self.spawner_drag = None;
             return;
         }
-        let duplicated_spawners = level_data.spawners.clone();
         let mut populated = false;
         hovered_spawners.sort_by(|idx_a, idx_b| {
             let a = get_spawner_size(&level_data.spawners[*idx_a]);
@@ -1127,10 +1429,15 @@ impl WorldPanel {
                 cmp
             }
         });
+        let shift = response.ctx.input().modifiers.shift;
+        let ctrl = response.ctx.input().modifiers.ctrl || response.ctx.input().modifiers.mac_cmd;
         if let Some(idx) = hovered_spawners.get(0) {
-            //let spawner = &mut level_data.spawners[*idx];
             if response.clicked() {
-                editor_data.selected_spawner = Some(*idx);
+                if shift {
+                    editor_data.toggle_spawner(*idx);
+                } else {
+                    editor_data.select_spawner(*idx);
+                }
                 self.right_clicked_spawner_idx = None;
             }
             if response.ctx.input().pointer.secondary_clicked() {
@@ -1138,13 +1445,15 @@ impl WorldPanel {
             }
         } else if response.hovered() {
             if response.clicked() {
-                editor_data.selected_spawner = None;
+                if !shift {
+                    editor_data.selected_spawners.clear();
+                }
                 self.right_clicked_spawner_idx = None;
             }
-            if response.double_clicked_by(egui::PointerButton::Primary) {
-                editor_data
-                    .cells_history
-                    .push((level_data.cells.clone(), duplicated_spawners.clone()));
+            if response.double_clicked_by(egui::PointerButton::Primary)
+                && editor_data.layers.is_spawner_editable(&editor_data.spawner_template.type_data)
+            {
+                editor_data.history.label("Add Spawner");
                 let mut spawner = editor_data.spawner_template.clone();
                 spawner.dropped_item = None;
                 spawner.spawner_group = None;
@@ -1153,70 +1462,141 @@ impl WorldPanel {
                     mode: cnmo_parse::lparse::level_data::cnms_types::SpawnerMode::MultiAndSingleplayer,
                     max_concurrent_spawns: 0,
                 };
-                if editor_data.spawner_grid_size > 1.0 {
-                    spawner.pos.0 = (pointer_pos.x / editor_data.spawner_grid_size).round()
-                        * editor_data.spawner_grid_size;
-                    spawner.pos.1 = (pointer_pos.y / editor_data.spawner_grid_size).round()
-                        * editor_data.spawner_grid_size;
-                } else {
-                    spawner.pos.0 = pointer_pos.x;
-                    spawner.pos.1 = pointer_pos.y;
-                }
+                spawner.pos = snap_spawner_pos(*pointer_pos, editor_data.spawner_grid_size);
                 level_data.spawners.push(spawner);
-                editor_data.selected_spawner = Some(level_data.spawners.len() - 1);
+                editor_data.select_spawner(level_data.spawners.len() - 1);
             }
             if response.ctx.input().pointer.secondary_clicked() {
                 self.right_clicked_spawner_idx = None;
             }
         }
-        if let Some(idx) = editor_data.selected_spawner {
-            let spawner = &mut level_data.spawners[idx];
-            if response.hovered() {
-                if response.drag_started() {
-                    editor_data
-                        .cells_history
-                        .push((level_data.cells.clone(), duplicated_spawners.clone()));
-                }
-                if response.dragged_by(egui::PointerButton::Primary) {
-                    spawner.pos.0 += (response.drag_delta().x / rect.width()) * cam_size.x;
-                    spawner.pos.1 += (response.drag_delta().y / rect.height()) * cam_size.y;
+
+        // Dragging from a spawner moves the selection, dragging from empty space selects with a box
+        if response.hovered() && response.drag_started() && response.ctx.input().pointer.primary_down() {
+            if let Some(idx) = hovered_spawners.first() {
+                if !editor_data.selected_spawners.contains(idx) {
+                    if shift {
+                        editor_data.selected_spawners.push(*idx);
+                    } else {
+                        editor_data.select_spawner(*idx);
+                    }
                 }
-                if response.drag_released() && editor_data.spawner_grid_size > 1.0 {
-                    spawner.pos.0 = (spawner.pos.0 / editor_data.spawner_grid_size).round()
-                        * editor_data.spawner_grid_size;
-                    spawner.pos.1 = (spawner.pos.1 / editor_data.spawner_grid_size).round()
-                        * editor_data.spawner_grid_size;
+                editor_data.history.label("Move Spawners");
+                self.spawner_drag = Some(SpawnerDrag::Move {
+                    start: *pointer_pos,
+                    origins: editor_data
+                        .selected_spawners
+                        .iter()
+                        .map(|idx| (*idx, level_data.spawners[*idx].pos))
+                        .collect(),
+                });
+            } else {
+                self.spawner_drag = Some(SpawnerDrag::Box {
+                    start: *pointer_pos,
+                    additive: shift,
+                });
+            }
+        }
+        match &self.spawner_drag {
+            Some(SpawnerDrag::Move { start, origins }) => {
+                // Snap the primary spawner to the grid and move the rest by the same amount
+                if let Some((_, primary)) = origins.last() {
+                    let target = level_data::Point(
+                        primary.0 + pointer_pos.x - start.x,
+                        primary.1 + pointer_pos.y - start.y,
+                    );
+                    let snapped = snap_spawner_pos(egui::pos2(target.0, target.1), editor_data.spawner_grid_size);
+                    for (idx, origin) in origins.iter() {
+                        level_data.spawners[*idx].pos.0 = origin.0 + snapped.0 - primary.0;
+                        level_data.spawners[*idx].pos.1 = origin.1 + snapped.1 - primary.1;
+                    }
                 }
-                if response.ctx.input().key_pressed(egui::Key::Delete)
-                    || (response.ctx.input().key_pressed(egui::Key::D)
-                        && response.ctx.input().modifiers.alt)
-                {
-                    delete_idx = Some(idx);
+            }
+            Some(SpawnerDrag::Box { start, additive }) => {
+                let min = (start.x.min(pointer_pos.x), start.y.min(pointer_pos.y));
+                let max = (start.x.max(pointer_pos.x), start.y.max(pointer_pos.y));
+                sprites.push(Sprite::new_pure_color(
+                    (min.0, min.1, 0.0),
+                    (max.0 - min.0, max.1 - min.1),
+                    (0.0, 0.2, 1.0, 0.15),
+                ));
+                sprites.append(&mut Sprite::new_rect(min, max, 2.0, (0.0, 0.4, 1.0, 0.8)).to_vec());
+                if !response.ctx.input().pointer.primary_down() {
+                    if !additive {
+                        editor_data.selected_spawners.clear();
+                    }
+                    for (idx, spawner) in level_data.spawners.iter().enumerate() {
+                        let size = get_spawner_size(spawner);
+                        if self.is_spawner_shown(spawner)
+                            && editor_data.layers.is_spawner_editable(&spawner.type_data)
+                            && spawner.pos.0 < max.0
+                            && spawner.pos.0 + size.0 > min.0
+                            && spawner.pos.1 < max.1
+                            && spawner.pos.1 + size.1 > min.1
+                            && !editor_data.selected_spawners.contains(&idx)
+                        {
+                            editor_data.selected_spawners.push(idx);
+                        }
+                    }
                 }
             }
-            if response.ctx.input().key_pressed(egui::Key::C)
-                && (response.ctx.input().modifiers.ctrl || response.ctx.input().modifiers.mac_cmd)
-                && editor_data.editing_text == None
+            None => {}
+        }
+        if !response.ctx.input().pointer.primary_down() {
+            self.spawner_drag = None;
+        }
+
+        let keys_enabled = response.hovered() && editor_data.editing_text == None;
+        let mut delete_selected = false;
+        if !editor_data.selected_spawners.is_empty() {
+            if keys_enabled
+                && (response.ctx.input().key_pressed(egui::Key::Delete)
+                    || (response.ctx.input().key_pressed(egui::Key::D)
+                        && response.ctx.input().modifiers.alt))
             {
-                editor_data.spawner_template = spawner.clone();
-                log::info!("Copied Spawner Data");
+                delete_selected = true;
             }
-            if response.ctx.input().key_pressed(egui::Key::V)
-                && response.ctx.input().modifiers.shift
-                && response.hovered()
-                && editor_data.editing_text == None
-            {
-                editor_data
-                    .cells_history
-                    .push((level_data.cells.clone(), duplicated_spawners.clone()));
-                spawner.dropped_item = editor_data.spawner_template.dropped_item.clone();
-                spawner.spawning_criteria = editor_data.spawner_template.spawning_criteria.clone();
-                spawner.type_data = editor_data.spawner_template.type_data.clone();
-                spawner.spawner_group = editor_data.spawner_template.spawner_group.clone();
+            if response.ctx.input().key_pressed(egui::Key::C) && ctrl && editor_data.editing_text == None {
+                let selected = editor_data
+                    .selected_spawners
+                    .iter()
+                    .map(|idx| level_data.spawners[*idx].clone())
+                    .collect::<Vec<_>>();
+                if let Some(primary) = selected.last() {
+                    editor_data.spawner_template = primary.clone();
+                }
+                editor_data.copied_spawners = relative_to_top_left(selected);
+                log::info!("Copied {} Spawner(s)", editor_data.copied_spawners.len());
+            }
+            if response.ctx.input().key_pressed(egui::Key::V) && shift && keys_enabled {
+                editor_data.history.label("Paste Spawner Data");
+                for idx in editor_data.selected_spawners.iter() {
+                    let spawner = &mut level_data.spawners[*idx];
+                    spawner.dropped_item = editor_data.spawner_template.dropped_item.clone();
+                    spawner.spawning_criteria = editor_data.spawner_template.spawning_criteria.clone();
+                    spawner.type_data = editor_data.spawner_template.type_data.clone();
+                    spawner.spawner_group = editor_data.spawner_template.spawner_group.clone();
+                }
                 log::info!("Pasted Spawner Data in Place");
             }
+            if response.ctx.input().key_pressed(egui::Key::D) && ctrl && keys_enabled {
+                // Only editable spawners can be selected, so the duplicates are editable too
+                editor_data.history.label("Duplicate Spawners");
+                let step = editor_data.spawner_grid_size.max(8.0);
+                let duplicates = editor_data
+                    .selected_spawners
+                    .iter()
+                    .map(|idx| {
+                        let mut spawner = level_data.spawners[*idx].clone();
+                        spawner.pos.0 += step;
+                        spawner.pos.1 += step;
+                        spawner
+                    })
+                    .collect::<Vec<_>>();
+                paste_spawners(level_data, editor_data, &duplicates, level_data::Point(0.0, 0.0));
+            }
         }
-        if let Some(idx) = editor_data.selected_spawner {
+        if let Some(idx) = editor_data.get_selected_spawner() {
             let spawner = &level_data.spawners[idx];
             let mut location = None;
             if let WobjType::Teleport(teleport) = &spawner.type_data {
@@ -1254,41 +1634,73 @@ impl WorldPanel {
                 ));
             }
         }
-        if ((response.ctx.input().key_pressed(egui::Key::V) && (response.ctx.input().modifiers.ctrl || response.ctx.input().modifiers.mac_cmd) && editor_data.editing_text == None)
+        if ((response.ctx.input().key_pressed(egui::Key::V) && ctrl && !shift && editor_data.editing_text == None)
             || (ui.ctx().input().key_pressed(egui::Key::Space) && editor_data.editing_text == None)) && response.hovered()
         {
-            editor_data
-                .cells_history
-                .push((level_data.cells.clone(), level_data.spawners.clone()));
-            let mut spawner = editor_data.spawner_template.clone();
-            if editor_data.spawner_grid_size > 1.0 {
-                spawner.pos.0 = (pointer_pos.x / editor_data.spawner_grid_size).round()
-                    * editor_data.spawner_grid_size;
-                spawner.pos.1 = (pointer_pos.y / editor_data.spawner_grid_size).round()
-                    * editor_data.spawner_grid_size;
-            } else {
-                spawner.pos.0 = pointer_pos.x;
-                spawner.pos.1 = pointer_pos.y;
+            let origin = snap_spawner_pos(*pointer_pos, editor_data.spawner_grid_size);
+            let layers = &editor_data.layers;
+            if editor_data.copied_spawners.len() > 1 {
+                if editor_data.copied_spawners.iter().all(|spawner| layers.is_spawner_editable(&spawner.type_data)) {
+                    editor_data.history.label("Paste Spawners");
+                    let copied = editor_data.copied_spawners.clone();
+                    paste_spawners(level_data, editor_data, &copied, origin);
+                } else {
+                    log::warn!("Can't paste spawners into a hidden or locked layer");
+                }
+            } else if layers.is_spawner_editable(&editor_data.spawner_template.type_data) {
+                editor_data.history.label("Paste Spawner");
+                let mut spawner = editor_data.spawner_template.clone();
+                spawner.pos = origin;
+                level_data.spawners.push(spawner);
+                editor_data.select_spawner(level_data.spawners.len() - 1);
             }
-            level_data.spawners.push(spawner);
-            editor_data.selected_spawner = Some(level_data.spawners.len() - 1);
         }
         populated |= self.right_clicked_spawner_idx != None;
+        populated |= editor_data.selected_spawners.len() > 1;
         self.hovered_on_context_menu = false;
         if populated {
             let response = response.clone().context_menu(|ui| {
                 if let Some(idx) = self.right_clicked_spawner_idx {
-                    if ui.button("Delete spawner").clicked() {
-                        editor_data
-                            .cells_history
-                            .push((level_data.cells.clone(), level_data.spawners.clone()));
-                        editor_data.selected_spawner = None;
+                    let group = editor_data.selected_spawners.len() > 1 && editor_data.selected_spawners.contains(&idx);
+                    if group {
+                        if ui.button(format!("Delete {} spawners", editor_data.selected_spawners.len())).clicked() {
+                            delete_selected = true;
+                            ui.close_menu();
+                        }
+                    } else if ui.button("Delete spawner").clicked() {
+                        editor_data.history.label("Delete Spawner");
+                        editor_data.selected_spawners.clear();
                         self.right_clicked_spawner_idx = None;
                         level_data.spawners.remove(idx);
                         ui.close_menu();
                     }
                 }
-                if let Some(idx) = editor_data.selected_spawner {
+                if editor_data.selected_spawners.len() > 1 {
+                    ui.menu_button("Align", |ui| {
+                        for align in [Align::Left, Align::Right, Align::Top, Align::Bottom, Align::CenterH, Align::CenterV] {
+                            if ui.button(align.get_name()).clicked() {
+                                editor_data.history.label("Align Spawners");
+                                align_spawners(level_data, &editor_data.selected_spawners, align);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    ui.add_enabled_ui(editor_data.selected_spawners.len() > 2, |ui| {
+                        ui.menu_button("Distribute", |ui| {
+                            if ui.button("Horizontally").clicked() {
+                                editor_data.history.label("Distribute Spawners");
+                                distribute_spawners(level_data, &editor_data.selected_spawners, true);
+                                ui.close_menu();
+                            }
+                            if ui.button("Vertically").clicked() {
+                                editor_data.history.label("Distribute Spawners");
+                                distribute_spawners(level_data, &editor_data.selected_spawners, false);
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                }
+                if let Some(idx) = editor_data.get_selected_spawner() {
                     let spawner = &mut level_data.spawners[idx];
                     match &mut spawner.type_data {
                         WobjType::Teleport(
@@ -1300,9 +1712,7 @@ impl WorldPanel {
                         | WobjType::TeleportArea1 { loc, .. }
                         | WobjType::TeleportArea2 { loc, .. } => {
                             if ui.button("Set teleport location").clicked() {
-                                editor_data
-                                    .cells_history
-                                    .push((level_data.cells.clone(), duplicated_spawners.clone()));
+                                editor_data.history.label("Set Teleport Location");
                                 loc.0 = pointer_pos.x;
                                 loc.1 = pointer_pos.y;
                                 ui.close_menu();
@@ -1319,12 +1729,238 @@ impl WorldPanel {
             self.close_context_menu = response.lost_focus();
             self.hovered_on_context_menu = response.hovered();
         }
-        if let Some(idx) = delete_idx {
-            editor_data
-                .cells_history
-                .push((level_data.cells.clone(), level_data.spawners.clone()));
-            level_data.spawners.remove(idx);
-            editor_data.selected_spawner = None;
+        if delete_selected {
+            editor_data.history.label("Delete Spawners");
+            let mut indices = std::mem::take(&mut editor_data.selected_spawners);
+            indices.sort_unstable();
+            indices.dedup();
+            for idx in indices.into_iter().rev() {
+                level_data.spawners.remove(idx);
+            }
+            self.right_clicked_spawner_idx = None;
+        }
+    }
+}
+
+/// Where a drag in the spawner tool started and what it's doing
+enum SpawnerDrag {
+    /// Moving the selected spawners, with their positions before the drag
+    Move {
+        start: egui::Pos2,
+        origins: Vec<(usize, level_data::Point)>,
+    },
+    /// Selecting every spawner touching a box, added to the selection if shift was held
+    Box { start: egui::Pos2, additive: bool },
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CenterH,
+    CenterV,
+}
+
+impl Align {
+    fn get_name(&self) -> &'static str {
+        match self {
+            Align::Left => "Left",
+            Align::Right => "Right",
+            Align::Top => "Top",
+            Align::Bottom => "Bottom",
+            Align::CenterH => "Center Horizontally",
+            Align::CenterV => "Center Vertically",
+        }
+    }
+}
+
+/// The tile or light level the shape and replace tools put on a layer
+#[derive(Clone, Copy, PartialEq)]
+enum Paint {
+    Foreground(TileId),
+    Background(TileId),
+    Light(u8),
+}
+
+impl Paint {
+    fn get_layer(&self) -> Layer {
+        match self {
+            Paint::Foreground(_) => Layer::Foreground,
+            Paint::Background(_) => Layer::Background,
+            Paint::Light(_) => Layer::Light,
+        }
+    }
+
+    /// What the cell has on the same layer
+    fn read(&self, cell: &Cell) -> Paint {
+        match self {
+            Paint::Foreground(_) => Paint::Foreground(cell.foreground),
+            Paint::Background(_) => Paint::Background(cell.background),
+            Paint::Light(_) => Paint::Light(cell.light),
+        }
+    }
+
+    fn apply(&self, cell: &mut Cell) {
+        match *self {
+            Paint::Foreground(tile) => cell.foreground = tile,
+            Paint::Background(tile) => cell.background = tile,
+            Paint::Light(light) => cell.light = light,
+        }
+    }
+}
+
+/// Like the brush and fill, the primary button paints the selected layer with
+/// the first selected tile and the secondary paints the other layer. With
+/// light the secondary button resets the light level instead.
+fn get_paint(editor_data: &EditorData, primary: bool) -> Paint {
+    if editor_data.shape_on_light {
+        return Paint::Light(if primary { editor_data.light_tool_level } else { level_data::consts::LIGHT_NORMAL });
+    }
+    let tile = TileId(editor_data.selected_tiles.first().map(|id| *id as u16));
+    if editor_data.foreground_placing == primary {
+        Paint::Foreground(tile)
+    } else {
+        Paint::Background(tile)
+    }
+}
+
+fn draw_paint_preview(sprites: &mut Vec<Sprite>, level_data: &level_data::LevelData, editor_data: &EditorData, paint: Paint, cells: &[(i32, i32)]) {
+    for (x, y) in cells.iter().copied() {
+        let pos = (x as f32 * 32.0, y as f32 * 32.0, 0.0);
+        match paint {
+            Paint::Foreground(TileId(Some(id))) | Paint::Background(TileId(Some(id))) => {
+                let props = &level_data.tile_properties[id as usize];
+                let idx = editor_data.get_tile_frame(props);
+                let mut sprite = Sprite::new(
+                    pos,
+                    (32.0, 32.0),
+                    (
+                        props.frames[idx].0 as f32 * 32.0,
+                        props.frames[idx].1 as f32 * 32.0,
+                        32.0,
+                        32.0,
+                    ),
+                );
+                sprite.tint = [0.8, 0.8, 0.8, 0.7];
+                sprites.push(sprite);
+            }
+            // Erasing has nothing to show, so mark the cells instead
+            Paint::Foreground(TileId(None)) | Paint::Background(TileId(None)) => {
+                sprites.push(Sprite::new_pure_color(pos, (32.0, 32.0), (1.0, 0.2, 0.2, 0.4)));
+            }
+            Paint::Light(light) => draw_light_preview(sprites, &[((x, y), light)]),
+        }
+    }
+}
+
+fn draw_light_preview(sprites: &mut Vec<Sprite>, changes: &[LightChange]) {
+    for ((x, y), light) in changes.iter().copied() {
+        let pos = (x as f32 * 32.0, y as f32 * 32.0, 0.0);
+        sprites.push(Sprite::new_pure_color(pos, (32.0, 32.0), get_light_tint(light)));
+        // Normal light has no tint, so the cells are highlighted too
+        sprites.push(Sprite::new_pure_color(pos, (32.0, 32.0), (1.0, 1.0, 0.0, 0.2)));
+    }
+}
+
+/// Lighter than normal is drawn as white over the tile and darker as black
+fn get_light_tint(light: u8) -> (f32, f32, f32, f32) {
+    use level_data::consts::*;
+    let color = if light < LIGHT_NORMAL { 1.0 } else { 0.0 };
+    let percent = if light < LIGHT_NORMAL {
+        1.0 - (light as f32 / LIGHT_NORMAL as f32)
+    } else if light > LIGHT_NORMAL {
+        (light - LIGHT_NORMAL) as f32 / (LIGHT_BLACK - LIGHT_NORMAL) as f32
+    } else {
+        0.0
+    };
+    (color, color, color, percent)
+}
+
+fn snap_spawner_pos(pos: egui::Pos2, grid_size: f32) -> level_data::Point {
+    if grid_size > 1.0 {
+        level_data::Point((pos.x / grid_size).round() * grid_size, (pos.y / grid_size).round() * grid_size)
+    } else {
+        level_data::Point(pos.x, pos.y)
+    }
+}
+
+/// Makes the positions relative to the top left most spawner position
+fn relative_to_top_left(mut spawners: Vec<Spawner>) -> Vec<Spawner> {
+    let min_x = spawners.iter().map(|spawner| spawner.pos.0).fold(f32::INFINITY, f32::min);
+    let min_y = spawners.iter().map(|spawner| spawner.pos.1).fold(f32::INFINITY, f32::min);
+    for spawner in spawners.iter_mut() {
+        spawner.pos.0 -= min_x;
+        spawner.pos.1 -= min_y;
+    }
+    spawners
+}
+
+/// Adds the spawners offset by `origin` and selects them
+fn paste_spawners(level_data: &mut level_data::LevelData, editor_data: &mut EditorData, spawners: &[Spawner], origin: level_data::Point) {
+    editor_data.selected_spawners.clear();
+    for spawner in spawners {
+        let mut spawner = spawner.clone();
+        spawner.pos.0 += origin.0;
+        spawner.pos.1 += origin.1;
+        level_data.spawners.push(spawner);
+        editor_data.selected_spawners.push(level_data.spawners.len() - 1);
+    }
+}
+
+fn align_spawners(level_data: &mut level_data::LevelData, selected: &[usize], align: Align) {
+    let bounds = selected.iter().fold(
+        (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        |bounds, idx| {
+            let spawner = &level_data.spawners[*idx];
+            let size = get_spawner_size(spawner);
+            (
+                bounds.0.min(spawner.pos.0),
+                bounds.1.min(spawner.pos.1),
+                bounds.2.max(spawner.pos.0 + size.0),
+                bounds.3.max(spawner.pos.1 + size.1),
+            )
+        },
+    );
+    for idx in selected {
+        let spawner = &mut level_data.spawners[*idx];
+        let size = get_spawner_size(spawner);
+        match align {
+            Align::Left => spawner.pos.0 = bounds.0,
+            Align::Right => spawner.pos.0 = bounds.2 - size.0,
+            Align::Top => spawner.pos.1 = bounds.1,
+            Align::Bottom => spawner.pos.1 = bounds.3 - size.1,
+            Align::CenterH => spawner.pos.0 = (bounds.0 + bounds.2 - size.0) / 2.0,
+            Align::CenterV => spawner.pos.1 = (bounds.1 + bounds.3 - size.1) / 2.0,
+        }
+    }
+}
+
+/// Spaces out the centers of the spawners evenly between the first and last one
+fn distribute_spawners(level_data: &mut level_data::LevelData, selected: &[usize], horizontal: bool) {
+    let center = |spawner: &Spawner| {
+        let size = get_spawner_size(spawner);
+        if horizontal {
+            spawner.pos.0 + size.0 / 2.0
+        } else {
+            spawner.pos.1 + size.1 / 2.0
+        }
+    };
+    let mut sorted = selected.to_vec();
+    sorted.sort_by(|a, b| center(&level_data.spawners[*a]).total_cmp(&center(&level_data.spawners[*b])));
+    let (Some(first), Some(last)) = (sorted.first(), sorted.last()) else {
+        return;
+    };
+    let start = center(&level_data.spawners[*first]);
+    let step = (center(&level_data.spawners[*last]) - start) / (sorted.len() - 1).max(1) as f32;
+    for (i, idx) in sorted.iter().enumerate() {
+        let spawner = &mut level_data.spawners[*idx];
+        let offset = start + step * i as f32 - center(spawner);
+        if horizontal {
+            spawner.pos.0 += offset;
+        } else {
+            spawner.pos.1 += offset;
         }
     }
 }
@@ -1409,6 +2045,17 @@ fn get_spawner_size(spawner: &cnmo_parse::lparse::level_data::cnms_types::Spawne
         _ => (32.0, 32.0),
     }
 }
+/// Draws a faded copy of a platform where it ends up, `offset` from the spawner
+fn draw_ghost(sprites: &mut Vec<Sprite>, spawner: &Spawner, offset: (f32, f32), src: (i32, i32)) {
+    let mut sprite = Sprite::new(
+        (spawner.pos.0 + offset.0, spawner.pos.1 + offset.1, 0.0),
+        (32.0, 32.0),
+        (src.0 as f32, src.1 as f32, 32.0, 32.0),
+    );
+    sprite.tint[3] = 0.35;
+    sprites.push(sprite);
+}
+
 fn draw_spawner(
     sprites: &mut Vec<Sprite>,
     spawner: &cnmo_parse::lparse::level_data::cnms_types::Spawner,
@@ -1436,7 +2083,7 @@ fn draw_spawner(
     };
     let draw_moving = |sprites: &mut Vec<Sprite>, dist: f32, speed: f32, vertical: bool| {
         let dist = if dist > -0.01 && dist < 0.01 { f32::EPSILON } else { dist };
-        let unclamped_pos = editor_data.time_past.as_secs_f32() * (speed * dist.signum() * 30.0);
+        let unclamped_pos = editor_data.preview_time.as_secs_f32() * (speed * dist.signum() * level_data::consts::FRAME_RATE as f32);
         let pos = if (unclamped_pos / dist.abs()) as i32 % 2 == 0 {
             unclamped_pos.rem_euclid(dist.abs())
         } else {
@@ -1547,13 +2194,14 @@ fn draw_spawner(
             bitmapy,
             jumpthrough: _,
         } => {
-            draw_rect(
+            let src = (
                 if *bitmapx == 0 && *bitmapy == 0 { 9 } else { *bitmapx as i32 } * 32,
                 if *bitmapy == 0 && *bitmapy == 0 { 5 } else { *bitmapy as i32 } * 32,
-                32,
-                32
             );
+            draw_rect(src.0, src.1, 32, 32);
             draw_moving(sprites, *dist, *speed, *vertical);
+            let end = if *vertical { (0.0, *dist) } else { (*dist, 0.0) };
+            draw_ghost(sprites, spawner, end, src);
         }
         WobjType::MovingFire {
             vertical,
@@ -1730,11 +2378,11 @@ fn draw_spawner(
             let dist = spawner.pos.0 - *origin_x as f32;
             let pos_x = *origin_x as f32
                 + dist
-                    * ((editor_data.time_past.as_secs_f32() * *degrees_per_second).to_radians())
+                    * ((editor_data.preview_time.as_secs_f32() * *degrees_per_second).to_radians())
                         .cos();
             let pos_y = spawner.pos.1
                 + dist
-                    * -((editor_data.time_past.as_secs_f32() * *degrees_per_second).to_radians())
+                    * -((editor_data.preview_time.as_secs_f32() * *degrees_per_second).to_radians())
                         .sin();
             sprites.append(
                 &mut Sprite::new_rect(
@@ -1769,7 +2417,7 @@ fn draw_spawner(
                 (256.0, 0.0, 32.0, 32.0),
             );
             let time = editor_data
-                .time_past
+                .preview_time
                 .as_secs_f32()
                 .rem_euclid(*time_on + *time_off);
             if *time_on + *time_off > f32::EPSILON {
@@ -1862,6 +2510,7 @@ fn draw_spawner(
             ..
         } => {
             draw_rect(bitmap_x32.0 as i32 * 32, bitmap_x32.1 as i32 * 32, 32, 32);
+            draw_ghost(sprites, spawner, (target_relative.0, target_relative.1), (bitmap_x32.0 as i32 * 32, bitmap_x32.1 as i32 * 32));
             sprites.append(
                 &mut Sprite::new_rect(
                     (
@@ -1878,7 +2527,7 @@ fn draw_spawner(
                 .to_vec(),
             );
             let dist = (target_relative.0.powi(2) + target_relative.1.powi(2)).sqrt();
-            let unclamped_pos = editor_data.time_past.as_secs_f32() * (*speed * 30.0);
+            let unclamped_pos = editor_data.preview_time.as_secs_f32() * (*speed * level_data::consts::FRAME_RATE as f32);
             let lin_pos = if (unclamped_pos / dist) as i32 % 2 == 0 {
                 (unclamped_pos.rem_euclid(dist)) / dist.max(f32::EPSILON)
             } else {
@@ -1933,11 +2582,12 @@ fn draw_spawner(
                 (32.0, 32.0),
                 (256.0, 0.0, 32.0, 32.0),
             );
+            let frame_rate = level_data::consts::FRAME_RATE as f32;
             let time = editor_data
-                .time_past
+                .preview_time
                 .as_secs_f32()
-                .rem_euclid((*time_off_before as f32 / 30.0) + (*time_on as f32 / 30.0) + (*time_off_after as f32 / 30.0));
-            if time > (*time_off_after) as f32 / 30.0 && time < (*time_off_after + *time_on) as f32 / 30.0 {
+                .rem_euclid((*time_off_before as f32 / frame_rate) + (*time_on as f32 / frame_rate) + (*time_off_after as f32 / frame_rate));
+            if time > (*time_off_after) as f32 / frame_rate && time < (*time_off_after + *time_on) as f32 / frame_rate {
                 sprite.tint = [1.0, 1.0, 1.0, 1.0];
             } else {
                 sprite.tint = [0.5, 0.5, 0.5, 0.5];
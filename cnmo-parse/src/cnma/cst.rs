@@ -0,0 +1,499 @@
+use super::diagnostic::{Diagnostic, LineParser, ParseOptions, Severity};
+use super::{
+    is_trivia, max_power_fields, mode_entry_lines, mode_footer, mode_header, parse_mode_entry,
+    parse_mode_header, Cnma, Error, Mode,
+};
+
+/// A single line inside of a section, kept exactly how it was written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionLine {
+    /// A blank line or comment, these don't change the parsed config.
+    Trivia(String),
+    /// A line that was parsed into the section's mode.
+    Entry(String),
+    /// A line that couldn't be parsed, like an unknown max power field. It's
+    /// left out of the config and written back exactly as it was.
+    Unknown(String),
+}
+
+/// One `MODE` section of a cnma file along with the lines around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Blank lines and comments before the header of this section that
+    /// weren't inside of another section.
+    pub leading: Vec<String>,
+    /// The `MODE` line as it was written.
+    pub header: String,
+    /// Every line between the header and the footer (or the next section).
+    pub lines: Vec<SectionLine>,
+    /// The `__ENDLUA__` or `ENDPETS` line for sections that have one.
+    pub footer: Option<String>,
+    /// What the section was parsed into.
+    pub mode: Mode,
+}
+
+/// A cnma file that remembers its comments, blank lines, line endings and
+/// the exact text of every entry. Writing a changed [`Cnma`] back through
+/// the document only touches the lines of the entries that changed, so
+/// hand edited files stay readable and diffs stay small.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CnmaDocument {
+    /// The sections in the order they appear in the file.
+    pub sections: Vec<Section>,
+    /// Blank lines and comments after the last section.
+    pub trailing: Vec<String>,
+    /// Either `"\n"` or `"\r\n"`, whatever the file used.
+    pub newline: &'static str,
+    /// If the last line of the file ended with a newline.
+    pub trailing_newline: bool,
+}
+
+impl Default for CnmaDocument {
+    fn default() -> Self {
+        Self {
+            sections: vec![],
+            trailing: vec![],
+            newline: "\n",
+            trailing_newline: true,
+        }
+    }
+}
+
+impl CnmaDocument {
+    /// Load a document from a file
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let s = match std::fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::CantOpenFile { source: e }),
+        };
+        Self::from_string(s.as_str())
+    }
+
+    /// Load a document from a string
+    pub fn from_string(s: &str) -> Result<Self, Error> {
+        Ok(Self::from_string_with_options(s, ParseOptions::default())?.0)
+    }
+
+    /// Load a document from a file, also returning the warnings found while parsing
+    pub fn from_file_with_options<P: AsRef<std::path::Path>>(path: P, options: ParseOptions) -> Result<(Self, Vec<Diagnostic>), Error> {
+        let s = match std::fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::CantOpenFile { source: e }),
+        };
+        Self::from_string_with_options(s.as_str(), options)
+    }
+
+    /// Load a document from a string, also returning the warnings found while parsing
+    pub fn from_string_with_options(s: &str, options: ParseOptions) -> Result<(Self, Vec<Diagnostic>), Error> {
+        let mut warnings = Vec::new();
+        let mut doc = CnmaDocument {
+            newline: if s.contains("\r\n") { "\r\n" } else { "\n" },
+            trailing_newline: s.ends_with('\n'),
+            ..Default::default()
+        };
+        let mut current: Option<Section> = None;
+        let mut pending = Vec::new();
+        let mut mode_locked = false;
+
+        for (line_num, line) in s.lines().enumerate() {
+            let mut parser = LineParser::new(line_num, line, options, &mut warnings);
+            if line.starts_with("MODE") && !mode_locked {
+                if let Some(section) = current.take() {
+                    doc.push_section(section);
+                }
+                let mode = parse_mode_header(&mut parser)?;
+                mode_locked = matches!(mode, Mode::LuaAutorunCode(_) | Mode::PetDefs(_));
+                current = Some(Section {
+                    leading: std::mem::take(&mut pending),
+                    header: line.to_string(),
+                    lines: vec![],
+                    footer: None,
+                    mode,
+                });
+                continue;
+            } else if line.starts_with("__ENDLUA__") && mode_locked {
+                match current.take() {
+                    Some(mut section) if matches!(section.mode, Mode::LuaAutorunCode(_)) => {
+                        mode_locked = false;
+                        section.footer = Some(line.to_string());
+                        doc.push_section(section);
+                    }
+                    _ => {
+                        return Err(Error::Corrupted(
+                            "__ENDLUA__ found outside of LUA_AUTORUN mode segment!".to_string(),
+                        ))
+                    }
+                }
+                continue;
+            } else if line.starts_with("ENDPETS") && mode_locked {
+                match current.take() {
+                    Some(mut section) if matches!(section.mode, Mode::PetDefs(_)) => {
+                        mode_locked = false;
+                        section.footer = Some(line.to_string());
+                        doc.push_section(section);
+                    }
+                    _ => {
+                        return Err(Error::Corrupted(
+                            "ENDPETS found outside of PETDEFS mode segment!".to_string(),
+                        ))
+                    }
+                }
+                continue;
+            }
+
+            match current.as_mut() {
+                Some(section) => match section.mode {
+                    Mode::LuaAutorunCode(_) => {
+                        parse_mode_entry(&mut section.mode, &mut parser)?;
+                        section.lines.push(SectionLine::Entry(line.to_string()));
+                    }
+                    Mode::MusicVolumeOverride => {
+                        section.lines.push(SectionLine::Trivia(line.to_string()))
+                    }
+                    _ if is_trivia(line) => {
+                        section.lines.push(SectionLine::Trivia(line.to_string()))
+                    }
+                    Mode::PetDefs(_) => {
+                        let mut parser = LineParser::new_space_separated(line_num, line, options, &mut warnings);
+                        let result = parse_mode_entry(&mut section.mode, &mut parser);
+                        section.lines.push(entry_or_unknown(result, line, options, &mut warnings)?);
+                    }
+                    _ => {
+                        let result = parse_mode_entry(&mut section.mode, &mut parser);
+                        section.lines.push(entry_or_unknown(result, line, options, &mut warnings)?);
+                    }
+                },
+                None if is_trivia(line) => pending.push(line.to_string()),
+                None => {
+                    let error = parser.error(
+                        0..line.len(),
+                        "entry outside of a mode section",
+                        "expected a `MODE <name>` line before any entries",
+                    );
+                    // Kept with the comments before the next section so it's written back
+                    entry_or_unknown(Err(error), line, options, &mut warnings)?;
+                    pending.push(line.to_string());
+                }
+            }
+        }
+
+        if let Some(section) = current.take() {
+            doc.push_section(section);
+        }
+        doc.trailing = pending;
+
+        Ok((doc, warnings))
+    }
+
+    fn push_section(&mut self, mut section: Section) {
+        if let Mode::LevelSelectOrder(ref mut vec) = section.mode {
+            vec.reverse();
+        }
+        self.sections.push(section);
+    }
+
+    /// The config that the document currently holds
+    pub fn cnma(&self) -> Cnma {
+        Cnma {
+            modes: self.sections.iter().map(|section| section.mode.clone()).collect(),
+        }
+    }
+
+    /// Find which section of the document a mode of the config was loaded from.
+    /// Prefers a section of the same kind at the same position, then one that
+    /// is exactly the same, then any section of the same kind.
+    fn find_section(&self, idx: usize, mode: &Mode, used: &[bool]) -> Option<usize> {
+        let same_kind = |other: &Mode| match (other, mode) {
+            (Mode::MaxPowerDef(a), Mode::MaxPowerDef(b)) => a.id == b.id,
+            _ => std::mem::discriminant(other) == std::mem::discriminant(mode),
+        };
+
+        if self.sections.get(idx).is_some_and(|section| {
+            !used[idx] && std::mem::discriminant(&section.mode) == std::mem::discriminant(mode)
+        }) {
+            return Some(idx);
+        }
+        (0..self.sections.len())
+            .find(|i| !used[*i] && self.sections[*i].mode == *mode)
+            .or_else(|| (0..self.sections.len()).find(|i| !used[*i] && same_kind(&self.sections[*i].mode)))
+    }
+
+    /// Writes the config out as text, reusing the original text of every line
+    /// that didn't change. Sections that aren't in the document are written
+    /// the same way as [`Cnma::save`] does.
+    pub fn write(&self, cnma: &Cnma) -> String {
+        let mut lines = Vec::new();
+        let mut used = vec![false; self.sections.len()];
+
+        for (idx, mode) in cnma.modes.iter().enumerate() {
+            match self.find_section(idx, mode, &used) {
+                Some(section_idx) => {
+                    used[section_idx] = true;
+                    self.sections[section_idx].write(mode, &mut lines);
+                }
+                None => {
+                    lines.push(mode_header(mode));
+                    lines.extend(mode_entry_lines(mode));
+                    if let Some(footer) = mode_footer(mode) {
+                        lines.push(footer.to_string());
+                    }
+                }
+            }
+        }
+        lines.extend(self.trailing.iter().cloned());
+
+        let mut contents = lines.join(self.newline);
+        if self.trailing_newline && !lines.is_empty() {
+            contents.push_str(self.newline);
+        }
+        contents
+    }
+
+    /// Writes the config to the path specified and updates the document to
+    /// match what was written, so later saves are compared against it.
+    pub fn save<P: AsRef<std::path::Path>>(&mut self, cnma: &Cnma, path: P) -> Result<(), Error> {
+        let contents = self.write(cnma);
+        match std::fs::write(path, contents.as_str()) {
+            Err(e) => Err(Error::CantOpenFile { source: e }),
+            _ => {
+                *self = Self::from_string(contents.as_str())?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Section {
+    fn write(&self, mode: &Mode, lines: &mut Vec<String>) {
+        lines.extend(self.leading.iter().cloned());
+        if mode_header(&self.mode) == mode_header(mode) {
+            lines.push(self.header.clone());
+        } else {
+            lines.push(mode_header(mode));
+        }
+
+        match (&self.mode, mode) {
+            (Mode::MaxPowerDef(old), Mode::MaxPowerDef(new)) => {
+                let old_fields = max_power_fields(old);
+                let new_fields = max_power_fields(new);
+                let mut written = [false; 6];
+                let last_entry = self.lines.iter().rposition(|line| matches!(line, SectionLine::Entry(_)));
+
+                for (line_idx, line) in self.lines.iter().enumerate() {
+                    match line {
+                        SectionLine::Trivia(text) | SectionLine::Unknown(text) => lines.push(text.clone()),
+                        SectionLine::Entry(text) => {
+                            let name = text.split_whitespace().next().unwrap_or_default();
+                            match new_fields.iter().position(|(field, _)| *field == name) {
+                                Some(field) => {
+                                    written[field] = true;
+                                    if old_fields[field].1 == new_fields[field].1 {
+                                        lines.push(text.clone());
+                                    } else {
+                                        lines.push(format!("{name} {}", new_fields[field].1));
+                                    }
+                                }
+                                None => lines.push(text.clone()),
+                            }
+                        }
+                    }
+                    if last_entry.map_or(line_idx + 1 == self.lines.len(), |last| last == line_idx) {
+                        for (field, (name, value)) in new_fields.iter().enumerate() {
+                            if !written[field] && old_fields[field].1 != *value {
+                                lines.push(format!("{name} {value}"));
+                            }
+                        }
+                    }
+                }
+                if self.lines.is_empty() {
+                    lines.extend(
+                        new_fields
+                            .iter()
+                            .zip(old_fields.iter())
+                            .filter(|(new, old)| new.1 != old.1)
+                            .map(|((name, value), _)| format!("{name} {value}")),
+                    );
+                }
+            }
+            _ => {
+                let old_text: Vec<&String> = self
+                    .lines
+                    .iter()
+                    .filter_map(|line| match line {
+                        SectionLine::Entry(text) => Some(text),
+                        SectionLine::Trivia(_) | SectionLine::Unknown(_) => None,
+                    })
+                    .collect();
+                let mut old_entries = mode_entry_lines(&self.mode);
+                if old_entries.len() != old_text.len() {
+                    old_entries = old_text.iter().map(|text| text.to_string()).collect();
+                }
+                let new_entries = mode_entry_lines(mode);
+                let (keep, inserts) = diff_lines(&old_entries, &new_entries);
+
+                if old_text.is_empty() {
+                    lines.extend(inserts[0].iter().cloned());
+                }
+                let mut entry_idx = 0;
+                for line in self.lines.iter() {
+                    match line {
+                        SectionLine::Trivia(text) | SectionLine::Unknown(text) => lines.push(text.clone()),
+                        SectionLine::Entry(text) => {
+                            lines.extend(inserts[entry_idx].iter().cloned());
+                            if keep[entry_idx] {
+                                lines.push(text.clone());
+                            }
+                            entry_idx += 1;
+                            if entry_idx == old_text.len() {
+                                lines.extend(inserts[entry_idx].iter().cloned());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(footer) = mode_footer(mode) {
+            lines.push(self.footer.clone().unwrap_or_else(|| footer.to_string()));
+        }
+    }
+}
+
+/// Turns the result of parsing an entry into its line. Lines that couldn't be
+/// parsed are kept as they are with a warning, unless the parsing is strict.
+fn entry_or_unknown(
+    result: Result<(), Error>,
+    line: &str,
+    options: ParseOptions,
+    warnings: &mut Vec<Diagnostic>,
+) -> Result<SectionLine, Error> {
+    match result {
+        Ok(()) => Ok(SectionLine::Entry(line.to_string())),
+        Err(Error::Diagnostic(diagnostic)) if !options.strict => {
+            warnings.push(Diagnostic {
+                severity: Severity::Warning,
+                message: format!("{}, the line is kept but ignored", diagnostic.message),
+                ..*diagnostic
+            });
+            Ok(SectionLine::Unknown(line.to_string()))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Line diff between the old and new entries of a section using their longest
+/// common subsequence. Returns which old lines are kept and the new lines that
+/// have to be inserted before each old line (the last list goes after all of them).
+fn diff_lines(old: &[String], new: &[String]) -> (Vec<bool>, Vec<Vec<String>>) {
+    let mut keep = vec![false; old.len()];
+    let mut inserts = vec![Vec::new(); old.len() + 1];
+
+    // Strip the common start and end so big unchanged lua scripts stay cheap
+    let prefix = old.iter().zip(new.iter()).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    keep[..prefix].iter_mut().for_each(|k| *k = true);
+    keep[old.len() - suffix..].iter_mut().for_each(|k| *k = true);
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let width = new_mid.len() + 1;
+    let mut lcs = vec![0u32; (old_mid.len() + 1) * width];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i * width + j] = if old_mid[i] == new_mid[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            keep[prefix + i] = true;
+            i += 1;
+            j += 1;
+        } else if j < new_mid.len()
+            && (i == old_mid.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j])
+        {
+            inserts[prefix + i].push(new_mid[j].clone());
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    (keep, inserts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "# Audio for the main game\n\
+        MODE MUSIC\n\
+        0 music/title.mid\n\
+        \n\
+        # Boss music\n\
+        1   music/boss.mid\n\
+        MODE MAXPOWER3\n\
+        spd 1.5\n\
+        sparkle 2\n\
+        grav 0.5\n\
+        MODE LEVELSELECT_ORDER\n\
+        lvl1 1000\n\
+        lvl2 _\n\
+        MODE LUA_AUTORUN\n\
+        -- comments stay in the code\n\
+        \n\
+        print(\"hi\")\n\
+        __ENDLUA__\n\
+        \n\
+        # The end\n";
+
+    #[test]
+    fn round_trip_is_byte_for_byte() {
+        let (doc, warnings) = CnmaDocument::from_string_with_options(CONFIG, ParseOptions::default()).unwrap();
+        assert_eq!(doc.write(&doc.cnma()), CONFIG);
+        assert_eq!(warnings.len(), 1);
+
+        let crlf = CONFIG.replace('\n', "\r\n");
+        let doc = CnmaDocument::from_string(&crlf).unwrap();
+        assert_eq!(doc.write(&doc.cnma()), crlf);
+
+        let no_newline = CONFIG.trim_end();
+        let doc = CnmaDocument::from_string(no_newline).unwrap();
+        assert_eq!(doc.write(&doc.cnma()), no_newline);
+    }
+
+    #[test]
+    fn unknown_lines_are_kept_with_a_warning() {
+        let text = "stray line\nMODE MAXPOWER3\nspd 1.5\nsparkle 2\n";
+        let (doc, warnings) = CnmaDocument::from_string_with_options(text, ParseOptions::default()).unwrap();
+        assert_eq!(doc.write(&doc.cnma()), text);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|warning| warning.severity == Severity::Warning));
+        assert_eq!(warnings[0].line, 1);
+        assert_eq!(warnings[1].line, 4);
+        assert_eq!(doc.sections[0].lines[1], SectionLine::Unknown("sparkle 2".to_string()));
+
+        let strict = ParseOptions { strict: true };
+        assert!(CnmaDocument::from_string_with_options(text, strict).is_err());
+    }
+
+    #[test]
+    fn changes_keep_unknown_lines_and_comments() {
+        let doc = CnmaDocument::from_string(CONFIG).unwrap();
+        let mut cnma = doc.cnma();
+        if let Mode::MaxPowerDef(def) = &mut cnma.modes[1] {
+            def.speed = 2.0;
+        }
+        assert_eq!(doc.write(&cnma), CONFIG.replace("spd 1.5", "spd 2"));
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use super::Error;
+
+/// How bad a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The file can't be loaded.
+    Error,
+    /// The file loaded but something was guessed, like a number that
+    /// couldn't be read falling back to its default.
+    Warning,
+}
+
+/// A problem found while parsing a cnma file, pointing at the exact text
+/// that caused it. Displaying it gives a rustc style report:
+///
+/// ```text
+/// warning: "fast" isn't a valid number, using 0 instead
+///   --> line 12:5
+///    |
+/// 12 | spd fast
+///    |     ^^^^
+///    = help: expected the speed multiplier, like `spd 1.5`
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// If this stops the file from loading or not
+    pub severity: Severity,
+    /// The line number, starting at 1
+    pub line: usize,
+    /// The byte range in the line that the diagnostic points to
+    pub columns: Range<usize>,
+    /// The full text of the line
+    pub source: String,
+    /// What went wrong
+    pub message: String,
+    /// What was expected to be there instead
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// Create a new error. `line_num` starts at 0 like the index from `lines().enumerate()`.
+    pub fn error(line_num: usize, source: &str, columns: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            line: line_num + 1,
+            columns,
+            source: source.to_string(),
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    /// Create a new warning. `line_num` starts at 0 like the index from `lines().enumerate()`.
+    pub fn warning(line_num: usize, source: &str, columns: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(line_num, source, columns, message)
+        }
+    }
+
+    /// Adds a hint about the expected form of the text
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let start = self.columns.start.min(self.source.len());
+        let end = self.columns.end.clamp(start, self.source.len());
+        let pad_width = self.source.get(..start).map_or(start, |s| s.chars().count());
+        let caret_width = self.source.get(start..end).map_or(1, |s| s.chars().count()).max(1);
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        match self.severity {
+            Severity::Error => writeln!(f, "error: {}", self.message)?,
+            Severity::Warning => writeln!(f, "warning: {}", self.message)?,
+        }
+        writeln!(f, "{gutter}--> line {}:{}", self.line, pad_width + 1)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.source)?;
+        write!(f, "{gutter} | {}{}", " ".repeat(pad_width), "^".repeat(caret_width))?;
+        if let Some(hint) = &self.hint {
+            write!(f, "\n{gutter} = help: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Settings for loading cnma files
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Numbers that can't be read are errors instead of warnings that fall
+    /// back to a default value, and so are lines that can't be parsed at all
+    /// instead of being kept as [`SectionLine::Unknown`](super::cst::SectionLine::Unknown).
+    pub strict: bool,
+}
+
+/// Splits up one line of a file into tokens that remember where they are,
+/// and turns problems with them into diagnostics.
+pub(crate) struct LineParser<'a> {
+    pub line_num: usize,
+    pub line: &'a str,
+    tokens: Vec<(Range<usize>, &'a str)>,
+    options: ParseOptions,
+    warnings: &'a mut Vec<Diagnostic>,
+}
+
+impl<'a> LineParser<'a> {
+    /// Tokens are split by any whitespace
+    pub fn new(line_num: usize, line: &'a str, options: ParseOptions, warnings: &'a mut Vec<Diagnostic>) -> Self {
+        let mut tokens = Vec::new();
+        let mut start = None;
+        for (idx, c) in line.char_indices().chain(std::iter::once((line.len(), ' '))) {
+            match (start, c.is_whitespace()) {
+                (None, false) => start = Some(idx),
+                (Some(s), true) => {
+                    tokens.push((s..idx, &line[s..idx]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        Self { line_num, line, tokens, options, warnings }
+    }
+
+    /// Tokens are split by every single space, so 2 spaces in a row make an empty token
+    pub fn new_space_separated(line_num: usize, line: &'a str, options: ParseOptions, warnings: &'a mut Vec<Diagnostic>) -> Self {
+        let mut tokens = Vec::new();
+        let mut start = 0;
+        for word in line.split(' ') {
+            tokens.push((start..start + word.len(), word));
+            start += word.len() + 1;
+        }
+        Self { line_num, line, tokens, options, warnings }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// The span of a token, or the end of the line if it's missing
+    pub fn span(&self, idx: usize) -> Range<usize> {
+        self.tokens
+            .get(idx)
+            .map(|(span, _)| span.clone())
+            .unwrap_or(self.line.len()..self.line.len() + 1)
+    }
+
+    pub fn error(&self, columns: Range<usize>, message: impl Into<String>, hint: &str) -> Error {
+        Error::Diagnostic(Box::new(
+            Diagnostic::error(self.line_num, self.line, columns, message).with_hint(hint),
+        ))
+    }
+
+    /// Gets a token, erroring if the line doesn't have it
+    pub fn token(&self, idx: usize, what: &str, hint: &str) -> Result<&'a str, Error> {
+        match self.tokens.get(idx) {
+            Some((_, token)) => Ok(token),
+            None => Err(self.error(self.span(idx), format!("missing {what}"), hint)),
+        }
+    }
+
+    /// Parses a number that's required to be valid no matter the options
+    pub fn required_number<T: FromStr>(&self, idx: usize, what: &str, hint: &str) -> Result<T, Error> {
+        let token = self.token(idx, what, hint)?;
+        token
+            .parse()
+            .map_err(|_| self.error(self.span(idx), format!("\"{token}\" isn't a valid {what}"), hint))
+    }
+
+    /// Parses a number, falling back to `default` with a warning if it can't be
+    /// read. In strict mode it errors instead.
+    pub fn number<T: FromStr + Display>(&mut self, idx: usize, default: T, what: &str, hint: &str) -> Result<T, Error> {
+        self.number_from(self.token(idx, what, hint)?, idx, default, what, hint)
+    }
+
+    /// Same as [`LineParser::number`] but with the token text already cleaned up
+    pub fn number_from<T: FromStr + Display>(&mut self, token: &str, idx: usize, default: T, what: &str, hint: &str) -> Result<T, Error> {
+        match token.parse() {
+            Ok(value) => Ok(value),
+            Err(_) if self.options.strict => {
+                Err(self.error(self.span(idx), format!("\"{token}\" isn't a valid {what}"), hint))
+            }
+            Err(_) => {
+                self.warnings.push(
+                    Diagnostic::warning(
+                        self.line_num,
+                        self.line,
+                        self.span(idx),
+                        format!("\"{token}\" isn't a valid {what}, using {default} instead"),
+                    )
+                    .with_hint(hint),
+                );
+                Ok(default)
+            }
+        }
+    }
+}
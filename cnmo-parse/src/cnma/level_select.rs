@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::path::Path;
+
+use super::Cnma;
+use crate::lparse::level_data::{LevelData, LevelMetaData, VersionSpecs};
+use crate::lparse::{Error, LParse};
+
+/// A level in the levels folder, found by its `.cnmb` and `.cnms` files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelFiles {
+    /// The file name without the extension, this is what goes in the level select order
+    pub name: String,
+    /// If the `.cnmb` file is there
+    pub has_cnmb: bool,
+    /// If the `.cnms` file is there
+    pub has_cnms: bool,
+}
+
+impl LevelFiles {
+    /// If both files are there
+    pub fn is_complete(&self) -> bool {
+        self.has_cnmb && self.has_cnms
+    }
+}
+
+/// Finds every level in a folder, sorted by name
+pub fn scan_levels<P: AsRef<Path>>(dir: P) -> Vec<LevelFiles> {
+    let mut levels: BTreeMap<String, LevelFiles> = BTreeMap::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let (name, ext) = match (path.file_stem(), path.extension()) {
+            (Some(name), Some(ext)) => (name.to_string_lossy().to_string(), ext.to_string_lossy().to_string()),
+            _ => continue,
+        };
+        if ext != "cnmb" && ext != "cnms" {
+            continue;
+        }
+        let level = levels.entry(name.clone()).or_insert(LevelFiles {
+            name,
+            has_cnmb: false,
+            has_cnms: false,
+        });
+        level.has_cnmb |= ext == "cnmb";
+        level.has_cnms |= ext == "cnms";
+    }
+    levels.into_values().collect()
+}
+
+/// Loads a level from `<dir>/<name>.cnmb` and `.cnms`
+pub fn load_level<P: AsRef<Path>>(dir: P, name: &str) -> Result<LevelData, Error> {
+    let (cnmb, cnms) = load_level_files(dir.as_ref(), name)?;
+    LevelData::from_lparse(&cnmb, &cnms, true)
+}
+
+/// Loads only the level select information of `<dir>/<name>.cnmb` and `.cnms`
+pub fn load_level_metadata<P: AsRef<Path>>(dir: P, name: &str) -> Result<LevelMetaData, Error> {
+    let (cnmb, cnms) = load_level_files(dir.as_ref(), name)?;
+    let version = VersionSpecs::from_version(cnmb.get_version().get_version_id())?;
+    LevelMetaData::from_lparse(&cnmb, &cnms, &version, true)
+}
+
+fn load_level_files(dir: &Path, name: &str) -> Result<(LParse, LParse), Error> {
+    // Not with_extension, level names can have dots in them like `world1.5`
+    let cnmb = LParse::from_file(dir.join(format!("{name}.cnmb")))?;
+    let cnms = LParse::from_file(dir.join(format!("{name}.cnms")))?;
+    Ok((cnmb, cnms))
+}
+
+/// Something wrong with the level select order compared to the levels folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelSelectProblem {
+    /// The level is listed but neither of its files exist
+    MissingLevel {
+        /// Name of the level
+        name: String,
+    },
+    /// The level is listed but only one of its files exist
+    IncompleteLevel {
+        /// Name of the level
+        name: String,
+        /// The extension of the missing file
+        missing: &'static str,
+    },
+    /// The level is listed more than once
+    DuplicateLevel {
+        /// Name of the level
+        name: String,
+    },
+    /// The level is in the folder but not on the level select menu
+    UnlistedLevel {
+        /// Name of the level
+        name: String,
+    },
+}
+
+impl LevelSelectProblem {
+    /// If the game will fail to load a level on the menu because of this
+    pub fn is_error(&self) -> bool {
+        matches!(self, LevelSelectProblem::MissingLevel { .. } | LevelSelectProblem::IncompleteLevel { .. })
+    }
+}
+
+impl Display for LevelSelectProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LevelSelectProblem::MissingLevel { name } => write!(f, "{name} is listed but doesn't exist"),
+            LevelSelectProblem::IncompleteLevel { name, missing } => write!(f, "{name} is listed but {name}.{missing} doesn't exist"),
+            LevelSelectProblem::DuplicateLevel { name } => write!(f, "{name} is listed more than once"),
+            LevelSelectProblem::UnlistedLevel { name } => write!(f, "{name} isn't on the level select menu"),
+        }
+    }
+}
+
+impl Cnma {
+    /// Compares the level select order against the levels found in `levels`, usually
+    /// from [`scan_levels`]. Problems with listed levels come first in menu order.
+    pub fn check_level_select(&self, levels: &[LevelFiles]) -> Vec<LevelSelectProblem> {
+        let mut problems = Vec::new();
+        let mut listed = BTreeSet::new();
+        for name in self.get_level_names() {
+            if !listed.insert(name) {
+                problems.push(LevelSelectProblem::DuplicateLevel { name: name.to_string() });
+                continue;
+            }
+            match levels.iter().find(|level| level.name == name) {
+                None => problems.push(LevelSelectProblem::MissingLevel { name: name.to_string() }),
+                Some(level) if !level.has_cnmb => problems.push(LevelSelectProblem::IncompleteLevel {
+                    name: name.to_string(),
+                    missing: "cnmb",
+                }),
+                Some(level) if !level.has_cnms => problems.push(LevelSelectProblem::IncompleteLevel {
+                    name: name.to_string(),
+                    missing: "cnms",
+                }),
+                Some(_) => {}
+            }
+        }
+        for level in levels.iter().filter(|level| level.is_complete()) {
+            if !listed.contains(level.name.as_str()) {
+                problems.push(LevelSelectProblem::UnlistedLevel { name: level.name.clone() });
+            }
+        }
+        problems
+    }
+}
@@ -0,0 +1,800 @@
+use std::ops::Range;
+
+use super::diagnostic::Diagnostic;
+
+/// What a [`Token`] of Lua code is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Spaces, tabs and new lines
+    Whitespace,
+    /// `-- line` and `--[[ block ]]` comments
+    Comment,
+    /// Reserved words like `function`, `if` and `end`
+    Keyword,
+    /// Variable, function and field names
+    Name,
+    /// Number literals like `10`, `4.5` or `0xff`
+    Number,
+    /// Quoted and `[[long]]` strings
+    String,
+    /// Operators and punctuation
+    Symbol,
+    /// A character that can't start any Lua token
+    Unknown,
+}
+
+/// A piece of Lua code. Every byte of the code is in exactly one token so
+/// they can be used for syntax highlighting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// What the token is
+    pub kind: TokenKind,
+    /// The byte range in the code
+    pub span: Range<usize>,
+    /// False for strings and comments that are missing their closing quote or brackets
+    pub terminated: bool,
+}
+
+/// A function defined in the script, for listing them in an outline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LuaFunction {
+    /// The full name, like `LUA_UPDATE0`, `util.clamp` or `Obj:update`
+    pub name: String,
+    /// The line `function` is on, starting at 1
+    pub line: usize,
+    /// If it was declared with `local function` or assigned to a local
+    pub is_local: bool,
+}
+
+impl LuaFunction {
+    /// If the game calls this function itself, like `LUA_CREATE0` or `LUA_UPDATE0`
+    /// for lua object type 0.
+    pub fn is_hook(&self) -> bool {
+        !self.is_local && self.name.starts_with("LUA_")
+    }
+}
+
+/// The result of checking a Lua script with [`check`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuaCheck {
+    /// The first syntax error, Lua stops at the first one so there is at most one
+    pub error: Option<Diagnostic>,
+    /// Every function defined before the syntax error, in order
+    pub functions: Vec<LuaFunction>,
+}
+
+const KEYWORDS: [&str; 22] = [
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in", "local", "nil", "not", "or",
+    "repeat", "return", "then", "true", "until", "while",
+];
+
+const SYMBOLS: [&str; 33] = [
+    "...", "..", "==", "~=", "<=", ">=", "<<", ">>", "//", "::", "+", "-", "*", "/", "%", "^", "#", "&", "~", "|", "<", ">", "=",
+    "(", ")", "{", "}", "[", "]", ";", ":", ",", ".",
+];
+
+/// Returns the length of the `=` signs of a long bracket like `[==[` at the
+/// start of `s`, or None if it isn't one.
+fn long_bracket_level(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('[')?;
+    let level = rest.bytes().take_while(|b| *b == b'=').count();
+    rest[level..].starts_with('[').then_some(level)
+}
+
+/// Finds the end of a long string or comment whose contents start at `from`.
+/// Returns the end of the closing bracket, or None if it is never closed.
+fn long_bracket_end(code: &str, from: usize, level: usize) -> Option<usize> {
+    let close = format!("]{}]", "=".repeat(level));
+    code[from..].find(&close).map(|idx| from + idx + close.len())
+}
+
+/// Splits Lua 5.4 code into tokens, including whitespace and comments.
+pub fn tokenize(code: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(c) = code[pos..].chars().next() {
+        let rest = &code[pos..];
+        let start = pos;
+        let mut terminated = true;
+        let kind = if c.is_whitespace() {
+            pos += rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+            TokenKind::Whitespace
+        } else if let Some(comment) = rest.strip_prefix("--") {
+            match long_bracket_level(comment) {
+                Some(level) => {
+                    let from = pos + 2 + level + 2;
+                    pos = long_bracket_end(code, from, level).unwrap_or_else(|| {
+                        terminated = false;
+                        code.len()
+                    });
+                }
+                None => pos += rest.find('\n').unwrap_or(rest.len()),
+            }
+            TokenKind::Comment
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            pos += rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            if KEYWORDS.contains(&&code[start..pos]) {
+                TokenKind::Keyword
+            } else {
+                TokenKind::Name
+            }
+        } else if c.is_ascii_digit() || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit())) {
+            let hex = rest.starts_with("0x") || rest.starts_with("0X");
+            let mut chars = rest.char_indices().skip(if hex { 2 } else { 0 }).peekable();
+            let mut len = rest.len();
+            while let Some((idx, c)) = chars.next() {
+                let exponent = if hex { matches!(c, 'p' | 'P') } else { matches!(c, 'e' | 'E') };
+                if exponent {
+                    if let Some((_, '+' | '-')) = chars.peek() {
+                        chars.next();
+                    }
+                } else if !(c.is_ascii_alphanumeric() || c == '.') {
+                    len = idx;
+                    break;
+                }
+            }
+            pos += len;
+            TokenKind::Number
+        } else if c == '"' || c == '\'' {
+            let mut chars = rest.char_indices().skip(1);
+            terminated = false;
+            pos += rest.len();
+            while let Some((idx, next)) = chars.next() {
+                match next {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '\n' => {
+                        pos = start + idx;
+                        break;
+                    }
+                    _ if next == c => {
+                        pos = start + idx + 1;
+                        terminated = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            TokenKind::String
+        } else if let Some(level) = long_bracket_level(rest) {
+            pos = long_bracket_end(code, pos + level + 2, level).unwrap_or_else(|| {
+                terminated = false;
+                code.len()
+            });
+            TokenKind::String
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            pos += symbol.len();
+            TokenKind::Symbol
+        } else {
+            pos += c.len_utf8();
+            TokenKind::Unknown
+        };
+        tokens.push(Token {
+            kind,
+            span: start..pos,
+            terminated,
+        });
+    }
+    tokens
+}
+
+/// What opened a block, so a missing `end` can point back to it
+struct Opener {
+    keyword: &'static str,
+    span: Range<usize>,
+}
+
+type ParseResult<T> = Result<T, Diagnostic>;
+
+/// A recursive descent parser for the Lua 5.4 grammar that only checks the
+/// syntax and remembers the functions it sees.
+struct Parser<'a> {
+    code: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    functions: Vec<LuaFunction>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(code: &'a str) -> Self {
+        let tokens = tokenize(code)
+            .into_iter()
+            .filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment) || !token.terminated)
+            .collect();
+        Self {
+            code,
+            tokens,
+            pos: 0,
+            functions: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n)
+    }
+
+    fn text(&self, token: Option<&Token>) -> &'a str {
+        token.map_or("", |token| &self.code[token.span.clone()])
+    }
+
+    fn check(&self, text: &str) -> bool {
+        match self.peek() {
+            Some(token) => {
+                matches!(token.kind, TokenKind::Keyword | TokenKind::Symbol) && &self.code[token.span.clone()] == text
+            }
+            None => false,
+        }
+    }
+
+    fn accept(&mut self, text: &str) -> bool {
+        let found = self.check(text);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        self.code[..offset].matches('\n').count()
+    }
+
+    fn current_span(&self) -> Range<usize> {
+        self.peek().map_or(self.code.len()..self.code.len(), |token| token.span.clone())
+    }
+
+    /// An error pointing at `span`, cut down to its first line
+    fn error_at(&self, span: Range<usize>, message: impl Into<String>) -> Diagnostic {
+        let line_start = self.code[..span.start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = self.code[span.start..].find('\n').map_or(self.code.len(), |idx| span.start + idx);
+        let source = self.code[line_start..line_end].trim_end_matches('\r');
+        let columns = span.start - line_start..span.end.min(line_end) - line_start;
+        Diagnostic::error(self.line_of(span.start), source, columns, message)
+    }
+
+    /// An error at the current token saying what was expected instead
+    fn error_expected(&self, expected: &str) -> Diagnostic {
+        let near = match self.peek() {
+            Some(token) => format!("'{}'", self.text(Some(token)).lines().next().unwrap_or("")),
+            None => "the end of the script".to_string(),
+        };
+        self.error_at(self.current_span(), format!("expected {expected} near {near}"))
+    }
+
+    fn expect(&mut self, text: &str) -> ParseResult<()> {
+        if self.accept(text) {
+            Ok(())
+        } else {
+            Err(self.error_expected(&format!("'{text}'")))
+        }
+    }
+
+    /// Expects the word that closes a block, saying where the block was opened if it's missing
+    fn expect_close(&mut self, text: &str, opener: &Opener) -> ParseResult<()> {
+        if self.accept(text) {
+            return Ok(());
+        }
+        let opened_line = self.line_of(opener.span.start) + 1;
+        let mut error = self.error_expected(&format!("'{text}'"));
+        if opened_line != error.line {
+            error = error.with_hint(format!("to close the '{}' at line {opened_line}", opener.keyword));
+        }
+        Err(error)
+    }
+
+    fn opener(&mut self, keyword: &'static str) -> Opener {
+        let span = self.current_span();
+        self.pos += 1;
+        Opener { keyword, span }
+    }
+
+    fn name(&mut self) -> ParseResult<&'a str> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Name => {
+                let name = self.text(Some(token));
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error_expected("a name")),
+        }
+    }
+
+    fn check_token(&self) -> ParseResult<()> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Unknown => {
+                Err(self.error_at(token.span.clone(), format!("unexpected symbol '{}'", self.text(Some(token)))))
+            }
+            Some(token) if !token.terminated => Err(match token.kind {
+                TokenKind::Comment => self.error_at(token.span.start..token.span.start + 2, "unfinished long comment"),
+                _ => self.error_at(token.span.clone(), "unfinished string"),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn is_block_end(&self) -> bool {
+        self.peek().is_none() || ["end", "else", "elseif", "until"].iter().any(|word| self.check(word))
+    }
+
+    fn chunk(&mut self) -> ParseResult<()> {
+        self.block()?;
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error_expected("the end of the script")),
+        }
+    }
+
+    fn block(&mut self) -> ParseResult<()> {
+        loop {
+            self.check_token()?;
+            if self.is_block_end() {
+                return Ok(());
+            }
+            if self.check("return") {
+                self.pos += 1;
+                if !self.is_block_end() && !self.check(";") {
+                    self.expr_list()?;
+                }
+                self.accept(";");
+                self.check_token()?;
+                if !self.is_block_end() {
+                    return Err(self.error_expected("the end of the block").with_hint("return has to be the last statement in a block"));
+                }
+                return Ok(());
+            }
+            self.statement()?;
+        }
+    }
+
+    fn statement(&mut self) -> ParseResult<()> {
+        let keyword = self.text(self.peek());
+        match keyword {
+            ";" => self.pos += 1,
+            "::" => {
+                self.pos += 1;
+                self.name()?;
+                self.expect("::")?;
+            }
+            "break" => self.pos += 1,
+            "goto" => {
+                self.pos += 1;
+                self.name()?;
+            }
+            "do" => {
+                let opener = self.opener("do");
+                self.block()?;
+                self.expect_close("end", &opener)?;
+            }
+            "while" => {
+                let opener = self.opener("while");
+                self.expr()?;
+                self.expect("do")?;
+                self.block()?;
+                self.expect_close("end", &opener)?;
+            }
+            "repeat" => {
+                let opener = self.opener("repeat");
+                self.block()?;
+                self.expect_close("until", &opener)?;
+                self.expr()?;
+            }
+            "if" => {
+                let opener = self.opener("if");
+                self.expr()?;
+                self.expect("then")?;
+                self.block()?;
+                while self.accept("elseif") {
+                    self.expr()?;
+                    self.expect("then")?;
+                    self.block()?;
+                }
+                if self.accept("else") {
+                    self.block()?;
+                }
+                self.expect_close("end", &opener)?;
+            }
+            "for" => {
+                let opener = self.opener("for");
+                self.name()?;
+                if self.accept("=") {
+                    self.expr()?;
+                    self.expect(",")?;
+                    self.expr()?;
+                    if self.accept(",") {
+                        self.expr()?;
+                    }
+                } else {
+                    while self.accept(",") {
+                        self.name()?;
+                    }
+                    self.expect("in")?;
+                    self.expr_list()?;
+                }
+                self.expect("do")?;
+                self.block()?;
+                self.expect_close("end", &opener)?;
+            }
+            "function" => {
+                let opener = self.opener("function");
+                let mut name = self.name()?.to_string();
+                while self.check(".") || self.check(":") {
+                    let is_method = self.check(":");
+                    name.push_str(self.text(self.peek()));
+                    self.pos += 1;
+                    name.push_str(self.name()?);
+                    if is_method {
+                        break;
+                    }
+                }
+                self.add_function(name, &opener, false);
+                self.function_body(&opener)?;
+            }
+            "local" => {
+                self.pos += 1;
+                if self.check("function") {
+                    let opener = self.opener("function");
+                    let name = self.name()?.to_string();
+                    self.add_function(name, &opener, true);
+                    self.function_body(&opener)?;
+                } else {
+                    let mut names = vec![self.name()?];
+                    self.attribute()?;
+                    while self.accept(",") {
+                        names.push(self.name()?);
+                        self.attribute()?;
+                    }
+                    if self.accept("=") {
+                        self.assigned_exprs(&names, true)?;
+                    }
+                }
+            }
+            _ => self.expr_statement()?,
+        }
+        Ok(())
+    }
+
+    fn attribute(&mut self) -> ParseResult<()> {
+        if self.accept("<") {
+            self.name()?;
+            self.expect(">")?;
+        }
+        Ok(())
+    }
+
+    fn add_function(&mut self, name: String, opener: &Opener, is_local: bool) {
+        let line = self.line_of(opener.span.start) + 1;
+        self.functions.push(LuaFunction { name, line, is_local });
+    }
+
+    /// The values of an assignment, remembering `name = function` as a function definition
+    fn assigned_exprs(&mut self, names: &[&str], is_local: bool) -> ParseResult<()> {
+        let mut idx = 0;
+        loop {
+            let name = names.get(idx).filter(|name| !name.is_empty());
+            if let (true, Some(name)) = (self.check("function"), name) {
+                let opener = self.opener("function");
+                self.add_function(name.to_string(), &opener, is_local);
+                self.function_body(&opener)?;
+                self.binary_rest()?;
+            } else {
+                self.expr()?;
+            }
+            idx += 1;
+            if !self.accept(",") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn expr_statement(&mut self) -> ParseResult<()> {
+        let start = self.pos;
+        let (name, is_call) = self.suffixed_expr()?;
+        if self.check("=") || self.check(",") {
+            let mut names = vec![name];
+            let mut targets = vec![is_call];
+            while self.accept(",") {
+                let (name, is_call) = self.suffixed_expr()?;
+                names.push(name);
+                targets.push(is_call);
+            }
+            if targets.iter().any(|is_call| *is_call) {
+                let span = self.tokens[start].span.clone();
+                return Err(self.error_at(span, "can't assign to a function call"));
+            }
+            self.expect("=")?;
+            self.assigned_exprs(&names, false)
+        } else if is_call {
+            Ok(())
+        } else {
+            Err(self.error_expected("'=' or a function call"))
+        }
+    }
+
+    /// Parses a name or parenthesized expression with any field accesses and calls after it.
+    /// Returns the dotted name if it's only made of names, and if it ends in a call.
+    fn suffixed_expr(&mut self) -> ParseResult<(&'a str, bool)> {
+        self.check_token()?;
+        let start = self.current_span().start;
+        let mut plain_name = true;
+        if self.accept("(") {
+            plain_name = false;
+            self.expr()?;
+            self.expect(")")?;
+        } else {
+            self.name()?;
+        }
+        let mut is_call = false;
+        loop {
+            self.check_token()?;
+            if self.accept(".") {
+                self.name()?;
+                is_call = false;
+            } else if self.accept("[") {
+                plain_name = false;
+                self.expr()?;
+                self.expect("]")?;
+                is_call = false;
+            } else if self.accept(":") {
+                plain_name = false;
+                self.name()?;
+                self.call_args()?;
+                is_call = true;
+            } else if self.check("(") || self.check("{") || self.peek().map(|token| token.kind) == Some(TokenKind::String) {
+                plain_name = false;
+                self.call_args()?;
+                is_call = true;
+            } else {
+                break;
+            }
+        }
+        let end = self.tokens[self.pos - 1].span.end;
+        Ok((if plain_name { &self.code[start..end] } else { "" }, is_call))
+    }
+
+    fn call_args(&mut self) -> ParseResult<()> {
+        if self.check("{") {
+            return self.table();
+        }
+        if self.peek().map(|token| token.kind) == Some(TokenKind::String) {
+            self.pos += 1;
+            return Ok(());
+        }
+        let opener = self.opener("(");
+        if !self.check(")") {
+            self.expr_list()?;
+        }
+        self.expect_close(")", &opener)
+    }
+
+    fn table(&mut self) -> ParseResult<()> {
+        let opener = self.opener("{");
+        while !self.check("}") {
+            self.check_token()?;
+            if self.accept("[") {
+                self.expr()?;
+                self.expect("]")?;
+                self.expect("=")?;
+            } else if self.peek().map(|token| token.kind) == Some(TokenKind::Name) && self.text(self.peek_nth(1)) == "=" {
+                self.pos += 2;
+            }
+            self.expr()?;
+            if !self.accept(",") && !self.accept(";") {
+                break;
+            }
+        }
+        self.expect_close("}", &opener)
+    }
+
+    fn function_body(&mut self, opener: &Opener) -> ParseResult<()> {
+        self.expect("(")?;
+        if !self.check(")") {
+            loop {
+                if self.accept("...") {
+                    break;
+                }
+                self.name()?;
+                if !self.accept(",") {
+                    break;
+                }
+            }
+        }
+        self.expect(")")?;
+        self.block()?;
+        self.expect_close("end", opener)
+    }
+
+    fn expr_list(&mut self) -> ParseResult<()> {
+        self.expr()?;
+        while self.accept(",") {
+            self.expr()?;
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self) -> ParseResult<()> {
+        self.simple_expr()?;
+        self.binary_rest()
+    }
+
+    /// Any binary operators and their right hand sides after a simple expression.
+    /// Precedence doesn't change if the syntax is valid so it's ignored.
+    fn binary_rest(&mut self) -> ParseResult<()> {
+        const BINARY: [&str; 21] = [
+            "+", "-", "*", "/", "//", "%", "^", "..", "==", "~=", "<", "<=", ">", ">=", "and", "or", "&", "|", "~", "<<", ">>",
+        ];
+        while BINARY.iter().any(|op| self.check(op)) {
+            self.pos += 1;
+            self.simple_expr()?;
+        }
+        Ok(())
+    }
+
+    fn simple_expr(&mut self) -> ParseResult<()> {
+        while self.accept("not") || self.accept("-") || self.accept("#") || self.accept("~") {}
+        self.check_token()?;
+        let kind = self.peek().map(|token| token.kind);
+        match (kind, self.text(self.peek())) {
+            (Some(TokenKind::Number | TokenKind::String), _) | (_, "nil" | "true" | "false" | "...") => {
+                self.pos += 1;
+                Ok(())
+            }
+            (_, "function") => {
+                let opener = self.opener("function");
+                self.function_body(&opener)
+            }
+            (_, "{") => self.table(),
+            (Some(TokenKind::Name), _) | (_, "(") => self.suffixed_expr().map(|_| ()),
+            _ => Err(self.error_expected("an expression")),
+        }
+    }
+}
+
+/// Checks the syntax of a Lua 5.4 script without running it, and lists the
+/// functions it defines. The line numbers in the diagnostic and functions
+/// count from the first line of `code`.
+pub fn check(code: &str) -> LuaCheck {
+    let mut parser = Parser::new(code);
+    let error = parser.chunk().err();
+    LuaCheck {
+        error,
+        functions: parser.functions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The kind and text of every token that isn't whitespace
+    fn tokens(code: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(code)
+            .into_iter()
+            .filter(|token| token.kind != TokenKind::Whitespace)
+            .map(|token| (token.kind, &code[token.span]))
+            .collect()
+    }
+
+    fn error_line(code: &str) -> Option<usize> {
+        check(code).error.map(|error| error.line)
+    }
+
+    #[test]
+    fn tokens_cover_the_whole_code() {
+        let code = "local x = 1 -- hi\nprint(x .. \"a\")\n";
+        let mut end = 0;
+        for token in tokenize(code) {
+            assert_eq!(token.span.start, end);
+            end = token.span.end;
+        }
+        assert_eq!(end, code.len());
+    }
+
+    #[test]
+    fn long_brackets() {
+        assert_eq!(tokens("[[a]] [==[b]]c]==]"), vec![(TokenKind::String, "[[a]]"), (TokenKind::String, "[==[b]]c]==]")]);
+        assert_eq!(tokens("--[=[ a\n]] b ]=] x"), vec![(TokenKind::Comment, "--[=[ a\n]] b ]=]"), (TokenKind::Name, "x")]);
+        // Without a second bracket it's a line comment and an index
+        assert_eq!(tokens("--[ a\nx[1]")[0], (TokenKind::Comment, "--[ a"));
+        assert_eq!(tokens("t[=1]")[1], (TokenKind::Symbol, "["));
+
+        let unfinished = tokenize("x = [==[ abc ]=]");
+        assert!(!unfinished.last().unwrap().terminated);
+        assert_eq!(check("x = [==[ abc ]=]").error.unwrap().message, "unfinished string");
+        assert_eq!(check("--[[ abc").error.unwrap().message, "unfinished long comment");
+        assert!(check("x = [[\nmulti\nline]]\n").error.is_none());
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(tokens(r#""a\"b" 'c\'d'"#), vec![(TokenKind::String, r#""a\"b""#), (TokenKind::String, r"'c\'d'")]);
+        assert_eq!(tokens(r#""\\" x"#), vec![(TokenKind::String, r#""\\""#), (TokenKind::Name, "x")]);
+        // An escaped new line continues the string
+        assert!(check("x = \"a\\\nb\"").error.is_none());
+        let error = check("x = \"abc\ny = 1").error.unwrap();
+        assert_eq!(error.message, "unfinished string");
+        assert_eq!(error.line, 1);
+    }
+
+    #[test]
+    fn numbers_and_symbols() {
+        assert_eq!(
+            tokens("0xff 1e-5 .5 3..4"),
+            vec![
+                (TokenKind::Number, "0xff"),
+                (TokenKind::Number, "1e-5"),
+                (TokenKind::Number, ".5"),
+                (TokenKind::Number, "3..4"),
+            ]
+        );
+        assert_eq!(tokens("a // b"), vec![(TokenKind::Name, "a"), (TokenKind::Symbol, "//"), (TokenKind::Name, "b")]);
+        assert_eq!(tokens("if x then")[0].0, TokenKind::Keyword);
+        assert_eq!(tokens("$")[0].0, TokenKind::Unknown);
+        assert_eq!(check("x = 1 $").error.unwrap().message, "unexpected symbol '$'");
+    }
+
+    #[test]
+    fn goto_and_labels() {
+        let code = "for i = 1, 3 do\n  if i == 2 then goto continue end\n  ::continue::\nend\n";
+        assert!(check(code).error.is_none());
+        assert!(check("::a:: ::b::; goto a").error.is_none());
+        assert!(check(":: 1 ::").error.is_some());
+        assert!(check("goto").error.is_some());
+    }
+
+    #[test]
+    fn attribs() {
+        assert!(check("local x <const> = 5\nlocal f <close>, y <const> = nil, 1").error.is_none());
+        assert!(check("local x <const = 5").error.is_some());
+        assert!(check("local x <1> = 5").error.is_some());
+    }
+
+    #[test]
+    fn method_calls() {
+        let code = "local obj = {}\nfunction obj:update(dt) self.x = self.x + dt end\nobj:update(1)\nobj:draw{ 1, 2 }\nobj:say \"hi\"\nprint(obj:get().x)";
+        let result = check(code);
+        assert_eq!(result.error, None);
+        assert_eq!(
+            result.functions,
+            vec![LuaFunction {
+                name: "obj:update".to_string(),
+                line: 2,
+                is_local: false,
+            }]
+        );
+        assert!(check("obj:update").error.is_some());
+        assert_eq!(check("obj:get() = 1").error.unwrap().message, "can't assign to a function call");
+    }
+
+    #[test]
+    fn functions_are_listed() {
+        let code = "function LUA_CREATE0()\nend\nlocal function helper() end\nutil.clamp = function(x) return x end\nlocal f = function() end\n";
+        let functions = check(code).functions;
+        let names = functions.iter().map(|f| (f.name.as_str(), f.line, f.is_local)).collect::<Vec<_>>();
+        assert_eq!(names, vec![("LUA_CREATE0", 1, false), ("helper", 3, true), ("util.clamp", 4, false), ("f", 5, true)]);
+        assert!(functions[0].is_hook());
+        assert!(!functions[1].is_hook());
+    }
+
+    #[test]
+    fn error_line_numbers() {
+        assert_eq!(error_line("x = 1\ny = 2\n"), None);
+        assert_eq!(error_line("x = 1\ny = = 2\n"), Some(2));
+        assert_eq!(error_line("\n\n\nlocal 5"), Some(4));
+        assert_eq!(error_line("return 1\nx = 2"), Some(2));
+
+        // A missing end points at the end of the script with a hint back to the opener
+        let error = check("x = 1\nfunction f()\n  return 1\n").error.unwrap();
+        assert_eq!(error.line, 4);
+        assert_eq!(error.hint.as_deref(), Some("to close the 'function' at line 2"));
+        assert_eq!(error.message, "expected 'end' near the end of the script");
+
+        let error = check("x = 1\nwhile true do\nuntil x").error.unwrap();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.source, "until x");
+        assert_eq!(error.columns, 0..5);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use super::{Cnma, MaxPowerAbility, MaxPowerDef, Mode};
+
+/// Something odd about the numbers in a [`MaxPowerDef`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaxPowerProblem {
+    /// A multiplier is below 0, which flips the direction of things in game
+    NegativeMultiplier {
+        /// The name of the field in the cnma file
+        field: &'static str,
+        /// Its value
+        value: f32,
+    },
+    /// A multiplier is so big the player will most likely clip through walls
+    HugeMultiplier {
+        /// The name of the field in the cnma file
+        field: &'static str,
+        /// Its value
+        value: f32,
+    },
+    /// With no gravity the player can't come back down after jumping
+    NoGravity,
+    /// The hp cost is negative so the power heals instead
+    NegativeHpCost(f32),
+    /// More than one section uses the same skin id, the game uses the last one
+    DuplicateSkinId(u8),
+}
+
+impl Display for MaxPowerProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaxPowerProblem::NegativeMultiplier { field, value } => write!(f, "{field} is negative ({value})"),
+            MaxPowerProblem::HugeMultiplier { field, value } => {
+                write!(f, "{field} is very big ({value}), the player might go through walls")
+            }
+            MaxPowerProblem::NoGravity => write!(f, "grav is 0, the player will never land"),
+            MaxPowerProblem::NegativeHpCost(cost) => write!(f, "hpcost is negative ({cost}), the power heals the player"),
+            MaxPowerProblem::DuplicateSkinId(id) => write!(f, "skin id {id} has more than one max power section"),
+        }
+    }
+}
+
+impl MaxPowerAbility {
+    /// Gets the ability from the id used in cnma files, 0 means no ability so it isn't valid here.
+    pub fn from_ability_id(id: u32) -> Option<Self> {
+        match id {
+            1 => Some(MaxPowerAbility::DoubleJump),
+            2 => Some(MaxPowerAbility::Flying),
+            3 => Some(MaxPowerAbility::DropShield),
+            4 => Some(MaxPowerAbility::MarioBounce),
+            _ => None,
+        }
+    }
+
+    /// The id used in cnma files
+    pub fn get_ability_id(&self) -> u32 {
+        match self {
+            MaxPowerAbility::DoubleJump => 1,
+            MaxPowerAbility::Flying => 2,
+            MaxPowerAbility::DropShield => 3,
+            MaxPowerAbility::MarioBounce => 4,
+        }
+    }
+}
+
+impl MaxPowerDef {
+    /// Multipliers bigger than this get a [`MaxPowerProblem::HugeMultiplier`]
+    pub const MAX_SENSIBLE_MULTIPLIER: f32 = 10.0;
+    /// Roughly how many pixels per frame a player runs without any powers
+    pub const BASE_SPEED: f32 = 5.0;
+    /// Roughly the upwards speed in pixels per frame of a normal jump
+    pub const BASE_JUMP: f32 = 10.0;
+    /// Roughly how much the players falling speed goes up per frame normally
+    pub const BASE_GRAVITY: f32 = 0.5;
+
+    /// A power that doesn't change anything about the player
+    pub fn new_neutral(id: u8) -> Self {
+        Self {
+            id,
+            speed: 1.0,
+            jump: 1.0,
+            gravity: 1.0,
+            hpcost: 0.0,
+            strength: 1.0,
+            ability: None,
+        }
+    }
+
+    /// Checks for numbers that are likely mistakes
+    pub fn validate(&self) -> Vec<MaxPowerProblem> {
+        let mut problems = Vec::new();
+        for (field, value) in [
+            ("spd", self.speed),
+            ("jmp", self.jump),
+            ("grav", self.gravity),
+            ("strength", self.strength),
+        ] {
+            if value < 0.0 {
+                problems.push(MaxPowerProblem::NegativeMultiplier { field, value });
+            } else if value > Self::MAX_SENSIBLE_MULTIPLIER {
+                problems.push(MaxPowerProblem::HugeMultiplier { field, value });
+            }
+        }
+        // Negative gravity already got a warning above
+        if self.gravity == 0.0 {
+            problems.push(MaxPowerProblem::NoGravity);
+        }
+        if self.hpcost < 0.0 {
+            problems.push(MaxPowerProblem::NegativeHpCost(self.hpcost));
+        }
+        problems
+    }
+
+    /// Simulates a running jump frame by frame from standing on the ground until
+    /// landing back at the same height, using the rough base player physics. The
+    /// points are (distance, height) in pixels with height going up, one per frame
+    /// at [`FRAME_RATE`](crate::lparse::level_data::consts::FRAME_RATE). Stops
+    /// after `max_frames` if the player never lands.
+    pub fn jump_arc(&self, max_frames: usize) -> Vec<(f32, f32)> {
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut vel = self.jump * Self::BASE_JUMP;
+        let mut points = vec![(x, y)];
+        for _ in 0..max_frames {
+            x += self.speed * Self::BASE_SPEED;
+            y += vel;
+            vel -= self.gravity * Self::BASE_GRAVITY;
+            if y <= 0.0 {
+                points.push((x, 0.0));
+                break;
+            }
+            points.push((x, y));
+        }
+        points
+    }
+}
+
+impl Cnma {
+    /// Every max power definition in the file in order
+    pub fn get_max_powers(&self) -> Vec<&MaxPowerDef> {
+        self.modes
+            .iter()
+            .filter_map(|mode| match mode {
+                Mode::MaxPowerDef(def) => Some(def),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Validates every max power definition and looks for skin ids used more than
+    /// once. Returns the skin id each problem is for, sorted by skin id.
+    pub fn check_max_powers(&self) -> Vec<(u8, MaxPowerProblem)> {
+        let mut by_id: BTreeMap<u8, Vec<&MaxPowerDef>> = BTreeMap::new();
+        for def in self.get_max_powers() {
+            by_id.entry(def.id).or_default().push(def);
+        }
+        let mut problems = Vec::new();
+        for (id, defs) in by_id {
+            if defs.len() > 1 {
+                problems.push((id, MaxPowerProblem::DuplicateSkinId(id)));
+            }
+            for def in defs {
+                problems.extend(def.validate().into_iter().map(|problem| (id, problem)));
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gravity_problems_are_reported_once() {
+        let def = MaxPowerDef { gravity: -1.0, ..MaxPowerDef::new_neutral(0) };
+        assert_eq!(def.validate(), vec![MaxPowerProblem::NegativeMultiplier { field: "grav", value: -1.0 }]);
+
+        let def = MaxPowerDef { gravity: 0.0, ..MaxPowerDef::new_neutral(0) };
+        assert_eq!(def.validate(), vec![MaxPowerProblem::NoGravity]);
+
+        assert!(MaxPowerDef::new_neutral(0).validate().is_empty());
+    }
+}
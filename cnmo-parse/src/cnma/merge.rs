@@ -0,0 +1,468 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use super::lua;
+use super::resources::ResourceKind;
+use super::{Cnma, Error, MaxPowerDef, Mode, PetDef, ResourceId};
+
+/// What happens to the level select order when a mod has one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LevelOrderMerge {
+    /// The mod's levels go after the base levels, levels already on the menu keep their place
+    #[default]
+    Append,
+    /// The mod's order is used instead of the base order
+    Replace,
+}
+
+/// Settings for [`Cnma::merge`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// How level select orders are combined
+    pub level_order: LevelOrderMerge,
+    /// Leave out the overlay's lua code when it's the same as the base code
+    /// (ignoring blank lines and trailing spaces), for mods that ship a copy
+    /// of the base config. Off by default, so the code is always concatenated.
+    pub skip_duplicate_lua: bool,
+}
+
+/// Something in the overlay replaced something different in the base
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeConflict {
+    /// A music or sound id points to a different file
+    Resource {
+        /// Music or sound
+        kind: ResourceKind,
+        /// The id used by both
+        id: u32,
+        /// The path in the base
+        base: String,
+        /// The path in the overlay, which is the one kept
+        overlay: String,
+    },
+    /// A skin has a different max power definition, the overlay's is kept
+    MaxPower(u8),
+    /// A pet with the same name is defined differently, the overlay's is kept
+    Pet(String),
+    /// A level is already on the level select menu, the base position is kept
+    DuplicateLevel(String),
+    /// A global lua function is defined again, the overlay's runs last so it's the one kept
+    LuaFunction {
+        /// Name of the function
+        name: String,
+        /// Line of the new definition in the overlay's lua code, starting at 1
+        line: usize,
+    },
+    /// The overlay's lua code is already in the base, so it runs twice unless
+    /// it was the same as all of the base code and was skipped
+    DuplicateLua {
+        /// If it was left out because of [`MergeOptions::skip_duplicate_lua`]
+        skipped: bool,
+    },
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeConflict::Resource { kind, id, base, overlay } => {
+                write!(f, "{kind} id {id} changed from {base} to {overlay}")
+            }
+            MergeConflict::MaxPower(id) => write!(f, "the max power for skin id {id} is replaced"),
+            MergeConflict::Pet(name) => write!(f, "pet {name} is replaced"),
+            MergeConflict::DuplicateLevel(name) => write!(f, "level {name} is already on the level select menu"),
+            MergeConflict::LuaFunction { name, line } => {
+                write!(f, "lua function {name} at line {line} replaces an existing one")
+            }
+            MergeConflict::DuplicateLua { skipped: true } => write!(f, "the lua code is the same as the base, so it's skipped"),
+            MergeConflict::DuplicateLua { skipped: false } => write!(f, "the lua code is already in the base, so it runs twice"),
+        }
+    }
+}
+
+impl Cnma {
+    /// Layers `overlay` on top of this config and returns the combined config
+    /// with one section of each kind. Per section:
+    /// - music and sound ids: the overlay's paths replace ones with the same id
+    /// - level select order: appended or replaced depending on `options`
+    /// - max powers: replaced by skin id
+    /// - lua code: the overlay's code goes after the base code, see
+    ///   [`MergeOptions::skip_duplicate_lua`] for leaving out copies of the base
+    /// - pets: replaced by name
+    ///
+    /// Returns everything in the overlay that changed something in the base.
+    /// Merging a config into an empty one flattens it, which is how repeated
+    /// sections in one file are combined.
+    pub fn merge(&self, overlay: &Cnma, options: MergeOptions) -> (Cnma, Vec<MergeConflict>) {
+        let mut merged = Cnma { modes: Vec::new() };
+        let mut conflicts = Vec::new();
+        merged.apply(self, options, &mut conflicts);
+        conflicts.clear();
+        merged.apply(overlay, options, &mut conflicts);
+        (merged, conflicts)
+    }
+
+    /// This config with one section of each kind, see [`Cnma::merge`]
+    pub fn flattened(&self) -> Cnma {
+        Cnma { modes: Vec::new() }.merge(self, MergeOptions::default()).0
+    }
+
+    /// Loads each file and merges them in order, so later files override earlier
+    /// ones like mods over the base game. Conflicts come with the index of the
+    /// file in `paths` that caused them.
+    pub fn from_files_layered<P: AsRef<Path>>(
+        paths: &[P],
+        options: MergeOptions,
+    ) -> Result<(Cnma, Vec<(usize, MergeConflict)>), Error> {
+        let mut merged = Cnma { modes: Vec::new() };
+        let mut conflicts = Vec::new();
+        for (idx, path) in paths.iter().enumerate() {
+            let layer = Cnma::from_file(path)?;
+            let mut layer_conflicts = Vec::new();
+            merged.apply(&layer, options, &mut layer_conflicts);
+            if idx > 0 {
+                conflicts.extend(layer_conflicts.into_iter().map(|conflict| (idx, conflict)));
+            }
+        }
+        Ok((merged, conflicts))
+    }
+
+    /// Finds the section matching `is_section`, or adds one made by `new`
+    fn section_mut(&mut self, is_section: impl Fn(&Mode) -> bool, new: impl FnOnce() -> Mode) -> &mut Mode {
+        let idx = match self.modes.iter().position(is_section) {
+            Some(idx) => idx,
+            None => {
+                self.modes.push(new());
+                self.modes.len() - 1
+            }
+        };
+        &mut self.modes[idx]
+    }
+
+    fn apply(&mut self, overlay: &Cnma, options: MergeOptions, conflicts: &mut Vec<MergeConflict>) {
+        for mode in overlay.modes.iter() {
+            match mode {
+                Mode::MusicIds(ids) => {
+                    if let Mode::MusicIds(base) = self.section_mut(|mode| matches!(mode, Mode::MusicIds(_)), || Mode::MusicIds(vec![])) {
+                        merge_resources(ResourceKind::Music, base, ids, conflicts);
+                    }
+                }
+                Mode::SoundIds(ids) => {
+                    if let Mode::SoundIds(base) = self.section_mut(|mode| matches!(mode, Mode::SoundIds(_)), || Mode::SoundIds(vec![])) {
+                        merge_resources(ResourceKind::Sound, base, ids, conflicts);
+                    }
+                }
+                Mode::MusicVolumeOverride => {
+                    self.section_mut(|mode| matches!(mode, Mode::MusicVolumeOverride), || Mode::MusicVolumeOverride);
+                }
+                Mode::LevelSelectOrder(levels) => {
+                    let section = self.section_mut(|mode| matches!(mode, Mode::LevelSelectOrder(_)), || Mode::LevelSelectOrder(vec![]));
+                    if let Mode::LevelSelectOrder(base) = section {
+                        merge_level_order(options.level_order, base, levels, conflicts);
+                    }
+                }
+                Mode::MaxPowerDef(def) => match self.modes.iter_mut().find_map(|mode| match mode {
+                    Mode::MaxPowerDef(base) if base.id == def.id => Some(base),
+                    _ => None,
+                }) {
+                    Some(base) => merge_max_power(base, def, conflicts),
+                    None => self.modes.push(Mode::MaxPowerDef(def.clone())),
+                },
+                Mode::LuaAutorunCode(code) => {
+                    let section = self.section_mut(|mode| matches!(mode, Mode::LuaAutorunCode(_)), || Mode::LuaAutorunCode("".to_string()));
+                    if let Mode::LuaAutorunCode(base) = section {
+                        merge_lua(base, code, options.skip_duplicate_lua, conflicts);
+                    }
+                }
+                Mode::PetDefs(pets) => {
+                    if let Mode::PetDefs(base) = self.section_mut(|mode| matches!(mode, Mode::PetDefs(_)), || Mode::PetDefs(vec![])) {
+                        merge_pets(base, pets, conflicts);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn merge_resources(kind: ResourceKind, base: &mut Vec<ResourceId>, overlay: &[ResourceId], conflicts: &mut Vec<MergeConflict>) {
+    for resource in overlay {
+        match base.iter_mut().find(|base| base.id == resource.id) {
+            Some(existing) => {
+                if existing.path != resource.path {
+                    conflicts.push(MergeConflict::Resource {
+                        kind,
+                        id: resource.id,
+                        base: existing.path.clone(),
+                        overlay: resource.path.clone(),
+                    });
+                    existing.path = resource.path.clone();
+                }
+            }
+            None => base.push(resource.clone()),
+        }
+    }
+}
+
+fn merge_level_order(
+    merge: LevelOrderMerge,
+    base: &mut Vec<(String, u32)>,
+    overlay: &[(String, u32)],
+    conflicts: &mut Vec<MergeConflict>,
+) {
+    if merge == LevelOrderMerge::Replace {
+        *base = overlay.to_vec();
+        return;
+    }
+    for level in overlay {
+        if base.iter().any(|existing| existing.0 == level.0) {
+            conflicts.push(MergeConflict::DuplicateLevel(level.0.clone()));
+        } else {
+            base.push(level.clone());
+        }
+    }
+}
+
+fn merge_max_power(base: &mut MaxPowerDef, overlay: &MaxPowerDef, conflicts: &mut Vec<MergeConflict>) {
+    if base != overlay {
+        conflicts.push(MergeConflict::MaxPower(overlay.id));
+        *base = overlay.clone();
+    }
+}
+
+/// The lines of lua code that aren't blank, without trailing whitespace, so
+/// copies of the same code with different spacing or line endings match
+fn normalized_lines(code: &str) -> Vec<&str> {
+    code.lines().map(str::trim_end).filter(|line| !line.is_empty()).collect()
+}
+
+fn merge_lua(base: &mut String, overlay: &str, skip_duplicate: bool, conflicts: &mut Vec<MergeConflict>) {
+    let overlay_lines = normalized_lines(overlay);
+    if overlay_lines.is_empty() {
+        return;
+    }
+    let base_lines = normalized_lines(base);
+    if skip_duplicate && base_lines == overlay_lines {
+        conflicts.push(MergeConflict::DuplicateLua { skipped: true });
+        return;
+    }
+    if base_lines.windows(overlay_lines.len()).any(|lines| lines == overlay_lines.as_slice()) {
+        conflicts.push(MergeConflict::DuplicateLua { skipped: false });
+    }
+    let defined = lua::check(base).functions;
+    for function in lua::check(overlay).functions.into_iter().filter(|function| !function.is_local) {
+        if defined.iter().any(|existing| !existing.is_local && existing.name == function.name) {
+            conflicts.push(MergeConflict::LuaFunction {
+                name: function.name,
+                line: function.line,
+            });
+        }
+    }
+    if !base.is_empty() && !base.ends_with('\n') {
+        base.push('\n');
+    }
+    base.push_str(overlay);
+}
+
+fn merge_pets(base: &mut Vec<PetDef>, overlay: &[PetDef], conflicts: &mut Vec<MergeConflict>) {
+    for pet in overlay {
+        match base.iter_mut().find(|existing| existing.name == pet.name) {
+            Some(existing) => {
+                if existing != pet {
+                    conflicts.push(MergeConflict::Pet(pet.name.clone()));
+                    *existing = pet.clone();
+                }
+            }
+            None => base.push(pet.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(id: u32, path: &str) -> ResourceId {
+        ResourceId { id, path: path.to_string() }
+    }
+
+    fn merge(base: Vec<Mode>, overlay: Vec<Mode>, options: MergeOptions) -> (Vec<Mode>, Vec<MergeConflict>) {
+        let (merged, conflicts) = Cnma { modes: base }.merge(&Cnma { modes: overlay }, options);
+        (merged.modes, conflicts)
+    }
+
+    #[test]
+    fn resources_replace_by_id() {
+        let base = vec![Mode::MusicIds(vec![resource(0, "a.mid"), resource(1, "b.mid")])];
+        let overlay = vec![Mode::MusicIds(vec![resource(1, "c.mid"), resource(0, "a.mid"), resource(2, "d.mid")])];
+        let (modes, conflicts) = merge(base, overlay, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::MusicIds(vec![resource(0, "a.mid"), resource(1, "c.mid"), resource(2, "d.mid")])]);
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict::Resource {
+                kind: ResourceKind::Music,
+                id: 1,
+                base: "b.mid".to_string(),
+                overlay: "c.mid".to_string(),
+            }]
+        );
+
+        let base = vec![Mode::SoundIds(vec![resource(0, "a.wav")])];
+        let overlay = vec![Mode::SoundIds(vec![resource(0, "b.wav")])];
+        let (modes, conflicts) = merge(base, overlay, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::SoundIds(vec![resource(0, "b.wav")])]);
+        assert!(matches!(conflicts[..], [MergeConflict::Resource { kind: ResourceKind::Sound, id: 0, .. }]));
+    }
+
+    #[test]
+    fn music_volume_override_is_kept_once() {
+        let modes = vec![Mode::MusicVolumeOverride];
+        let (modes, conflicts) = merge(modes.clone(), modes, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::MusicVolumeOverride]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn level_order_appends_or_replaces() {
+        let level = |name: &str| (name.to_string(), 0);
+        let base = vec![Mode::LevelSelectOrder(vec![level("lvl1"), level("lvl2")])];
+        let overlay = vec![Mode::LevelSelectOrder(vec![level("lvl2"), level("mod1")])];
+
+        let (modes, conflicts) = merge(base.clone(), overlay.clone(), MergeOptions::default());
+        assert_eq!(modes, vec![Mode::LevelSelectOrder(vec![level("lvl1"), level("lvl2"), level("mod1")])]);
+        assert_eq!(conflicts, vec![MergeConflict::DuplicateLevel("lvl2".to_string())]);
+
+        let options = MergeOptions { level_order: LevelOrderMerge::Replace, ..Default::default() };
+        let (modes, conflicts) = merge(base, overlay.clone(), options);
+        assert_eq!(modes, overlay);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn max_powers_replace_by_skin_id() {
+        let def = |id: u8, speed: f32| MaxPowerDef { id, speed, ..Default::default() };
+        let base = vec![Mode::MaxPowerDef(def(1, 1.0)), Mode::MaxPowerDef(def(2, 1.0))];
+        let overlay = vec![Mode::MaxPowerDef(def(2, 2.0)), Mode::MaxPowerDef(def(1, 1.0)), Mode::MaxPowerDef(def(3, 1.0))];
+        let (modes, conflicts) = merge(base, overlay, MergeOptions::default());
+        assert_eq!(
+            modes,
+            vec![Mode::MaxPowerDef(def(1, 1.0)), Mode::MaxPowerDef(def(2, 2.0)), Mode::MaxPowerDef(def(3, 1.0))]
+        );
+        assert_eq!(conflicts, vec![MergeConflict::MaxPower(2)]);
+    }
+
+    #[test]
+    fn pets_replace_by_name() {
+        let pet = |name: &str, idle_snd: i32| PetDef { name: name.to_string(), idle_snd, ..Default::default() };
+        let base = vec![Mode::PetDefs(vec![pet("SLIME", -1), pet("BAT", -1)])];
+        let overlay = vec![Mode::PetDefs(vec![pet("BAT", 3), pet("DOG", -1)])];
+        let (modes, conflicts) = merge(base, overlay, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::PetDefs(vec![pet("SLIME", -1), pet("BAT", 3), pet("DOG", -1)])]);
+        assert_eq!(conflicts, vec![MergeConflict::Pet("BAT".to_string())]);
+    }
+
+    #[test]
+    fn lua_is_appended_and_redefinitions_reported() {
+        let base = vec![Mode::LuaAutorunCode("function LUA_CREATE0()\nend\n".to_string())];
+        let overlay = vec![Mode::LuaAutorunCode("print(1)\nfunction LUA_CREATE0()\nend\n".to_string())];
+        let (modes, conflicts) = merge(base, overlay, MergeOptions::default());
+        assert_eq!(
+            modes,
+            vec![Mode::LuaAutorunCode("function LUA_CREATE0()\nend\nprint(1)\nfunction LUA_CREATE0()\nend\n".to_string())]
+        );
+        assert_eq!(conflicts, vec![MergeConflict::LuaFunction { name: "LUA_CREATE0".to_string(), line: 2 }]);
+    }
+
+    #[test]
+    fn lua_already_in_the_base_is_still_appended() {
+        let base = vec![Mode::LuaAutorunCode("print(1)\nend\n\nprint(2)\n".to_string())];
+
+        // Short code that happens to be in the base isn't lost
+        let overlay = vec![Mode::LuaAutorunCode("end\n".to_string())];
+        let (modes, conflicts) = merge(base.clone(), overlay, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::LuaAutorunCode("print(1)\nend\n\nprint(2)\nend\n".to_string())]);
+        assert_eq!(conflicts, vec![MergeConflict::DuplicateLua { skipped: false }]);
+
+        // Only part of a line matches, so it isn't a copy
+        let overlay = vec![Mode::LuaAutorunCode("print(".to_string())];
+        let (modes, conflicts) = merge(base.clone(), overlay, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::LuaAutorunCode("print(1)\nend\n\nprint(2)\nprint(".to_string())]);
+        assert!(conflicts.is_empty());
+
+        // Blank code has nothing to add
+        let overlay = vec![Mode::LuaAutorunCode("\n  \n".to_string())];
+        let (modes, conflicts) = merge(base.clone(), overlay, MergeOptions::default());
+        assert_eq!(modes, base);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn copies_of_the_base_lua_can_be_skipped() {
+        let base = vec![Mode::LuaAutorunCode("print(1)\n\nprint(2)\n".to_string())];
+        let options = MergeOptions { skip_duplicate_lua: true, ..Default::default() };
+
+        // A copy with different blank lines and line endings
+        let overlay = vec![Mode::LuaAutorunCode("print(1)\r\nprint(2)  \r\n".to_string())];
+        let (modes, conflicts) = merge(base.clone(), overlay.clone(), options);
+        assert_eq!(modes, base);
+        assert_eq!(conflicts, vec![MergeConflict::DuplicateLua { skipped: true }]);
+
+        // Without the option the copy runs twice
+        let (modes, conflicts) = merge(base.clone(), overlay, MergeOptions::default());
+        assert_eq!(modes, vec![Mode::LuaAutorunCode("print(1)\n\nprint(2)\nprint(1)\r\nprint(2)  \r\n".to_string())]);
+        assert_eq!(conflicts, vec![MergeConflict::DuplicateLua { skipped: false }]);
+
+        // Part of the base isn't a copy of it
+        let overlay = vec![Mode::LuaAutorunCode("print(2)\n".to_string())];
+        let (modes, conflicts) = merge(base, overlay, options);
+        assert_eq!(modes, vec![Mode::LuaAutorunCode("print(1)\n\nprint(2)\nprint(2)\n".to_string())]);
+        assert_eq!(conflicts, vec![MergeConflict::DuplicateLua { skipped: false }]);
+    }
+
+    #[test]
+    fn conflicts_inside_the_base_are_not_reported() {
+        let cnma = Cnma {
+            modes: vec![
+                Mode::MusicIds(vec![resource(0, "a.mid")]),
+                Mode::MusicIds(vec![resource(0, "b.mid")]),
+            ],
+        };
+        let (merged, conflicts) = cnma.merge(&Cnma { modes: vec![] }, MergeOptions::default());
+        assert_eq!(merged.modes, vec![Mode::MusicIds(vec![resource(0, "b.mid")])]);
+        assert!(conflicts.is_empty());
+        assert_eq!(cnma.flattened().modes, merged.modes);
+    }
+
+    #[test]
+    fn layered_conflicts_point_to_the_file_that_caused_them() {
+        let dir = std::env::temp_dir().join(format!("cnmo_merge_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = [
+            // Conflicts inside of the base game's own config aren't from a mod
+            "MODE MUSIC\n0 a.mid\n0 b.mid\n",
+            "MODE MUSIC\n0 c.mid\n1 d.mid\n",
+            "MODE MUSIC\n1 e.mid\nMODE MUSIC\n1 f.mid\n",
+        ];
+        let paths = files
+            .iter()
+            .enumerate()
+            .map(|(idx, contents)| {
+                let path = dir.join(format!("layer{idx}.cnma"));
+                std::fs::write(&path, contents).unwrap();
+                path
+            })
+            .collect::<Vec<_>>();
+
+        let result = Cnma::from_files_layered(&paths, MergeOptions::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let (merged, conflicts) = result.unwrap();
+
+        assert_eq!(merged.modes, vec![Mode::MusicIds(vec![resource(0, "c.mid"), resource(1, "f.mid")])]);
+        let files = conflicts
+            .iter()
+            .map(|(idx, conflict)| match conflict {
+                MergeConflict::Resource { id, overlay, .. } => (*idx, *id, overlay.as_str()),
+                _ => panic!("unexpected conflict {conflict}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![(1, 0, "c.mid"), (2, 1, "e.mid"), (2, 1, "f.mid")]);
+    }
+}
@@ -0,0 +1,557 @@
+use std::fmt::Display;
+
+/// Lossless representation of a cnma file that keeps comments and formatting.
+pub mod cst;
+/// Parse errors and warnings that point to the text that caused them.
+pub mod diagnostic;
+/// Checking that the files music and sound ids point to exist.
+pub mod resources;
+/// Validating and previewing max power definitions.
+pub mod max_power;
+/// Highlighting, syntax checking and outlining the lua autorun code.
+pub mod lua;
+/// Layering mod configs over the base game config.
+pub mod merge;
+/// Checking that levels only use music and sound ids that exist.
+#[cfg(feature = "level_data")]
+pub mod references;
+/// Managing the level select order against the levels folder.
+#[cfg(feature = "level_data")]
+pub mod level_select;
+
+use diagnostic::{Diagnostic, LineParser, ParseOptions};
+
+/// Errors when dealing with CNMA files
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// [`from_file`](crate::cnma::Cnma::from_file) and [`save`](crate::cnma::Cnma::save) can return this
+    #[error("Can't open the file!")]
+    CantOpenFile {
+        /// The actuall [`std::io::Error`] from reading/writing to the file.
+        source: std::io::Error,
+    },
+    /// The file is not a CNM config file.
+    #[error("The file isn't a CNM Audio Definition file.")]
+    NotCnmaFile,
+    /// There was a corrupted entry at the line specified
+    #[error("Cnma file has a corrupt entry at line {0}!")]
+    CorruptedEntry(usize),
+    /// There was an mode field but it had no mode name
+    #[error("Cnma file has an entry without a mode!")]
+    NoMode,
+    /// The file is corrupted because of the string inside of the tuple variant
+    #[error("Cnma file is corrupted because of {0}!")]
+    Corrupted(String),
+    /// A line couldn't be parsed, the diagnostic points to where and why
+    #[error("{0}")]
+    Diagnostic(Box<diagnostic::Diagnostic>),
+}
+
+/// Used in the SoundID and the MusicID modes to specify what file a sound
+/// is related to and its related ID.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceId {
+    /// The ID for CNM Online. If 2 are the same, the last is used and a memory leak occurs.
+    pub id: u32,
+    /// The path to the file in question. It is relative based on the exe's directory and
+    /// doesn't need a "./" at the start.
+    pub path: String,
+}
+
+impl Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Id: {}, Path: {}", self.id, self.path))
+    }
+}
+
+impl ResourceId {
+    fn parse(parser: &mut LineParser) -> Result<Self, Error> {
+        const HINT: &str = "expected an id and a file path, like `3 sounds/eat.wav`";
+        Ok(ResourceId {
+            id: parser.required_number(0, "resource id", HINT)?,
+            path: parser.token(1, "file path", HINT)?.to_string(),
+        })
+    }
+}
+
+/// A power defintion for a particular skin id, or any id.
+/// These are activated when hitting the MaxPower trigger in game
+/// and modify your player stats.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MaxPowerDef {
+    /// The skin id for this power definition
+    pub id: u8,
+    /// The speed multiplier
+    pub speed: f32,
+    /// Jump multiplier
+    pub jump: f32,
+    /// Gravity multiplier
+    pub gravity: f32,
+    /// How many hp points are removed per second while the power is active
+    pub hpcost: f32,
+    /// The strength multiplier
+    pub strength: f32,
+    /// An optional double jump ability.
+    pub ability: Option<MaxPowerAbility>,
+}
+
+/// A ability activated on a double jump.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum MaxPowerAbility {
+    /// Basic double jump
+    #[default]
+    DoubleJump,
+    /// Basic flying ability akin to the normal wings power up
+    Flying,
+    /// Activates a sheild once you hit the ground for a short moment
+    /// of time
+    DropShield,
+    /// Allows you to bounce on enemies but not hurt them.
+    MarioBounce,
+}
+
+/// Pet AI Settings
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PetAI {
+    ///
+    Fly {
+        ///
+        num_fly_frames: u8,
+    },
+    ///
+    Walk {
+        ///
+        num_idle_frames: u8,
+        ///
+        num_walk_frames: u8,
+        ///
+        num_fall_frames: u8,
+    },
+    ///
+    Bounce {
+        ///
+        num_idle_frames: u8,
+        ///
+        num_bounce_frames: u8,
+        ///
+        jump_height: f32,
+        ///
+        bounce_idly: bool,
+    },
+}
+
+impl Default for PetAI {
+    fn default() -> Self {
+        Self::Fly {
+            num_fly_frames: 1,
+        }
+    }
+}
+
+/// A Pet definition
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PetDef {
+    ///
+    pub name: String,
+    ///
+    pub animbase: (u16, u16),
+    ///
+    pub iconbase: (u16, u16),
+    ///
+    pub ai: PetAI,
+    ///
+    pub idle_snd: i32,
+}
+
+impl Default for PetDef {
+    fn default() -> Self {
+        Self {
+            name: "SLIMYTEST".to_string(),
+            animbase: (0, 0),
+            iconbase: (0, 0),
+            ai: Default::default(),
+            idle_snd: -1,
+        }
+    }
+}
+
+impl PetDef {
+    /// The animations of the pet and the frames they use. Frames go right one
+    /// 32x32 tile at a time starting from `animbase`, in the order of the animations.
+    pub fn get_animations(&self) -> Vec<(&'static str, std::ops::Range<u16>)> {
+        let mut start = 0;
+        let mut next = |frames: u8| {
+            let range = start..start + frames as u16;
+            start = range.end;
+            range
+        };
+        match self.ai {
+            PetAI::Fly { num_fly_frames } => vec![("Fly", next(num_fly_frames))],
+            PetAI::Walk {
+                num_idle_frames,
+                num_walk_frames,
+                num_fall_frames,
+            } => vec![
+                ("Idle", next(num_idle_frames)),
+                ("Walk", next(num_walk_frames)),
+                ("Fall", next(num_fall_frames)),
+            ],
+            PetAI::Bounce {
+                num_idle_frames,
+                num_bounce_frames,
+                ..
+            } => vec![("Idle", next(num_idle_frames)), ("Bounce", next(num_bounce_frames))],
+        }
+    }
+
+    /// The pixel position of the top left of a frame in the gfx file
+    pub fn get_frame_pos(&self, frame: u16) -> (u32, u32) {
+        ((self.animbase.0 as u32 + frame as u32) * 32, self.animbase.1 as u32 * 32)
+    }
+
+    /// Parses a pet definition line, `line` is used for the line number in errors
+    /// and starts at 0.
+    pub fn from_string(s: &str, line: usize) -> Result<Self, Error> {
+        Self::parse(&mut LineParser::new_space_separated(line, s, ParseOptions::default(), &mut vec![]))
+    }
+
+    fn parse(parser: &mut LineParser) -> Result<Self, Error> {
+        const HINT: &str = "expected `\"name\" animx animy iconx icony idle_snd ai ...` where ai is `f fly_frames`, `w idle_frames walk_frames fall_frames` or `b idle_frames bounce_frames bounce_idly jump_height`";
+        if parser.len() < 7 {
+            return Err(parser.error(0..parser.line.len(), "pet definition is missing fields", HINT));
+        }
+        let name = parser.token(0, "name", HINT)?.trim_end_matches('"').trim_matches('"').to_owned();
+        let basex = parser.number(1, 0, "animation x position", HINT)?;
+        let basey = parser.number(2, 0, "animation y position", HINT)?;
+        let iconx = parser.number(3, 0, "icon x position", HINT)?;
+        let icony = parser.number(4, 0, "icon y position", HINT)?;
+        let idle_snd = parser.number(5, 0, "idle sound id", HINT)?;
+
+        let ai = match parser.token(6, "ai type", HINT)?.chars().next() {
+            Some('f') => PetAI::Fly {
+                num_fly_frames: parser.number(7, 1, "frame count", "expected `f fly_frames`")?,
+            },
+            Some('w') => {
+                const WALK_HINT: &str = "expected `w idle_frames walk_frames fall_frames`";
+                PetAI::Walk {
+                    num_idle_frames: parser.number(7, 1, "frame count", WALK_HINT)?,
+                    num_walk_frames: parser.number(8, 1, "frame count", WALK_HINT)?,
+                    num_fall_frames: parser.number(9, 1, "frame count", WALK_HINT)?,
+                }
+            },
+            Some('b') => {
+                const BOUNCE_HINT: &str = "expected `b idle_frames bounce_frames bounce_idly jump_height`";
+                PetAI::Bounce {
+                    num_idle_frames: parser.number(7, 1, "frame count", BOUNCE_HINT)?,
+                    num_bounce_frames: parser.number(8, 1, "frame count", BOUNCE_HINT)?,
+                    bounce_idly: parser.number(9, 0, "bounce idly flag", BOUNCE_HINT)? != 0,
+                    jump_height: parser.number(10, 2.0, "jump height", BOUNCE_HINT)?,
+                }
+            },
+            _ => return Err(parser.error(parser.span(6), "unknown pet ai type", "expected `f` (fly), `w` (walk) or `b` (bounce)")),
+        };
+
+        Ok(Self {
+            name,
+            animbase: (basex, basey),
+            iconbase: (iconx, icony),
+            idle_snd,
+            ai,
+        })
+    }
+
+    ///
+    pub fn as_string(&self) -> String {
+        "\"".to_string() + &self.name + "\" " + &self.animbase.0.to_string() + " " + &self.animbase.1.to_string() + " " + 
+            &self.iconbase.0.to_string() + " " + &self.iconbase.1.to_string() + " " +
+            &self.idle_snd.to_string() + " " + 
+        &(match self.ai {
+            PetAI::Fly {
+                ref num_fly_frames,
+            } => {
+                "f ".to_string() + &num_fly_frames.to_string()
+            },
+            PetAI::Bounce {
+                ref num_idle_frames,
+                ref num_bounce_frames,
+                ref bounce_idly,
+                ref jump_height,
+            } => {
+                "b ".to_string() + &num_idle_frames.to_string() + " " + &num_bounce_frames.to_string() + " " + if *bounce_idly { "1" } else { "0" } + " " + &jump_height.to_string()
+            },
+            PetAI::Walk {
+                ref num_idle_frames,
+                ref num_walk_frames,
+                ref num_fall_frames,
+            } => {
+                "w ".to_string() + &num_idle_frames.to_string() + " " + &num_walk_frames.to_string() + " " + &num_fall_frames.to_string()
+            }
+        })
+    }
+}
+
+/// What section/mode contents there are
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "mode", content = "data"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    /// Music Resource Ids
+    MusicIds(Vec<ResourceId>),
+    /// Sound Resource Ids
+    SoundIds(Vec<ResourceId>),
+    /// A deprecated CNM Online section
+    MusicVolumeOverride,
+    /// The order of levels on the level select menu, same order
+    /// as the strings in the vector here.
+    LevelSelectOrder(Vec<(String, u32)>),
+    /// A power defintion for a particular skin
+    MaxPowerDef(MaxPowerDef),
+    /// Code run at the beginning of the game, certain function names
+    /// will run as hooks for object code, etc.
+    LuaAutorunCode(String),
+    /// Pet Definitions
+    PetDefs(Vec<PetDef>),
+}
+
+/// CNMA file. Holds generic configuration of the game and resource
+/// locations.
+/// - Sound and music ids and file paths
+/// - Lua scripting code
+/// - Custom upgrade/powers
+/// - The order of the level select menu
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug)]
+pub struct Cnma {
+    /// Vector of the sections of the file.
+    pub modes: Vec<Mode>,
+}
+
+impl Cnma {
+    /// Load a Cnma config from a file
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let s = match std::fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) => return Err(Error::CantOpenFile { source: e }),
+        };
+        Self::from_string(s.as_str())
+    }
+
+    /// Load a Cnma config from a string. Blank lines and comments (lines starting
+    /// with `#`) are skipped, except for inside of Lua code.
+    pub fn from_string(s: &str) -> Result<Self, Error> {
+        Ok(cst::CnmaDocument::from_string(s)?.cnma())
+    }
+
+    /// Load a Cnma config from a file, also returning the warnings found while parsing
+    pub fn from_file_with_options<P: AsRef<std::path::Path>>(path: P, options: ParseOptions) -> Result<(Self, Vec<Diagnostic>), Error> {
+        let (document, warnings) = cst::CnmaDocument::from_file_with_options(path, options)?;
+        Ok((document.cnma(), warnings))
+    }
+
+    /// Load a Cnma config from a string, also returning the warnings found while parsing
+    pub fn from_string_with_options(s: &str, options: ParseOptions) -> Result<(Self, Vec<Diagnostic>), Error> {
+        let (document, warnings) = cst::CnmaDocument::from_string_with_options(s, options)?;
+        Ok((document.cnma(), warnings))
+    }
+
+    /// Every music resource in the file in order
+    pub fn get_music_ids(&self) -> Vec<&ResourceId> {
+        self.modes
+            .iter()
+            .filter_map(|mode| match mode {
+                Mode::MusicIds(ids) => Some(ids.iter()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Every sound resource in the file in order
+    pub fn get_sound_ids(&self) -> Vec<&ResourceId> {
+        self.modes
+            .iter()
+            .filter_map(|mode| match mode {
+                Mode::SoundIds(ids) => Some(ids.iter()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// The music resource the game uses for an id, if there's more than one it
+    /// uses the last.
+    pub fn get_music(&self, id: u32) -> Option<&ResourceId> {
+        self.get_music_ids().into_iter().rev().find(|res| res.id == id)
+    }
+
+    /// The sound resource the game uses for an id, if there's more than one it
+    /// uses the last.
+    pub fn get_sound(&self, id: u32) -> Option<&ResourceId> {
+        self.get_sound_ids().into_iter().rev().find(|res| res.id == id)
+    }
+
+    /// The names of the levels on the level select menu in the order they show up
+    pub fn get_level_names(&self) -> Vec<&str> {
+        self.modes
+            .iter()
+            .filter_map(|mode| match mode {
+                Mode::LevelSelectOrder(levels) => Some(levels.iter().map(|(name, _)| name.as_str())),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Saves the cnma file to the path specified, creates the file if it doesn't
+    /// exist and overwrites it if it does.
+    ///
+    /// This regenerates the whole file, use [`cst::CnmaDocument`] to keep the
+    /// comments and formatting of a file that was loaded.
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), Error> {
+        match std::fs::write(path, cst::CnmaDocument::default().write(self)) {
+            Err(e) => Err(Error::CantOpenFile { source: e }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_trivia(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+fn parse_mode_header(parser: &mut LineParser) -> Result<Mode, Error> {
+    const HINT: &str = "expected one of MUSIC, SOUNDS, MUSIC_VOLUME_OVERRIDE, LEVELSELECT_ORDER, MAXPOWER<id>, LUA_AUTORUN or PETDEFS";
+    match parser.token(1, "mode name", HINT)? {
+        "MUSIC" => Ok(Mode::MusicIds(Vec::new())),
+        "SOUNDS" => Ok(Mode::SoundIds(Vec::new())),
+        "MUSIC_VOLUME_OVERRIDE" => Ok(Mode::MusicVolumeOverride),
+        "LEVELSELECT_ORDER" => Ok(Mode::LevelSelectOrder(Vec::new())),
+        s if s.starts_with("MAXPOWER") => Ok(Mode::MaxPowerDef(MaxPowerDef {
+            id: parser.number_from(
+                &s[s.find(|c: char| c.is_digit(10)).unwrap_or(s.len())..s.len()],
+                1,
+                0,
+                "skin id",
+                "expected the skin id after MAXPOWER, like `MODE MAXPOWER3`",
+            )?,
+            ..Default::default()
+        })),
+        "LUA_AUTORUN" => Ok(Mode::LuaAutorunCode("".to_string())),
+        "PETDEFS" => Ok(Mode::PetDefs(vec![])),
+        mode_name => Err(parser.error(parser.span(1), format!("unknown mode name \"{mode_name}\""), HINT)),
+    }
+}
+
+fn parse_mode_entry(mode: &mut Mode, parser: &mut LineParser) -> Result<(), Error> {
+    match mode {
+        Mode::MusicIds(v) => {
+            v.push(ResourceId::parse(parser)?)
+        }
+        Mode::SoundIds(v) => {
+            v.push(ResourceId::parse(parser)?)
+        }
+        Mode::MusicVolumeOverride => {}
+        Mode::LevelSelectOrder(v) => {
+            const HINT: &str = "expected a level file name and its score, like `lvl1 1000`";
+            let name = parser.token(0, "level name", HINT)?;
+            let score = match parser.token(1, "score", HINT)? {
+                "_" => 0,
+                _ => parser.number(1, 0, "score", HINT)?,
+            };
+            v.push((name.to_string(), score));
+        }
+        Mode::MaxPowerDef(def) => {
+            const HINT: &str = "expected one of spd, jmp, grav, hpcost, strength or ability followed by its value, like `spd 1.5`";
+            let field_name = parser.token(0, "field name", HINT)?;
+            parser.token(1, "field value", HINT)?;
+
+            match field_name {
+                "spd" => def.speed = parser.number(1, 0.0, "speed multiplier", "expected the speed multiplier, like `spd 1.5`")?,
+                "jmp" => def.jump = parser.number(1, 0.0, "jump multiplier", "expected the jump multiplier, like `jmp 1.5`")?,
+                "grav" => def.gravity = parser.number(1, 0.0, "gravity multiplier", "expected the gravity multiplier, like `grav 0.5`")?,
+                "hpcost" => def.hpcost = parser.number(1, 0.0, "hp cost", "expected the hp lost per second, like `hpcost 1`")?,
+                "strength" => def.strength = parser.number(1, 0.0, "strength multiplier", "expected the strength multiplier, like `strength 2`")?,
+                "ability" => {
+                    const ABILITY_HINT: &str = "expected 0 (none), 1 (double jump), 2 (flying), 3 (drop shield) or 4 (mario bounce)";
+                    def.ability = match parser.number(1, 0, "ability id", ABILITY_HINT)? {
+                        0 => None,
+                        id => match MaxPowerAbility::from_ability_id(id) {
+                            Some(ability) => Some(ability),
+                            None => return Err(parser.error(parser.span(1), format!("unknown ability id {id}"), ABILITY_HINT)),
+                        },
+                    }
+                }
+                _ => return Err(parser.error(parser.span(0), format!("unknown max power field \"{field_name}\""), HINT)),
+            };
+        }
+        Mode::LuaAutorunCode(code) => {
+            code.push_str((parser.line.to_string() + "\n").as_str());
+        }
+        Mode::PetDefs(defs) => {
+            defs.push(PetDef::parse(parser)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn mode_header(mode: &Mode) -> String {
+    match mode {
+        Mode::MusicIds(_) => "MODE MUSIC".to_string(),
+        Mode::SoundIds(_) => "MODE SOUNDS".to_string(),
+        Mode::MusicVolumeOverride => "MODE MUSIC_VOLUME_OVERRIDE".to_string(),
+        Mode::LevelSelectOrder(_) => "MODE LEVELSELECT_ORDER".to_string(),
+        Mode::MaxPowerDef(def) => format!("MODE MAXPOWER{}", def.id),
+        Mode::LuaAutorunCode(_) => "MODE LUA_AUTORUN".to_string(),
+        Mode::PetDefs(_) => "MODE PETDEFS".to_string(),
+    }
+}
+
+fn mode_footer(mode: &Mode) -> Option<&'static str> {
+    match mode {
+        Mode::LuaAutorunCode(_) => Some("__ENDLUA__"),
+        Mode::PetDefs(_) => Some("ENDPETS"),
+        _ => None,
+    }
+}
+
+fn max_power_ability_id(ability: &Option<MaxPowerAbility>) -> u32 {
+    ability.as_ref().map_or(0, MaxPowerAbility::get_ability_id)
+}
+
+/// The names of the fields in a max power section and their values as text
+fn max_power_fields(def: &MaxPowerDef) -> [(&'static str, String); 6] {
+    [
+        ("spd", def.speed.to_string()),
+        ("jmp", def.jump.to_string()),
+        ("grav", def.gravity.to_string()),
+        ("hpcost", def.hpcost.to_string()),
+        ("strength", def.strength.to_string()),
+        ("ability", max_power_ability_id(&def.ability).to_string()),
+    ]
+}
+
+/// The lines of a mode in the order they appear in the file, without the header and footer
+fn mode_entry_lines(mode: &Mode) -> Vec<String> {
+    match mode {
+        Mode::MusicIds(v) | Mode::SoundIds(v) => {
+            v.iter().map(|res| format!("{} {}", res.id, res.path)).collect()
+        }
+        Mode::MusicVolumeOverride => vec![],
+        Mode::LevelSelectOrder(v) => {
+            v.iter().rev().map(|lvl| format!("{} {}", lvl.0, lvl.1)).collect()
+        }
+        Mode::MaxPowerDef(def) => {
+            max_power_fields(def).iter().map(|(name, value)| format!("{name} {value}")).collect()
+        }
+        Mode::LuaAutorunCode(s) => s.lines().map(|line| line.to_string()).collect(),
+        Mode::PetDefs(pets) => pets.iter().map(|pet| pet.as_string()).collect(),
+    }
+}
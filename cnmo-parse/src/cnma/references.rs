@@ -0,0 +1,122 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use super::level_select::load_level;
+use super::{Cnma, Mode};
+use crate::lparse::level_data::cnms_types::wobj_type::WobjType;
+use crate::lparse::level_data::LevelData;
+
+/// An id used by a level or pet that isn't in the music or sound lists
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferenceProblem {
+    /// A tunes trigger plays music that doesn't exist
+    UnknownMusic {
+        /// The level file name without the extension
+        level: String,
+        /// Index of the spawner in the level
+        spawner: usize,
+        /// The music id it uses
+        music_id: u32,
+    },
+    /// A sound effect point plays a sound that doesn't exist
+    UnknownSound {
+        /// The level file name without the extension
+        level: String,
+        /// Index of the spawner in the level
+        spawner: usize,
+        /// The sound id it uses
+        sound_id: u32,
+    },
+    /// A pet's idle sound doesn't exist
+    UnknownPetSound {
+        /// Name of the pet
+        pet: String,
+        /// The sound id it uses
+        sound_id: i32,
+    },
+    /// A level on the level select menu couldn't be loaded to check it
+    UnloadableLevel {
+        /// The level file name without the extension
+        level: String,
+        /// Why it couldn't be loaded
+        error: String,
+    },
+}
+
+impl Display for ReferenceProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferenceProblem::UnknownMusic { level, spawner, music_id } => {
+                write!(f, "{level}: tunes trigger #{spawner} uses music id {music_id} which doesn't exist")
+            }
+            ReferenceProblem::UnknownSound { level, spawner, sound_id } => {
+                write!(f, "{level}: sound effect point #{spawner} uses sound id {sound_id} which doesn't exist")
+            }
+            ReferenceProblem::UnknownPetSound { pet, sound_id } => {
+                write!(f, "pet {pet} uses idle sound id {sound_id} which doesn't exist")
+            }
+            ReferenceProblem::UnloadableLevel { level, error } => {
+                write!(f, "{level}: can't be loaded to check it ({error})")
+            }
+        }
+    }
+}
+
+impl Cnma {
+    /// Finds the music and sound ids used in a level that aren't in this config.
+    /// `level` is only used to label the problems.
+    pub fn check_level_references(&self, level: &str, level_data: &LevelData) -> Vec<ReferenceProblem> {
+        let mut problems = Vec::new();
+        for (idx, spawner) in level_data.spawners.iter().enumerate() {
+            match spawner.type_data {
+                WobjType::TunesTrigger { music_id, .. } if self.get_music(music_id).is_none() => {
+                    problems.push(ReferenceProblem::UnknownMusic {
+                        level: level.to_string(),
+                        spawner: idx,
+                        music_id,
+                    })
+                }
+                WobjType::SfxPoint { sound_id } if self.get_sound(sound_id).is_none() => {
+                    problems.push(ReferenceProblem::UnknownSound {
+                        level: level.to_string(),
+                        spawner: idx,
+                        sound_id,
+                    })
+                }
+                _ => {}
+            }
+        }
+        problems
+    }
+
+    /// Checks the pets idle sounds and every level on the level select menu,
+    /// loading them from `<levels_dir>/<name>.cnmb` and `.cnms`.
+    pub fn check_references<P: AsRef<Path>>(&self, levels_dir: P) -> Vec<ReferenceProblem> {
+        let mut problems = Vec::new();
+
+        for mode in self.modes.iter() {
+            if let Mode::PetDefs(pets) = mode {
+                for pet in pets.iter() {
+                    if pet.idle_snd >= 0 && self.get_sound(pet.idle_snd as u32).is_none() {
+                        problems.push(ReferenceProblem::UnknownPetSound {
+                            pet: pet.name.clone(),
+                            sound_id: pet.idle_snd,
+                        });
+                    }
+                }
+            }
+        }
+
+        for level in self.get_level_names() {
+            match load_level(levels_dir.as_ref(), level) {
+                Ok(level_data) => problems.extend(self.check_level_references(level, &level_data)),
+                Err(err) => problems.push(ReferenceProblem::UnloadableLevel {
+                    level: level.to_string(),
+                    error: err.to_string(),
+                }),
+            }
+        }
+
+        problems
+    }
+}
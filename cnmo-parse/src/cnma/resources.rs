@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use super::{Cnma, Mode};
+
+/// Which list a resource id is from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResourceKind {
+    /// From a `MODE MUSIC` section, files are in the `music/` folder
+    Music,
+    /// From a `MODE SOUNDS` section, files are in the `sounds/` folder
+    Sound,
+}
+
+impl ResourceKind {
+    /// The folder the game keeps these resources in
+    pub fn get_folder(&self) -> &'static str {
+        match self {
+            ResourceKind::Music => "music",
+            ResourceKind::Sound => "sounds",
+        }
+    }
+}
+
+impl Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceKind::Music => f.write_str("music"),
+            ResourceKind::Sound => f.write_str("sound"),
+        }
+    }
+}
+
+/// Something wrong with the resources a cnma file points to
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceProblem {
+    /// The file doesn't exist at all
+    MissingFile {
+        /// The list the id is in
+        kind: ResourceKind,
+        /// The id of the resource
+        id: u32,
+        /// The path written in the cnma file
+        path: String,
+    },
+    /// The file only exists with different capitalization, which works on
+    /// Windows but not on Linux.
+    CaseMismatch {
+        /// The list the id is in
+        kind: ResourceKind,
+        /// The id of the resource
+        id: u32,
+        /// The path written in the cnma file
+        path: String,
+        /// The path as it actually is on disk
+        actual: String,
+    },
+    /// The same id is used more than once, the game uses the last one and
+    /// leaks the memory of the others.
+    DuplicateId {
+        /// The list the id is in
+        kind: ResourceKind,
+        /// The id used more than once
+        id: u32,
+        /// Every path that uses the id, in file order
+        paths: Vec<String>,
+    },
+    /// No resources use the ids in this range (inclusive).
+    IdGap {
+        /// The list with the gap
+        kind: ResourceKind,
+        /// First unused id
+        start: u32,
+        /// Last unused id
+        end: u32,
+    },
+    /// A file in the resource folder that no id points to
+    UnreferencedFile {
+        /// What folder the file is in
+        kind: ResourceKind,
+        /// Path of the file relative to the root
+        path: String,
+    },
+}
+
+impl ResourceProblem {
+    /// If the game will fail to load something because of this problem
+    pub fn is_error(&self) -> bool {
+        matches!(self, ResourceProblem::MissingFile { .. } | ResourceProblem::CaseMismatch { .. })
+    }
+}
+
+impl Display for ResourceProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceProblem::MissingFile { kind, id, path } => {
+                write!(f, "{kind} {id}: {path} doesn't exist")
+            }
+            ResourceProblem::CaseMismatch { kind, id, path, actual } => {
+                write!(f, "{kind} {id}: {path} is named {actual} on disk, this breaks on Linux")
+            }
+            ResourceProblem::DuplicateId { kind, id, paths } => {
+                write!(f, "{kind} {id} is used {} times ({})", paths.len(), paths.join(", "))
+            }
+            ResourceProblem::IdGap { kind, start, end } if start == end => {
+                write!(f, "{kind} id {start} is unused")
+            }
+            ResourceProblem::IdGap { kind, start, end } => {
+                write!(f, "{kind} ids {start} to {end} are unused")
+            }
+            ResourceProblem::UnreferencedFile { kind, path } => {
+                write!(f, "{path} isn't used by any {kind} id")
+            }
+        }
+    }
+}
+
+impl Cnma {
+    /// Checks the music and sound ids against the files in `root`, which should be
+    /// the folder the game's exe is in. Problems are sorted by kind then id.
+    pub fn check_resources<P: AsRef<Path>>(&self, root: P) -> Vec<ResourceProblem> {
+        let root = root.as_ref();
+        let mut problems = Vec::new();
+
+        let mut resources: BTreeMap<ResourceKind, Vec<(u32, &str)>> = BTreeMap::new();
+        for mode in self.modes.iter() {
+            match mode {
+                Mode::MusicIds(ids) => resources
+                    .entry(ResourceKind::Music)
+                    .or_default()
+                    .extend(ids.iter().map(|res| (res.id, res.path.as_str()))),
+                Mode::SoundIds(ids) => resources
+                    .entry(ResourceKind::Sound)
+                    .or_default()
+                    .extend(ids.iter().map(|res| (res.id, res.path.as_str()))),
+                _ => {}
+            }
+        }
+
+        for kind in [ResourceKind::Music, ResourceKind::Sound] {
+            let ids = resources.get(&kind).map(|ids| ids.as_slice()).unwrap_or_default();
+            let mut by_id: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+            for (id, path) in ids.iter() {
+                by_id.entry(*id).or_default().push(path);
+                match find_file(root, path) {
+                    Some(actual) if actual == normalize(path) => {}
+                    Some(actual) => problems.push(ResourceProblem::CaseMismatch {
+                        kind,
+                        id: *id,
+                        path: path.to_string(),
+                        actual,
+                    }),
+                    None => problems.push(ResourceProblem::MissingFile {
+                        kind,
+                        id: *id,
+                        path: path.to_string(),
+                    }),
+                }
+            }
+
+            let mut next_id = 0;
+            for (id, paths) in by_id.iter() {
+                if *id > next_id {
+                    problems.push(ResourceProblem::IdGap { kind, start: next_id, end: id - 1 });
+                }
+                next_id = id + 1;
+                if paths.len() > 1 {
+                    problems.push(ResourceProblem::DuplicateId {
+                        kind,
+                        id: *id,
+                        paths: paths.iter().map(|path| path.to_string()).collect(),
+                    });
+                }
+            }
+
+            let referenced: BTreeSet<String> = ids.iter().map(|(_, path)| normalize(path).to_lowercase()).collect();
+            let mut files = Vec::new();
+            list_files(root, Path::new(kind.get_folder()), &mut files);
+            files.sort();
+            for file in files {
+                if !referenced.contains(&file.to_lowercase()) {
+                    problems.push(ResourceProblem::UnreferencedFile { kind, path: file });
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// Paths in cnma files can use either slash and start with "./"
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+/// Finds the file a path points to by going through each folder, so a path that only
+/// matches with different capitalization can be found on any file system. Returns the
+/// path with the capitalization used on disk.
+fn find_file(root: &Path, path: &str) -> Option<String> {
+    let mut dir = root.to_path_buf();
+    let mut actual = Vec::new();
+    for component in normalize(path).split('/').filter(|c| !c.is_empty()) {
+        let names: Vec<String> = std::fs::read_dir(&dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        let name = names
+            .iter()
+            .find(|name| name.as_str() == component)
+            .or_else(|| names.iter().find(|name| name.eq_ignore_ascii_case(component)))?;
+        dir.push(name);
+        actual.push(name.clone());
+    }
+    if dir.is_file() {
+        Some(actual.join("/"))
+    } else {
+        None
+    }
+}
+
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<String>) {
+    let entries = match std::fs::read_dir(root.join(dir)) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path: PathBuf = dir.join(entry.file_name());
+        if entry.path().is_dir() {
+            list_files(root, &path, files);
+        } else {
+            files.push(path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
@@ -15,10 +15,11 @@
 //!     of a CNM online level file, and has functions to save and load
 //!     them from their respective .cnmb and .cnms lparse files.
 //! - "serde" which adds serde traits for said level data structs
-//!     so that the level data can also additionally be saved to any
-//!     other format that you want. This is NOT a serde implementation
-//!     for lparse or cnma files (and you need "level_data" feature
-//!     enabled already).
+//!     and the cnma config structs so that they can also additionally
+//!     be saved to any other format that you want, like JSON or TOML.
+//!     This is NOT a serde implementation for the lparse or cnma file
+//!     formats themselves (and you need the "level_data" feature
+//!     enabled for the level data structs).
 //! 
 //! Heres the [`Github Link`]
 //! 
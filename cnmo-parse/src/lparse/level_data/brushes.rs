@@ -0,0 +1,326 @@
+use super::cnmb_types::{Cells, TileId};
+
+/// A saved pattern of cells, with both tile layers and the light level.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamp {
+    /// Name shown in the brush library
+    pub name: String,
+    /// The cells of the pattern, painted from the top left
+    pub cells: Cells,
+}
+
+/// Which part of a patch of terrain a tile is drawn for.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TerrainPiece {
+    /// Surrounded by terrain on all sides
+    Fill,
+    /// Nothing above
+    Top,
+    /// Nothing below
+    Bottom,
+    /// Nothing to the left
+    Left,
+    /// Nothing to the right
+    Right,
+    /// Nothing above or to the left
+    TopLeft,
+    /// Nothing above or to the right
+    TopRight,
+    /// Nothing below or to the left
+    BottomLeft,
+    /// Nothing below or to the right
+    BottomRight,
+    /// Terrain on all sides but not diagonally up and to the left
+    InnerTopLeft,
+    /// Terrain on all sides but not diagonally up and to the right
+    InnerTopRight,
+    /// Terrain on all sides but not diagonally down and to the left
+    InnerBottomLeft,
+    /// Terrain on all sides but not diagonally down and to the right
+    InnerBottomRight,
+}
+
+impl TerrainPiece {
+    /// Every piece, in the order they're usually laid out in a tileset
+    pub const ALL: [TerrainPiece; 13] = [
+        TerrainPiece::TopLeft,
+        TerrainPiece::Top,
+        TerrainPiece::TopRight,
+        TerrainPiece::Left,
+        TerrainPiece::Fill,
+        TerrainPiece::Right,
+        TerrainPiece::BottomLeft,
+        TerrainPiece::Bottom,
+        TerrainPiece::BottomRight,
+        TerrainPiece::InnerTopLeft,
+        TerrainPiece::InnerTopRight,
+        TerrainPiece::InnerBottomLeft,
+        TerrainPiece::InnerBottomRight,
+    ];
+
+    /// Picks the piece for a cell from which of its 8 neighbors are part of
+    /// the same terrain. `neighbors` goes left to right, top to bottom
+    /// skipping the cell itself, so index 1 is above and 6 is below.
+    pub fn from_neighbors(neighbors: [bool; 8]) -> Self {
+        let [up_left, up, up_right, left, right, down_left, down, down_right] = neighbors;
+        match (up, down, left, right) {
+            (false, _, false, _) => TerrainPiece::TopLeft,
+            (false, _, _, false) => TerrainPiece::TopRight,
+            (_, false, false, _) => TerrainPiece::BottomLeft,
+            (_, false, _, false) => TerrainPiece::BottomRight,
+            (false, _, _, _) => TerrainPiece::Top,
+            (_, false, _, _) => TerrainPiece::Bottom,
+            (_, _, false, _) => TerrainPiece::Left,
+            (_, _, _, false) => TerrainPiece::Right,
+            _ if !up_left => TerrainPiece::InnerTopLeft,
+            _ if !up_right => TerrainPiece::InnerTopRight,
+            _ if !down_left => TerrainPiece::InnerBottomLeft,
+            _ if !down_right => TerrainPiece::InnerBottomRight,
+            _ => TerrainPiece::Fill,
+        }
+    }
+
+    /// The piece to use instead when a rule doesn't have this one
+    fn fallback(&self) -> Option<Self> {
+        match self {
+            TerrainPiece::Fill => None,
+            TerrainPiece::TopLeft | TerrainPiece::TopRight => Some(TerrainPiece::Top),
+            TerrainPiece::BottomLeft | TerrainPiece::BottomRight => Some(TerrainPiece::Bottom),
+            _ => Some(TerrainPiece::Fill),
+        }
+    }
+}
+
+/// A set of tiles tagged as the pieces of one kind of terrain, so painting
+/// the terrain can pick the right tile for each cell from its neighbors.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AutoTileRule {
+    /// Name shown in the brush library
+    pub name: String,
+    /// The tile id used for each piece. A piece can only have one tile.
+    pub pieces: Vec<(TerrainPiece, u16)>,
+}
+
+impl AutoTileRule {
+    /// Creates a rule without any pieces
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pieces: Vec::new(),
+        }
+    }
+
+    /// The tile tagged as the piece, if there is one
+    pub fn get_piece(&self, piece: TerrainPiece) -> Option<u16> {
+        self.pieces.iter().find(|(p, _)| *p == piece).map(|(_, id)| *id)
+    }
+
+    /// Tags the tile as the piece, or untags the piece if `id` is `None`
+    pub fn set_piece(&mut self, piece: TerrainPiece, id: Option<u16>) {
+        self.pieces.retain(|(p, _)| *p != piece);
+        if let Some(id) = id {
+            self.pieces.push((piece, id));
+        }
+    }
+
+    /// The tile to draw for the piece, falling back to a similar piece if the
+    /// rule doesn't have one for it. Only `None` if the rule has no pieces.
+    pub fn get_tile(&self, piece: TerrainPiece) -> Option<u16> {
+        let mut piece = Some(piece);
+        while let Some(current) = piece {
+            if let Some(id) = self.get_piece(current) {
+                return Some(id);
+            }
+            piece = current.fallback();
+        }
+        self.pieces.first().map(|(_, id)| *id)
+    }
+
+    /// If the tile is one of the pieces of this terrain
+    pub fn contains(&self, id: TileId) -> bool {
+        id.0.is_some_and(|id| self.pieces.iter().any(|(_, piece)| *piece == id))
+    }
+
+    /// Adds (or removes if `place` is false) the terrain at a cell on one of
+    /// the layers, then updates the cell and the cells around it to the
+    /// pieces that fit their neighbors. Cells outside the grid are ignored.
+    pub fn paint(&self, cells: &mut Cells, x: i32, y: i32, foreground: bool, place: bool) {
+        if !in_bounds(cells, x, y) {
+            return;
+        }
+        let layer = |cells: &Cells, x: i32, y: i32| -> TileId {
+            let cell = cells.get_cell(x, y);
+            if foreground { cell.foreground } else { cell.background }
+        };
+        if place {
+            if !self.contains(layer(cells, x, y)) {
+                // Any piece marks the cell as terrain until it's updated below
+                set_layer(cells, x, y, foreground, TileId(self.get_tile(TerrainPiece::Fill)));
+            }
+        } else if self.contains(layer(cells, x, y)) {
+            set_layer(cells, x, y, foreground, TileId(None));
+        }
+        for ny in y - 1..=y + 1 {
+            for nx in x - 1..=x + 1 {
+                if in_bounds(cells, nx, ny) && self.contains(layer(cells, nx, ny)) {
+                    let piece = TerrainPiece::from_neighbors(self.get_neighbors(cells, nx, ny, foreground));
+                    set_layer(cells, nx, ny, foreground, TileId(self.get_tile(piece)));
+                }
+            }
+        }
+    }
+
+    fn get_neighbors(&self, cells: &Cells, x: i32, y: i32, foreground: bool) -> [bool; 8] {
+        let mut neighbors = [false; 8];
+        let mut idx = 0;
+        for ny in y - 1..=y + 1 {
+            for nx in x - 1..=x + 1 {
+                if nx == x && ny == y {
+                    continue;
+                }
+                // The edge of the level counts as more terrain so it doesn't get a border
+                neighbors[idx] = !in_bounds(cells, nx, ny) || {
+                    let cell = cells.get_cell(nx, ny);
+                    self.contains(if foreground { cell.foreground } else { cell.background })
+                };
+                idx += 1;
+            }
+        }
+        neighbors
+    }
+}
+
+/// If the cell is inside of the grid. Positions outside of it get clamped
+/// onto the border cells by [`Cells::get_cell`], so check this first when
+/// that isn't wanted.
+pub fn in_bounds(cells: &Cells, x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < cells.width() as i32 && y < cells.height() as i32
+}
+
+fn set_layer(cells: &mut Cells, x: i32, y: i32, foreground: bool, id: TileId) {
+    let cell = cells.get_cell_mut(x, y);
+    if foreground {
+        cell.foreground = id;
+    } else {
+        cell.background = id;
+    }
+}
+
+/// The stamps and auto-tiling rules saved for a project.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BrushLibrary {
+    /// Saved multi-cell patterns
+    pub stamps: Vec<Stamp>,
+    /// Terrain auto-tiling rules
+    pub auto_tiles: Vec<AutoTileRule>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A rule with a different tile for every piece, numbered by `ALL`
+    fn full_rule() -> AutoTileRule {
+        let mut rule = AutoTileRule::new("test");
+        for (id, piece) in TerrainPiece::ALL.iter().enumerate() {
+            rule.set_piece(*piece, Some(id as u16 + 1));
+        }
+        rule
+    }
+
+    fn piece_at(rule: &AutoTileRule, cells: &Cells, x: i32, y: i32) -> Option<TerrainPiece> {
+        let id = cells.get_cell(x, y).foreground.0?;
+        rule.pieces.iter().find(|(_, piece_id)| *piece_id == id).map(|(piece, _)| *piece)
+    }
+
+    #[test]
+    fn pieces_from_neighbors() {
+        let all = [true; 8];
+        let without = |idx: &[usize]| {
+            let mut neighbors = all;
+            for i in idx {
+                neighbors[*i] = false;
+            }
+            neighbors
+        };
+        assert_eq!(TerrainPiece::from_neighbors(all), TerrainPiece::Fill);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[1])), TerrainPiece::Top);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[6])), TerrainPiece::Bottom);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[3])), TerrainPiece::Left);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[4])), TerrainPiece::Right);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[1, 3])), TerrainPiece::TopLeft);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[1, 4])), TerrainPiece::TopRight);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[6, 3])), TerrainPiece::BottomLeft);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[6, 4])), TerrainPiece::BottomRight);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[0])), TerrainPiece::InnerTopLeft);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[2])), TerrainPiece::InnerTopRight);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[5])), TerrainPiece::InnerBottomLeft);
+        assert_eq!(TerrainPiece::from_neighbors(without(&[7])), TerrainPiece::InnerBottomRight);
+        // Diagonals only matter when every side is terrain
+        assert_eq!(TerrainPiece::from_neighbors(without(&[0, 1])), TerrainPiece::Top);
+        assert_eq!(TerrainPiece::from_neighbors([false; 8]), TerrainPiece::TopLeft);
+    }
+
+    #[test]
+    fn missing_pieces_fall_back() {
+        let mut rule = AutoTileRule::new("test");
+        assert_eq!(rule.get_tile(TerrainPiece::Fill), None);
+        rule.set_piece(TerrainPiece::Fill, Some(1));
+        rule.set_piece(TerrainPiece::Top, Some(2));
+        assert_eq!(rule.get_tile(TerrainPiece::TopLeft), Some(2));
+        assert_eq!(rule.get_tile(TerrainPiece::InnerBottomRight), Some(1));
+        assert_eq!(rule.get_tile(TerrainPiece::BottomLeft), Some(1));
+        rule.set_piece(TerrainPiece::Top, None);
+        assert_eq!(rule.get_tile(TerrainPiece::TopLeft), Some(1));
+    }
+
+    #[test]
+    fn paint_picks_pieces() {
+        let rule = full_rule();
+        let mut cells = Cells::new(7, 7);
+        for y in 2..=4 {
+            for x in 2..=4 {
+                rule.paint(&mut cells, x, y, true, true);
+            }
+        }
+        let expected = [
+            [TerrainPiece::TopLeft, TerrainPiece::Top, TerrainPiece::TopRight],
+            [TerrainPiece::Left, TerrainPiece::Fill, TerrainPiece::Right],
+            [TerrainPiece::BottomLeft, TerrainPiece::Bottom, TerrainPiece::BottomRight],
+        ];
+        for (y, row) in expected.iter().enumerate() {
+            for (x, piece) in row.iter().enumerate() {
+                assert_eq!(piece_at(&rule, &cells, x as i32 + 2, y as i32 + 2), Some(*piece), "at {x}, {y}");
+            }
+        }
+        assert_eq!(cells.get_cell(1, 1).foreground.0, None);
+        assert_eq!(cells.get_cell(3, 3).background.0, None);
+
+        // Erasing updates the neighbors too
+        rule.paint(&mut cells, 3, 2, true, false);
+        assert_eq!(cells.get_cell(3, 2).foreground.0, None);
+        assert_eq!(piece_at(&rule, &cells, 3, 3), Some(TerrainPiece::Top));
+    }
+
+    #[test]
+    fn paint_at_the_edges() {
+        let rule = full_rule();
+        let mut cells = Cells::new(3, 3);
+        rule.paint(&mut cells, -1, 0, true, true);
+        rule.paint(&mut cells, 0, 3, true, true);
+        for y in 0..3 {
+            for x in 0..3 {
+                assert_eq!(cells.get_cell(x, y).foreground.0, None);
+            }
+        }
+
+        // The edge of the level counts as terrain
+        rule.paint(&mut cells, 0, 0, false, true);
+        assert_eq!(cells.get_cell(0, 0).background.0, rule.get_piece(TerrainPiece::BottomRight));
+        assert_eq!(cells.get_cell(0, 0).foreground.0, None);
+    }
+}
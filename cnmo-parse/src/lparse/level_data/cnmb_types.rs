@@ -49,7 +49,7 @@ impl Default for BackgroundFlags {
 
 /// A background layer in CNM Online
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct BackgroundLayer {
     /// Where the original image is (offset)
     pub origin: Point,
@@ -258,7 +258,7 @@ pub(super) fn save_background_vec(
 
 /// How a tile will damage the player
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DamageType {
     /// It won't damage the player
     None,
@@ -276,7 +276,7 @@ pub enum DamageType {
 
 /// What collision type does the tile have?
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CollisionType {
     /// A normal box
     Box(Rect),
@@ -288,7 +288,7 @@ pub enum CollisionType {
 
 /// All properties of a tile.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TileProperties {
     /// Is it solid
     pub solid: bool,
@@ -601,7 +601,7 @@ impl TileId {
 
 /// A cell on the cnm world grid.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Cell {
     /// Shown infront of cnm objects
     pub foreground: TileId,
@@ -623,7 +623,7 @@ impl Default for Cell {
 }
 
 /// The grid of cells of a cnm world
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Cells {
     cells: Vec<Cell>,
     width: usize,
@@ -103,4 +103,21 @@ impl ItemType {
     pub fn get_item_id(&self) -> u32 {
         num_traits::ToPrimitive::to_u32(self).unwrap_or(0) + 1
     }
+
+    /// Finds an item from the name of its enum variant (like `Money50`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        (1..)
+            .map_while(Self::from_item_id)
+            .find(|item| format!("{item:?}") == name)
+    }
+
+    /// How much money the player gets from picking up this item. 0 for non-money items.
+    pub fn get_money_value(&self) -> u32 {
+        match self {
+            Self::Money50 => 50,
+            Self::Money100 => 100,
+            Self::Money500 => 500,
+            _ => 0,
+        }
+    }
 }
@@ -57,11 +57,23 @@ impl SpawnerMode {
             &Self::NoSpawn => 4 << 24,
         }
     }
+
+    /// How many objects a spawner with this mode spawns at once in a game with
+    /// `player_count` players. A player count of 1 is singleplayer.
+    pub fn get_spawn_count(&self, player_count: u32) -> u32 {
+        match self {
+            Self::MultiAndSingleplayer => 1,
+            Self::SingleplayerOnly => if player_count <= 1 { 1 } else { 0 },
+            Self::MultiplayerOnly => if player_count > 1 { 1 } else { 0 },
+            Self::PlayerCountBased => player_count,
+            Self::NoSpawn => 0,
+        }
+    }
 }
 
 /// Criteria for how a World Object (Wobj) spawns.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SpawningCriteria {
     /// How many seconds between spawns
     pub spawn_delay_secs: f32,
@@ -73,7 +85,7 @@ pub struct SpawningCriteria {
 
 /// A template for a object to spawn in cnm
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Spawner {
     /// Where it will spawn
     pub pos: Point,
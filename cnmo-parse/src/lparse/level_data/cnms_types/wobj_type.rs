@@ -113,7 +113,7 @@ pub enum UpgradeTriggerType {
 
 /// Used in certain World Object (Wobj) types
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Teleport {
     /// Name of the teleport, look in [`crate::lparse::level_data::VersionSpecs`] to see how long it can be
     pub name: String,
@@ -208,9 +208,168 @@ impl CustomizableMovingPlatformType {
     }
 }
 
+/// Text given to one of the [`std::str::FromStr`] impls in this module that
+/// isn't a value of the enum
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Unknown enum value {0}!")]
+pub struct UnknownVariant(String);
+
+/// Implements [`std::str::FromStr`] for enums without data, parsing the names
+/// of the variants the same as `{:?}` prints them
+macro_rules! impl_from_str_for_variant_names {
+    ($ty:ty, $($variant:ident),+) => {
+        impl std::str::FromStr for $ty {
+            type Err = UnknownVariant;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($variant) => Ok(Self::$variant),)+
+                    _ => Err(UnknownVariant(s.to_string())),
+                }
+            }
+        }
+    };
+}
+
+impl_from_str_for_variant_names!(TunesTriggerSize, Small, Big, VeryBig);
+impl_from_str_for_variant_names!(RuneType, Fire, Ice, Air, Lightning);
+impl_from_str_for_variant_names!(PushZoneType, Horizontal, Vertical, HorizontalSmall);
+impl_from_str_for_variant_names!(KeyColor, Red, Green, Blue);
+impl_from_str_for_variant_names!(BackgroundSwitcherShape, Small, Horizontal, Vertical);
+impl_from_str_for_variant_names!(CustomizableMovingPlatformType, Normal, OneWay, Despawn);
+
+/// Gets the text of the only field of a variant printed with `{:?}`, like the
+/// `5` in `Waypoint(5)` or the `true` in `Small2 { face_left: true }`
+fn get_debug_field<'a>(s: &'a str, variant: &str) -> Option<&'a str> {
+    let rest = s.strip_prefix(variant)?.trim();
+    if let Some(inner) = rest.strip_prefix('(') {
+        return inner.strip_suffix(')').map(str::trim);
+    }
+    let inner = rest.strip_prefix('{')?.strip_suffix('}')?;
+    inner.split_once(':').map(|(_, v)| v.trim())
+}
+
+impl std::str::FromStr for TtNodeType {
+    type Err = UnknownVariant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NormalTrigger" => Ok(Self::NormalTrigger),
+            "ChaseTrigger" => Ok(Self::ChaseTrigger),
+            "BozoWaypoint" => Ok(Self::BozoWaypoint),
+            _ => get_debug_field(s, "Waypoint")
+                .and_then(|id| id.parse().ok())
+                .map(Self::Waypoint)
+                .ok_or_else(|| UnknownVariant(s.to_string())),
+        }
+    }
+}
+
+impl std::str::FromStr for RockGuyType {
+    type Err = UnknownVariant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Medium" => Ok(Self::Medium),
+            "Small1" => Ok(Self::Small1),
+            _ => get_debug_field(s, "Small2")
+                .and_then(|face_left| face_left.parse().ok())
+                .map(|face_left| Self::Small2 { face_left })
+                .ok_or_else(|| UnknownVariant(s.to_string())),
+        }
+    }
+}
+
+impl std::str::FromStr for UpgradeTriggerType {
+    type Err = UnknownVariant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let skin_power_override = |s: &str| match s {
+            "None" => Some(None),
+            _ => get_debug_field(s, "Some").and_then(|id| id.parse().ok()).map(Some),
+        };
+        match s {
+            "Wings" => Ok(Self::Wings),
+            "DeephausBoots" => Ok(Self::DeephausBoots),
+            "CrystalWings" => Ok(Self::CrystalWings),
+            "Vortex" => Ok(Self::Vortex),
+            "None" => Ok(Self::None),
+            _ => get_debug_field(s, "MaxPowerRune")
+                .and_then(skin_power_override)
+                .map(|skin_power_override| Self::MaxPowerRune { skin_power_override })
+                .ok_or_else(|| UnknownVariant(s.to_string())),
+        }
+    }
+}
+
+/// Value of a single field of a [`WobjType`], used to look at and change
+/// objects without having to match on every type.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    /// Booleans
+    Bool(bool),
+    /// Every integer field type fits in here
+    Int(i64),
+    /// Floats
+    Float(f32),
+    /// Strings and names of enum values
+    Text(String),
+    /// An optional field that isn't set
+    None,
+}
+
+impl FieldValue {
+    /// Gets the value as a bool, if it is one
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an integer. Floats get truncated.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(v) => Some(*v),
+            Self::Float(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a float. Integers get converted.
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::Int(v) => Some(*v as f32),
+            Self::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as text, if it is text
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::Text(v) => f.write_str(v),
+            Self::None => f.write_str("none"),
+        }
+    }
+}
+
 /// Type of a CNM Online object (and what will spawn from a spawner)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum WobjType {
     ///
     Teleport(Teleport),
@@ -1373,4 +1532,815 @@ impl WobjType {
             &Self::LensFlare => (159, 0, 0.0),
         }
     }
+
+    /// Name of the object type, the same as the name of the enum variant.
+    pub fn get_type_name(&self) -> &'static str {
+        match self {
+            Self::Teleport(..) => "Teleport",
+            Self::Slime { .. } => "Slime",
+            Self::TunesTrigger { .. } => "TunesTrigger",
+            Self::PlayerSpawn { .. } => "PlayerSpawn",
+            Self::TextSpawner { .. } => "TextSpawner",
+            Self::MovingPlatform { .. } => "MovingPlatform",
+            Self::BreakableWall { .. } => "BreakableWall",
+            Self::BackgroundSwitcher { .. } => "BackgroundSwitcher",
+            Self::DroppedItem { .. } => "DroppedItem",
+            Self::WandRune { .. } => "WandRune",
+            Self::Heavy { .. } => "Heavy",
+            Self::Dragon { .. } => "Dragon",
+            Self::BozoPin { .. } => "BozoPin",
+            Self::Bozo { .. } => "Bozo",
+            Self::SilverSlime => "SilverSlime",
+            Self::LavaMonster { .. } => "LavaMonster",
+            Self::TtMinion { .. } => "TtMinion",
+            Self::SlimeWalker => "SlimeWalker",
+            Self::MegaFish { .. } => "MegaFish",
+            Self::LavaDragonHead { .. } => "LavaDragonHead",
+            Self::TtNode { .. } => "TtNode",
+            Self::TtBoss { .. } => "TtBoss",
+            Self::EaterBug { .. } => "EaterBug",
+            Self::SpiderWalker { .. } => "SpiderWalker",
+            Self::SpikeTrap => "SpikeTrap",
+            Self::RotatingFireColunmPiece { .. } => "RotatingFireColunmPiece",
+            Self::MovingFire { .. } => "MovingFire",
+            Self::SuperDragon { .. } => "SuperDragon",
+            Self::SuperDragonLandingZone { .. } => "SuperDragonLandingZone",
+            Self::BozoLaserMinion { .. } => "BozoLaserMinion",
+            Self::Checkpoint { .. } => "Checkpoint",
+            Self::SpikeGuy => "SpikeGuy",
+            Self::BanditGuy { .. } => "BanditGuy",
+            Self::PushZone { .. } => "PushZone",
+            Self::VerticalWindZone { .. } => "VerticalWindZone",
+            Self::DisapearingPlatform { .. } => "DisapearingPlatform",
+            Self::KamakaziSlime => "KamakaziSlime",
+            Self::SpringBoard { .. } => "SpringBoard",
+            Self::Jumpthrough { .. } => "Jumpthrough",
+            Self::BreakablePlatform { .. } => "BreakablePlatform",
+            Self::LockedBlock { .. } => "LockedBlock",
+            Self::RockGuy { .. } => "RockGuy",
+            Self::RockGuySlider => "RockGuySlider",
+            Self::RockGuySmasher => "RockGuySmasher",
+            Self::HealthSetTrigger { .. } => "HealthSetTrigger",
+            Self::Vortex { .. } => "Vortex",
+            Self::CustomizeableMoveablePlatform { .. } => "CustomizeableMoveablePlatform",
+            Self::GraphicsChangeTrigger { .. } => "GraphicsChangeTrigger",
+            Self::BossBarInfo { .. } => "BossBarInfo",
+            Self::BgSpeed { .. } => "BgSpeed",
+            Self::BgTransparency { .. } => "BgTransparency",
+            Self::TeleportTrigger1 { .. } => "TeleportTrigger1",
+            Self::TeleportArea1 { .. } => "TeleportArea1",
+            Self::SfxPoint { .. } => "SfxPoint",
+            Self::Wolf => "Wolf",
+            Self::Supervirus => "Supervirus",
+            Self::Lua { .. } => "Lua",
+            Self::UpgradeTrigger { .. } => "UpgradeTrigger",
+            Self::FinishTrigger { .. } => "FinishTrigger",
+            Self::GravityTrigger { .. } => "GravityTrigger",
+            Self::SkinUnlock { .. } => "SkinUnlock",
+            Self::CoolPlatform { .. } => "CoolPlatform",
+            Self::TeleportArea2 { .. } => "TeleportArea2",
+            Self::InvisBlock => "InvisBlock",
+            Self::PetUnlock { .. } => "PetUnlock",
+            Self::KeyRemover { .. } => "KeyRemover",
+            Self::LensFlare => "LensFlare",
+        }
+    }
+
+    /// Gets the names and values of every field of this object type. Fields
+    /// that are points or ranges are split up into their parts (like `loc.x`
+    /// and `loc.y`), and fields that are enums are given as their name.
+    pub fn get_fields(&self) -> Vec<(&'static str, FieldValue)> {
+        match self {
+            Self::Teleport(teleport) => vec![
+                ("name", FieldValue::Text(teleport.name.clone())),
+                ("cost", FieldValue::Int(teleport.cost as i64)),
+                ("loc.x", FieldValue::Float(teleport.loc.0)),
+                ("loc.y", FieldValue::Float(teleport.loc.1)),
+            ],
+            Self::Slime { flying } => vec![
+                ("flying", FieldValue::Bool(*flying)),
+            ],
+            Self::TunesTrigger { size, music_id } => vec![
+                ("size", FieldValue::Text(format!("{:?}", size))),
+                ("music_id", FieldValue::Int(*music_id as i64)),
+            ],
+            Self::PlayerSpawn { special_entrance } => vec![
+                ("special_entrance", FieldValue::Bool(*special_entrance)),
+            ],
+            Self::TextSpawner { dialoge_box, despawn, text } => vec![
+                ("dialoge_box", FieldValue::Bool(*dialoge_box)),
+                ("despawn", FieldValue::Bool(*despawn)),
+                ("text", FieldValue::Text(text.clone())),
+            ],
+            Self::MovingPlatform { vertical, dist, speed, bitmapx, bitmapy, jumpthrough } => vec![
+                ("vertical", FieldValue::Bool(*vertical)),
+                ("dist", FieldValue::Float(*dist)),
+                ("speed", FieldValue::Float(*speed)),
+                ("bitmapx", FieldValue::Int(*bitmapx as i64)),
+                ("bitmapy", FieldValue::Int(*bitmapy as i64)),
+                ("jumpthrough", FieldValue::Bool(*jumpthrough)),
+            ],
+            Self::BreakableWall { skin_id, health } => vec![
+                ("skin_id", skin_id.map_or(FieldValue::None, |v| FieldValue::Int(v as i64))),
+                ("health", FieldValue::Float(*health)),
+            ],
+            Self::BackgroundSwitcher { shape, enabled_layers } => vec![
+                ("shape", FieldValue::Text(format!("{:?}", shape))),
+                ("enabled_layers.start", FieldValue::Int(enabled_layers.start as i64)),
+                ("enabled_layers.end", FieldValue::Int(enabled_layers.end as i64)),
+            ],
+            Self::DroppedItem { item } => vec![
+                ("item", FieldValue::Text(format!("{:?}", item))),
+            ],
+            Self::WandRune { rune_type } => vec![
+                ("rune_type", FieldValue::Text(format!("{:?}", rune_type))),
+            ],
+            Self::Heavy { speed, face_left } => vec![
+                ("speed", FieldValue::Float(*speed)),
+                ("face_left", FieldValue::Bool(*face_left)),
+            ],
+            Self::Dragon { space_skin } => vec![
+                ("space_skin", FieldValue::Bool(*space_skin)),
+            ],
+            Self::BozoPin { flying_speed } => vec![
+                ("flying_speed", FieldValue::Float(*flying_speed)),
+            ],
+            Self::Bozo { mark_ii } => vec![
+                ("mark_ii", FieldValue::Bool(*mark_ii)),
+            ],
+            Self::SilverSlime => vec![],
+            Self::LavaMonster { face_left } => vec![
+                ("face_left", FieldValue::Bool(*face_left)),
+            ],
+            Self::TtMinion { small } => vec![
+                ("small", FieldValue::Bool(*small)),
+            ],
+            Self::SlimeWalker => vec![],
+            Self::MegaFish { water_level, swimming_speed } => vec![
+                ("water_level", FieldValue::Int(*water_level as i64)),
+                ("swimming_speed", FieldValue::Float(*swimming_speed)),
+            ],
+            Self::LavaDragonHead { len, health } => vec![
+                ("len", FieldValue::Int(*len as i64)),
+                ("health", FieldValue::Float(*health)),
+            ],
+            Self::TtNode { node_type } => vec![
+                ("node_type", FieldValue::Text(format!("{:?}", node_type))),
+            ],
+            Self::TtBoss { speed } => vec![
+                ("speed", FieldValue::Float(*speed)),
+            ],
+            Self::EaterBug { pop_up_speed } => vec![
+                ("pop_up_speed", FieldValue::Float(*pop_up_speed)),
+            ],
+            Self::SpiderWalker { speed } => vec![
+                ("speed", FieldValue::Float(*speed)),
+            ],
+            Self::SpikeTrap => vec![],
+            Self::RotatingFireColunmPiece { origin_x, degrees_per_second } => vec![
+                ("origin_x", FieldValue::Int(*origin_x as i64)),
+                ("degrees_per_second", FieldValue::Float(*degrees_per_second)),
+            ],
+            Self::MovingFire { vertical, dist, speed, despawn, bitmapx, bitmapy } => vec![
+                ("vertical", FieldValue::Bool(*vertical)),
+                ("dist", FieldValue::Int(*dist as i64)),
+                ("speed", FieldValue::Float(*speed)),
+                ("despawn", FieldValue::Bool(*despawn)),
+                ("bitmapx", FieldValue::Int(*bitmapx as i64)),
+                ("bitmapy", FieldValue::Int(*bitmapy as i64)),
+            ],
+            Self::SuperDragon { waypoint_id } => vec![
+                ("waypoint_id", FieldValue::Int(*waypoint_id as i64)),
+            ],
+            Self::SuperDragonLandingZone { waypoint_id } => vec![
+                ("waypoint_id", FieldValue::Int(*waypoint_id as i64)),
+            ],
+            Self::BozoLaserMinion { speed } => vec![
+                ("speed", FieldValue::Float(*speed)),
+            ],
+            Self::Checkpoint { checkpoint_num } => vec![
+                ("checkpoint_num", FieldValue::Int(*checkpoint_num as i64)),
+            ],
+            Self::SpikeGuy => vec![],
+            Self::BanditGuy { speed } => vec![
+                ("speed", FieldValue::Float(*speed)),
+            ],
+            Self::PushZone { push_zone_type, push_speed } => vec![
+                ("push_zone_type", FieldValue::Text(format!("{:?}", push_zone_type))),
+                ("push_speed", FieldValue::Float(*push_speed)),
+            ],
+            Self::VerticalWindZone { acceleration } => vec![
+                ("acceleration", FieldValue::Float(*acceleration)),
+            ],
+            Self::DisapearingPlatform { time_on, time_off, starts_on } => vec![
+                ("time_on", FieldValue::Float(*time_on)),
+                ("time_off", FieldValue::Float(*time_off)),
+                ("starts_on", FieldValue::Bool(*starts_on)),
+            ],
+            Self::KamakaziSlime => vec![],
+            Self::SpringBoard { jump_velocity } => vec![
+                ("jump_velocity", FieldValue::Float(*jump_velocity)),
+            ],
+            Self::Jumpthrough { big } => vec![
+                ("big", FieldValue::Bool(*big)),
+            ],
+            Self::BreakablePlatform { time_till_fall } => vec![
+                ("time_till_fall", FieldValue::Float(*time_till_fall)),
+            ],
+            Self::LockedBlock { color, consume_key } => vec![
+                ("color", FieldValue::Text(format!("{:?}", color))),
+                ("consume_key", FieldValue::Bool(*consume_key)),
+            ],
+            Self::RockGuy { rock_guy_type } => vec![
+                ("rock_guy_type", FieldValue::Text(format!("{:?}", rock_guy_type))),
+            ],
+            Self::RockGuySlider => vec![],
+            Self::RockGuySmasher => vec![],
+            Self::HealthSetTrigger { target_health } => vec![
+                ("target_health", FieldValue::Float(*target_health)),
+            ],
+            Self::Vortex { attract_enemies } => vec![
+                ("attract_enemies", FieldValue::Bool(*attract_enemies)),
+            ],
+            Self::CustomizeableMoveablePlatform { bitmap_x32, target_relative, speed, start_paused, ty } => vec![
+                ("bitmap_x32.x", FieldValue::Int(bitmap_x32.0 as i64)),
+                ("bitmap_x32.y", FieldValue::Int(bitmap_x32.1 as i64)),
+                ("target_relative.x", FieldValue::Float(target_relative.0)),
+                ("target_relative.y", FieldValue::Float(target_relative.1)),
+                ("speed", FieldValue::Float(*speed)),
+                ("start_paused", FieldValue::Bool(*start_paused)),
+                ("ty", FieldValue::Text(format!("{:?}", ty))),
+            ],
+            Self::GraphicsChangeTrigger { gfx_file } => vec![
+                ("gfx_file", FieldValue::Text(gfx_file.clone())),
+            ],
+            Self::BossBarInfo { boss_name } => vec![
+                ("boss_name", FieldValue::Text(boss_name.clone())),
+            ],
+            Self::BgSpeed { vertical_axis, layer, speed } => vec![
+                ("vertical_axis", FieldValue::Bool(*vertical_axis)),
+                ("layer", FieldValue::Int(*layer as i64)),
+                ("speed", FieldValue::Float(*speed)),
+            ],
+            Self::BgTransparency { layer, transparency } => vec![
+                ("layer", FieldValue::Int(*layer as i64)),
+                ("transparency", FieldValue::Int(*transparency as i64)),
+            ],
+            Self::TeleportTrigger1 { link_id, delay_secs } => vec![
+                ("link_id", FieldValue::Int(*link_id as i64)),
+                ("delay_secs", FieldValue::Float(*delay_secs)),
+            ],
+            Self::TeleportArea1 { link_id, loc } => vec![
+                ("link_id", FieldValue::Int(*link_id as i64)),
+                ("loc.x", FieldValue::Float(loc.0)),
+                ("loc.y", FieldValue::Float(loc.1)),
+            ],
+            Self::SfxPoint { sound_id } => vec![
+                ("sound_id", FieldValue::Int(*sound_id as i64)),
+            ],
+            Self::Wolf => vec![],
+            Self::Supervirus => vec![],
+            Self::Lua { lua_wobj_type } => vec![
+                ("lua_wobj_type", FieldValue::Int(*lua_wobj_type as i64)),
+            ],
+            Self::UpgradeTrigger { trigger_type } => vec![
+                ("trigger_type", FieldValue::Text(format!("{:?}", trigger_type))),
+            ],
+            Self::FinishTrigger { next_level, extra_unlocked_level, is_secret } => vec![
+                ("next_level", FieldValue::Text(next_level.clone())),
+                ("extra_unlocked_level", extra_unlocked_level.clone().map_or(FieldValue::None, FieldValue::Text)),
+                ("is_secret", FieldValue::Bool(*is_secret)),
+            ],
+            Self::GravityTrigger { gravity } => vec![
+                ("gravity", FieldValue::Float(*gravity)),
+            ],
+            Self::SkinUnlock { id } => vec![
+                ("id", FieldValue::Int(*id as i64)),
+            ],
+            Self::CoolPlatform { time_off_before, time_on, time_off_after } => vec![
+                ("time_off_before", FieldValue::Int(*time_off_before as i64)),
+                ("time_on", FieldValue::Int(*time_on as i64)),
+                ("time_off_after", FieldValue::Int(*time_off_after as i64)),
+            ],
+            Self::TeleportArea2 { loc, start_activated, teleport_players, link_id } => vec![
+                ("loc.x", FieldValue::Float(loc.0)),
+                ("loc.y", FieldValue::Float(loc.1)),
+                ("start_activated", FieldValue::Bool(*start_activated)),
+                ("teleport_players", FieldValue::Bool(*teleport_players)),
+                ("link_id", FieldValue::Int(*link_id as i64)),
+            ],
+            Self::InvisBlock => vec![],
+            Self::PetUnlock { petid } => vec![
+                ("petid", FieldValue::Int(*petid as i64)),
+            ],
+            Self::KeyRemover { remove_red, remove_green, remove_blue } => vec![
+                ("remove_red", FieldValue::Bool(*remove_red)),
+                ("remove_green", FieldValue::Bool(*remove_green)),
+                ("remove_blue", FieldValue::Bool(*remove_blue)),
+            ],
+            Self::LensFlare => vec![],
+        }
+    }
+
+    /// Sets the field with the name `field` (in the same format as [`WobjType::get_fields`])
+    /// to `value`. Returns false if the field doesn't exist, can't be set or the value is
+    /// the wrong type or out of the range the field can hold.
+    pub fn set_field(&mut self, field: &str, value: &FieldValue) -> bool {
+        let result = match self {
+            Self::Teleport(teleport) => match field {
+                "name" => value.as_text().map(|v| teleport.name = v.to_string()),
+                "cost" => value.as_i64().and_then(|v| i32::try_from(v).ok()).map(|v| teleport.cost = v),
+                "loc.x" => value.as_f32().map(|v| teleport.loc.0 = v),
+                "loc.y" => value.as_f32().map(|v| teleport.loc.1 = v),
+                _ => None,
+            },
+            Self::Slime { flying } => match field {
+                "flying" => value.as_bool().map(|v| *flying = v),
+                _ => None,
+            },
+            Self::TunesTrigger { size, music_id } => match field {
+                "size" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *size = v),
+                "music_id" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *music_id = v),
+                _ => None,
+            },
+            Self::PlayerSpawn { special_entrance } => match field {
+                "special_entrance" => value.as_bool().map(|v| *special_entrance = v),
+                _ => None,
+            },
+            Self::TextSpawner { dialoge_box, despawn, text } => match field {
+                "dialoge_box" => value.as_bool().map(|v| *dialoge_box = v),
+                "despawn" => value.as_bool().map(|v| *despawn = v),
+                "text" => value.as_text().map(|v| *text = v.to_string()),
+                _ => None,
+            },
+            Self::MovingPlatform { vertical, dist, speed, bitmapx, bitmapy, jumpthrough } => match field {
+                "vertical" => value.as_bool().map(|v| *vertical = v),
+                "dist" => value.as_f32().map(|v| *dist = v),
+                "speed" => value.as_f32().map(|v| *speed = v),
+                "bitmapx" => value.as_i64().and_then(|v| u16::try_from(v).ok()).map(|v| *bitmapx = v),
+                "bitmapy" => value.as_i64().and_then(|v| u16::try_from(v).ok()).map(|v| *bitmapy = v),
+                "jumpthrough" => value.as_bool().map(|v| *jumpthrough = v),
+                _ => None,
+            },
+            Self::BreakableWall { skin_id, health } => match field {
+                "skin_id" => match value {
+                    FieldValue::None => {
+                        *skin_id = None;
+                        Some(())
+                    },
+                    _ => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *skin_id = Some(v)),
+                },
+                "health" => value.as_f32().map(|v| *health = v),
+                _ => None,
+            },
+            Self::BackgroundSwitcher { shape, enabled_layers } => match field {
+                "shape" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *shape = v),
+                "enabled_layers.start" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| enabled_layers.start = v),
+                "enabled_layers.end" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| enabled_layers.end = v),
+                _ => None,
+            },
+            Self::DroppedItem { item } => match field {
+                "item" => value.as_text().and_then(ItemType::from_name).map(|v| *item = v),
+                _ => None,
+            },
+            Self::WandRune { rune_type } => match field {
+                "rune_type" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *rune_type = v),
+                _ => None,
+            },
+            Self::TtNode { node_type } => match field {
+                "node_type" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *node_type = v),
+                _ => None,
+            },
+            Self::RockGuy { rock_guy_type } => match field {
+                "rock_guy_type" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *rock_guy_type = v),
+                _ => None,
+            },
+            Self::UpgradeTrigger { trigger_type } => match field {
+                "trigger_type" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *trigger_type = v),
+                _ => None,
+            },
+            Self::Heavy { speed, face_left } => match field {
+                "speed" => value.as_f32().map(|v| *speed = v),
+                "face_left" => value.as_bool().map(|v| *face_left = v),
+                _ => None,
+            },
+            Self::Dragon { space_skin } => match field {
+                "space_skin" => value.as_bool().map(|v| *space_skin = v),
+                _ => None,
+            },
+            Self::BozoPin { flying_speed } => match field {
+                "flying_speed" => value.as_f32().map(|v| *flying_speed = v),
+                _ => None,
+            },
+            Self::Bozo { mark_ii } => match field {
+                "mark_ii" => value.as_bool().map(|v| *mark_ii = v),
+                _ => None,
+            },
+            Self::LavaMonster { face_left } => match field {
+                "face_left" => value.as_bool().map(|v| *face_left = v),
+                _ => None,
+            },
+            Self::TtMinion { small } => match field {
+                "small" => value.as_bool().map(|v| *small = v),
+                _ => None,
+            },
+            Self::MegaFish { water_level, swimming_speed } => match field {
+                "water_level" => value.as_i64().and_then(|v| i32::try_from(v).ok()).map(|v| *water_level = v),
+                "swimming_speed" => value.as_f32().map(|v| *swimming_speed = v),
+                _ => None,
+            },
+            Self::LavaDragonHead { len, health } => match field {
+                "len" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *len = v),
+                "health" => value.as_f32().map(|v| *health = v),
+                _ => None,
+            },
+            Self::TtBoss { speed } => match field {
+                "speed" => value.as_f32().map(|v| *speed = v),
+                _ => None,
+            },
+            Self::EaterBug { pop_up_speed } => match field {
+                "pop_up_speed" => value.as_f32().map(|v| *pop_up_speed = v),
+                _ => None,
+            },
+            Self::SpiderWalker { speed } => match field {
+                "speed" => value.as_f32().map(|v| *speed = v),
+                _ => None,
+            },
+            Self::RotatingFireColunmPiece { origin_x, degrees_per_second } => match field {
+                "origin_x" => value.as_i64().and_then(|v| i32::try_from(v).ok()).map(|v| *origin_x = v),
+                "degrees_per_second" => value.as_f32().map(|v| *degrees_per_second = v),
+                _ => None,
+            },
+            Self::MovingFire { vertical, dist, speed, despawn, bitmapx, bitmapy } => match field {
+                "vertical" => value.as_bool().map(|v| *vertical = v),
+                "dist" => value.as_i64().and_then(|v| i32::try_from(v).ok()).map(|v| *dist = v),
+                "speed" => value.as_f32().map(|v| *speed = v),
+                "despawn" => value.as_bool().map(|v| *despawn = v),
+                "bitmapx" => value.as_i64().and_then(|v| u16::try_from(v).ok()).map(|v| *bitmapx = v),
+                "bitmapy" => value.as_i64().and_then(|v| u16::try_from(v).ok()).map(|v| *bitmapy = v),
+                _ => None,
+            },
+            Self::SuperDragon { waypoint_id } => match field {
+                "waypoint_id" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *waypoint_id = v),
+                _ => None,
+            },
+            Self::SuperDragonLandingZone { waypoint_id } => match field {
+                "waypoint_id" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *waypoint_id = v),
+                _ => None,
+            },
+            Self::BozoLaserMinion { speed } => match field {
+                "speed" => value.as_f32().map(|v| *speed = v),
+                _ => None,
+            },
+            Self::Checkpoint { checkpoint_num } => match field {
+                "checkpoint_num" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *checkpoint_num = v),
+                _ => None,
+            },
+            Self::BanditGuy { speed } => match field {
+                "speed" => value.as_f32().map(|v| *speed = v),
+                _ => None,
+            },
+            Self::PushZone { push_zone_type, push_speed } => match field {
+                "push_zone_type" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *push_zone_type = v),
+                "push_speed" => value.as_f32().map(|v| *push_speed = v),
+                _ => None,
+            },
+            Self::VerticalWindZone { acceleration } => match field {
+                "acceleration" => value.as_f32().map(|v| *acceleration = v),
+                _ => None,
+            },
+            Self::DisapearingPlatform { time_on, time_off, starts_on } => match field {
+                "time_on" => value.as_f32().map(|v| *time_on = v),
+                "time_off" => value.as_f32().map(|v| *time_off = v),
+                "starts_on" => value.as_bool().map(|v| *starts_on = v),
+                _ => None,
+            },
+            Self::SpringBoard { jump_velocity } => match field {
+                "jump_velocity" => value.as_f32().map(|v| *jump_velocity = v),
+                _ => None,
+            },
+            Self::Jumpthrough { big } => match field {
+                "big" => value.as_bool().map(|v| *big = v),
+                _ => None,
+            },
+            Self::BreakablePlatform { time_till_fall } => match field {
+                "time_till_fall" => value.as_f32().map(|v| *time_till_fall = v),
+                _ => None,
+            },
+            Self::LockedBlock { color, consume_key } => match field {
+                "color" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *color = v),
+                "consume_key" => value.as_bool().map(|v| *consume_key = v),
+                _ => None,
+            },
+            Self::HealthSetTrigger { target_health } => match field {
+                "target_health" => value.as_f32().map(|v| *target_health = v),
+                _ => None,
+            },
+            Self::Vortex { attract_enemies } => match field {
+                "attract_enemies" => value.as_bool().map(|v| *attract_enemies = v),
+                _ => None,
+            },
+            Self::CustomizeableMoveablePlatform { bitmap_x32, target_relative, speed, start_paused, ty } => match field {
+                "ty" => value.as_text().and_then(|v| v.parse().ok()).map(|v| *ty = v),
+                "bitmap_x32.x" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| bitmap_x32.0 = v),
+                "bitmap_x32.y" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| bitmap_x32.1 = v),
+                "target_relative.x" => value.as_f32().map(|v| target_relative.0 = v),
+                "target_relative.y" => value.as_f32().map(|v| target_relative.1 = v),
+                "speed" => value.as_f32().map(|v| *speed = v),
+                "start_paused" => value.as_bool().map(|v| *start_paused = v),
+                _ => None,
+            },
+            Self::GraphicsChangeTrigger { gfx_file } => match field {
+                "gfx_file" => value.as_text().map(|v| *gfx_file = v.to_string()),
+                _ => None,
+            },
+            Self::BossBarInfo { boss_name } => match field {
+                "boss_name" => value.as_text().map(|v| *boss_name = v.to_string()),
+                _ => None,
+            },
+            Self::BgSpeed { vertical_axis, layer, speed } => match field {
+                "vertical_axis" => value.as_bool().map(|v| *vertical_axis = v),
+                "layer" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *layer = v),
+                "speed" => value.as_f32().map(|v| *speed = v),
+                _ => None,
+            },
+            Self::BgTransparency { layer, transparency } => match field {
+                "layer" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *layer = v),
+                "transparency" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *transparency = v),
+                _ => None,
+            },
+            Self::TeleportTrigger1 { link_id, delay_secs } => match field {
+                "link_id" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *link_id = v),
+                "delay_secs" => value.as_f32().map(|v| *delay_secs = v),
+                _ => None,
+            },
+            Self::TeleportArea1 { link_id, loc } => match field {
+                "link_id" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *link_id = v),
+                "loc.x" => value.as_f32().map(|v| loc.0 = v),
+                "loc.y" => value.as_f32().map(|v| loc.1 = v),
+                _ => None,
+            },
+            Self::SfxPoint { sound_id } => match field {
+                "sound_id" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *sound_id = v),
+                _ => None,
+            },
+            Self::Lua { lua_wobj_type } => match field {
+                "lua_wobj_type" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *lua_wobj_type = v),
+                _ => None,
+            },
+            Self::FinishTrigger { next_level, extra_unlocked_level, is_secret } => match field {
+                "next_level" => value.as_text().map(|v| *next_level = v.to_string()),
+                "extra_unlocked_level" => match value {
+                    FieldValue::None => {
+                        *extra_unlocked_level = None;
+                        Some(())
+                    },
+                    _ => value.as_text().map(|v| *extra_unlocked_level = Some(v.to_string())),
+                },
+                "is_secret" => value.as_bool().map(|v| *is_secret = v),
+                _ => None,
+            },
+            Self::GravityTrigger { gravity } => match field {
+                "gravity" => value.as_f32().map(|v| *gravity = v),
+                _ => None,
+            },
+            Self::SkinUnlock { id } => match field {
+                "id" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *id = v),
+                _ => None,
+            },
+            Self::CoolPlatform { time_off_before, time_on, time_off_after } => match field {
+                "time_off_before" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *time_off_before = v),
+                "time_on" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *time_on = v),
+                "time_off_after" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *time_off_after = v),
+                _ => None,
+            },
+            Self::TeleportArea2 { loc, start_activated, teleport_players, link_id } => match field {
+                "loc.x" => value.as_f32().map(|v| loc.0 = v),
+                "loc.y" => value.as_f32().map(|v| loc.1 = v),
+                "start_activated" => value.as_bool().map(|v| *start_activated = v),
+                "teleport_players" => value.as_bool().map(|v| *teleport_players = v),
+                "link_id" => value.as_i64().and_then(|v| u32::try_from(v).ok()).map(|v| *link_id = v),
+                _ => None,
+            },
+            Self::PetUnlock { petid } => match field {
+                "petid" => value.as_i64().and_then(|v| u8::try_from(v).ok()).map(|v| *petid = v),
+                _ => None,
+            },
+            Self::KeyRemover { remove_red, remove_green, remove_blue } => match field {
+                "remove_red" => value.as_bool().map(|v| *remove_red = v),
+                "remove_green" => value.as_bool().map(|v| *remove_green = v),
+                "remove_blue" => value.as_bool().map(|v| *remove_blue = v),
+                _ => None,
+            },
+            _ => None,
+        };
+        result.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs of the same object type with every field different, so setting
+    /// the fields of one onto the other has to change all of them
+    fn get_sample_pairs() -> Vec<[WobjType; 2]> {
+        use WobjType::*;
+
+        vec![
+            [
+                Teleport(super::Teleport { name: "Town".to_string(), cost: 100, loc: Point(16.0, 32.0) }),
+                Teleport(super::Teleport { name: "Castle".to_string(), cost: -1, loc: Point(64.5, 8.0) }),
+            ],
+            [Slime { flying: true }, Slime { flying: false }],
+            [
+                TunesTrigger { size: TunesTriggerSize::Big, music_id: 3 },
+                TunesTrigger { size: TunesTriggerSize::VeryBig, music_id: 7 },
+            ],
+            [PlayerSpawn { special_entrance: true }, PlayerSpawn { special_entrance: false }],
+            [
+                TextSpawner { dialoge_box: true, despawn: false, text: "Hello".to_string() },
+                TextSpawner { dialoge_box: false, despawn: true, text: "Bye".to_string() },
+            ],
+            [
+                MovingPlatform { vertical: true, dist: 64.0, speed: 2.5, bitmapx: 32, bitmapy: 64, jumpthrough: false },
+                MovingPlatform { vertical: false, dist: 128.0, speed: 1.0, bitmapx: 0, bitmapy: 96, jumpthrough: true },
+            ],
+            [BreakableWall { skin_id: Some(2), health: 10.0 }, BreakableWall { skin_id: None, health: 5.0 }],
+            [
+                BackgroundSwitcher { shape: BackgroundSwitcherShape::Horizontal, enabled_layers: 1..4 },
+                BackgroundSwitcher { shape: BackgroundSwitcherShape::Vertical, enabled_layers: 2..8 },
+            ],
+            [DroppedItem { item: ItemType::Money50 }, DroppedItem { item: ItemType::FireWand }],
+            [WandRune { rune_type: RuneType::Ice }, WandRune { rune_type: RuneType::Lightning }],
+            [Heavy { speed: 1.5, face_left: true }, Heavy { speed: 3.0, face_left: false }],
+            [Dragon { space_skin: true }, Dragon { space_skin: false }],
+            [BozoPin { flying_speed: 4.0 }, BozoPin { flying_speed: 6.0 }],
+            [Bozo { mark_ii: true }, Bozo { mark_ii: false }],
+            [LavaMonster { face_left: true }, LavaMonster { face_left: false }],
+            [TtMinion { small: true }, TtMinion { small: false }],
+            [MegaFish { water_level: 100, swimming_speed: 2.0 }, MegaFish { water_level: -20, swimming_speed: 3.5 }],
+            [LavaDragonHead { len: 10, health: 20.0 }, LavaDragonHead { len: 4, health: 50.0 }],
+            [TtNode { node_type: TtNodeType::Waypoint(5) }, TtNode { node_type: TtNodeType::BozoWaypoint }],
+            [TtNode { node_type: TtNodeType::ChaseTrigger }, TtNode { node_type: TtNodeType::NormalTrigger }],
+            [TtBoss { speed: 2.0 }, TtBoss { speed: 4.0 }],
+            [EaterBug { pop_up_speed: 1.0 }, EaterBug { pop_up_speed: 2.0 }],
+            [SpiderWalker { speed: 1.0 }, SpiderWalker { speed: 2.0 }],
+            [
+                RotatingFireColunmPiece { origin_x: 32, degrees_per_second: 90.0 },
+                RotatingFireColunmPiece { origin_x: -16, degrees_per_second: 45.0 },
+            ],
+            [
+                MovingFire { vertical: true, dist: 64, speed: 2.0, despawn: false, bitmapx: 32, bitmapy: 0 },
+                MovingFire { vertical: false, dist: 32, speed: 1.0, despawn: true, bitmapx: 0, bitmapy: 32 },
+            ],
+            [SuperDragon { waypoint_id: 1 }, SuperDragon { waypoint_id: 2 }],
+            [SuperDragonLandingZone { waypoint_id: 1 }, SuperDragonLandingZone { waypoint_id: 2 }],
+            [BozoLaserMinion { speed: 1.0 }, BozoLaserMinion { speed: 2.0 }],
+            [Checkpoint { checkpoint_num: 1 }, Checkpoint { checkpoint_num: 2 }],
+            [BanditGuy { speed: 1.0 }, BanditGuy { speed: 2.0 }],
+            [
+                PushZone { push_zone_type: PushZoneType::Vertical, push_speed: 1.0 },
+                PushZone { push_zone_type: PushZoneType::HorizontalSmall, push_speed: -2.0 },
+            ],
+            [VerticalWindZone { acceleration: 0.5 }, VerticalWindZone { acceleration: 1.0 }],
+            [
+                DisapearingPlatform { time_on: 1.0, time_off: 2.0, starts_on: true },
+                DisapearingPlatform { time_on: 3.0, time_off: 4.0, starts_on: false },
+            ],
+            [SpringBoard { jump_velocity: 10.0 }, SpringBoard { jump_velocity: 20.0 }],
+            [Jumpthrough { big: true }, Jumpthrough { big: false }],
+            [BreakablePlatform { time_till_fall: 1.0 }, BreakablePlatform { time_till_fall: 2.0 }],
+            [
+                LockedBlock { color: KeyColor::Green, consume_key: true },
+                LockedBlock { color: KeyColor::Blue, consume_key: false },
+            ],
+            [
+                RockGuy { rock_guy_type: RockGuyType::Small2 { face_left: true } },
+                RockGuy { rock_guy_type: RockGuyType::Small1 },
+            ],
+            [
+                RockGuy { rock_guy_type: RockGuyType::Medium },
+                RockGuy { rock_guy_type: RockGuyType::Small2 { face_left: false } },
+            ],
+            [HealthSetTrigger { target_health: 10.0 }, HealthSetTrigger { target_health: 20.0 }],
+            [Vortex { attract_enemies: true }, Vortex { attract_enemies: false }],
+            [
+                CustomizeableMoveablePlatform {
+                    bitmap_x32: (1, 2),
+                    target_relative: Point(32.0, 0.0),
+                    speed: 1.0,
+                    start_paused: true,
+                    ty: CustomizableMovingPlatformType::OneWay,
+                },
+                CustomizeableMoveablePlatform {
+                    bitmap_x32: (3, 4),
+                    target_relative: Point(0.0, -64.0),
+                    speed: 2.0,
+                    start_paused: false,
+                    ty: CustomizableMovingPlatformType::Despawn,
+                },
+            ],
+            [
+                GraphicsChangeTrigger { gfx_file: "gfx.bmp".to_string() },
+                GraphicsChangeTrigger { gfx_file: "gfx2.bmp".to_string() },
+            ],
+            [BossBarInfo { boss_name: "Bozo".to_string() }, BossBarInfo { boss_name: "Teddy".to_string() }],
+            [
+                BgSpeed { vertical_axis: true, layer: 1, speed: 0.5 },
+                BgSpeed { vertical_axis: false, layer: 2, speed: 1.5 },
+            ],
+            [BgTransparency { layer: 1, transparency: 3 }, BgTransparency { layer: 2, transparency: 7 }],
+            [TeleportTrigger1 { link_id: 1, delay_secs: 1.0 }, TeleportTrigger1 { link_id: 2, delay_secs: 2.0 }],
+            [
+                TeleportArea1 { link_id: 1, loc: Point(1.0, 2.0) },
+                TeleportArea1 { link_id: 2, loc: Point(3.0, 4.0) },
+            ],
+            [SfxPoint { sound_id: 1 }, SfxPoint { sound_id: 2 }],
+            [Lua { lua_wobj_type: 1 }, Lua { lua_wobj_type: 2 }],
+            [
+                UpgradeTrigger { trigger_type: UpgradeTriggerType::MaxPowerRune { skin_power_override: Some(3) } },
+                UpgradeTrigger { trigger_type: UpgradeTriggerType::MaxPowerRune { skin_power_override: None } },
+            ],
+            [
+                UpgradeTrigger { trigger_type: UpgradeTriggerType::Wings },
+                UpgradeTrigger { trigger_type: UpgradeTriggerType::None },
+            ],
+            [
+                UpgradeTrigger { trigger_type: UpgradeTriggerType::DeephausBoots },
+                UpgradeTrigger { trigger_type: UpgradeTriggerType::Vortex },
+            ],
+            [
+                FinishTrigger { next_level: "level2".to_string(), extra_unlocked_level: Some("secret".to_string()), is_secret: true },
+                FinishTrigger { next_level: "level3".to_string(), extra_unlocked_level: None, is_secret: false },
+            ],
+            [GravityTrigger { gravity: 0.5 }, GravityTrigger { gravity: 1.0 }],
+            [SkinUnlock { id: 1 }, SkinUnlock { id: 2 }],
+            [
+                CoolPlatform { time_off_before: 1, time_on: 2, time_off_after: 3 },
+                CoolPlatform { time_off_before: 4, time_on: 5, time_off_after: 6 },
+            ],
+            [
+                TeleportArea2 { loc: Point(1.0, 2.0), start_activated: true, teleport_players: false, link_id: 1 },
+                TeleportArea2 { loc: Point(3.0, 4.0), start_activated: false, teleport_players: true, link_id: 2 },
+            ],
+            [PetUnlock { petid: 1 }, PetUnlock { petid: 2 }],
+            [
+                KeyRemover { remove_red: true, remove_green: false, remove_blue: true },
+                KeyRemover { remove_red: false, remove_green: true, remove_blue: false },
+            ],
+        ]
+    }
+
+    #[test]
+    fn every_field_can_be_set() {
+        for [a, b] in get_sample_pairs() {
+            for (from, to) in [(&a, &b), (&b, &a)] {
+                let mut wobj = to.clone();
+                for (name, value) in from.get_fields() {
+                    assert!(wobj.set_field(name, &value), "Can't set {} of {}", name, from.get_type_name());
+                }
+                assert_eq!(&wobj, from);
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_field_types_are_rejected() {
+        let mut wall = WobjType::BreakableWall { skin_id: Some(2), health: 10.0 };
+        assert!(!wall.set_field("skin_id", &FieldValue::Text("3".to_string())));
+        assert!(!wall.set_field("health", &FieldValue::Bool(true)));
+        assert!(!wall.set_field("size", &FieldValue::Int(1)));
+        assert_eq!(wall, WobjType::BreakableWall { skin_id: Some(2), health: 10.0 });
+
+        let mut finish = WobjType::FinishTrigger {
+            next_level: "level2".to_string(),
+            extra_unlocked_level: Some("secret".to_string()),
+            is_secret: false,
+        };
+        assert!(!finish.set_field("extra_unlocked_level", &FieldValue::Int(1)));
+        assert!(finish.set_field("extra_unlocked_level", &FieldValue::None));
+        assert!(!finish.set_field("next_level", &FieldValue::None));
+
+        let mut rock_guy = WobjType::RockGuy { rock_guy_type: RockGuyType::Medium };
+        assert!(!rock_guy.set_field("rock_guy_type", &FieldValue::Text("Large".to_string())));
+        assert!(!rock_guy.set_field("rock_guy_type", &FieldValue::Text("Small2 { face_left: maybe }".to_string())));
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let mut checkpoint = WobjType::Checkpoint { checkpoint_num: 4 };
+        assert!(!checkpoint.set_field("checkpoint_num", &FieldValue::Int(300)));
+        assert!(!checkpoint.set_field("checkpoint_num", &FieldValue::Int(-1)));
+        assert!(checkpoint.set_field("checkpoint_num", &FieldValue::Int(255)));
+        assert_eq!(checkpoint, WobjType::Checkpoint { checkpoint_num: 255 });
+
+        let mut sfx = WobjType::SfxPoint { sound_id: 7 };
+        assert!(!sfx.set_field("sound_id", &FieldValue::Int(-1)));
+        assert_eq!(sfx, WobjType::SfxPoint { sound_id: 7 });
+
+        let mut wall = WobjType::BreakableWall { skin_id: Some(2), health: 10.0 };
+        assert!(!wall.set_field("skin_id", &FieldValue::Int(256)));
+        assert_eq!(wall, WobjType::BreakableWall { skin_id: Some(2), health: 10.0 });
+    }
 }
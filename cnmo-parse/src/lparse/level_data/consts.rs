@@ -12,3 +12,7 @@ pub const OPAQUE: u8 = 0;
 pub const CLEAR: u8 = 7;
 /// The frame rate of CNM Online
 pub const FRAME_RATE: i32 = 30;
+/// Width of the screen in CNM Online in pixels
+pub const SCREEN_WIDTH: usize = 320;
+/// Height of the screen in CNM Online in pixels
+pub const SCREEN_HEIGHT: usize = 240;
@@ -13,17 +13,25 @@ pub mod cnmb_types;
 pub mod cnms_types;
 /// Consts used in CNM Online that are also used here (like tile size).
 pub mod consts;
+/// Encounter statistics for balancing levels.
+pub mod stats;
+/// Searching for spawners in a level.
+pub mod query;
+/// Find and replace over the spawners in a level.
+pub mod replace;
+/// Saved tile stamps and auto-tiling rules for painting levels.
+pub mod brushes;
 
 /// Duration of something in ticks. (There are 30 ticks per second in CNM
 /// Online, so a Duration of 30 is 1 second). Negative values have uses in
 /// very specific and special cases in CNM Online. Mostly is 0 or above though.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Duration(pub i32);
 
 /// Defines a point for CNM types.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Point(pub f32, pub f32);
 
 /// Version specs of the level data (seperate from the lparse file version)
@@ -125,7 +133,7 @@ impl VersionSpecs {
 
 /// Difficulty rating for a level
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq, Eq, num_derive::FromPrimitive, num_derive::ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, num_derive::FromPrimitive, num_derive::ToPrimitive)]
 pub enum DifficultyRating {
     ///
     Tutorial,
@@ -179,7 +187,7 @@ impl DifficultyRating {
 
 /// Difficulty rating for a level
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq, Eq, num_derive::FromPrimitive, num_derive::ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, num_derive::FromPrimitive, num_derive::ToPrimitive)]
 pub enum LevelType {
     ///
     Normal,
@@ -217,7 +225,7 @@ impl LevelType {
 /// 
 /// Controls stuff for how its shown on the level select menu.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LevelMetaData {
     /// Title of the level
     pub title: String,
@@ -366,6 +374,27 @@ impl LevelData {
         self.cells.save(cnmb, self.tile_properties.len() + 1, &self.version);
     }
 
+    /// Counts every item that can be picked up in the level. This includes the
+    /// items placed with `DroppedItem` spawners and the items other spawners drop.
+    ///
+    /// Returns a list of items and how many of them there are, sorted by item id.
+    /// Items that aren't in the level at all aren't in the list.
+    pub fn count_items(&self) -> Vec<(cnms_types::item_type::ItemType, usize)> {
+        let mut counts = std::collections::BTreeMap::new();
+        for spawner in self.spawners.iter() {
+            if let cnms_types::wobj_type::WobjType::DroppedItem { item } = spawner.type_data {
+                *counts.entry(item.get_item_id()).or_insert(0) += 1;
+            }
+            if let Some(item) = spawner.dropped_item {
+                *counts.entry(item.get_item_id()).or_insert(0) += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .filter_map(|(id, count)| Some((cnms_types::item_type::ItemType::from_item_id(id)?, count)))
+            .collect()
+    }
+
     fn tile_properties_from_lparse(cnmb: &LParse, version: &VersionSpecs, ignore_warnings: bool) -> Result<Vec<cnmb_types::TileProperties>, Error> {
         let mut tile_properties = Vec::new();
 
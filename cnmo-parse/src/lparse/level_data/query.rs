@@ -0,0 +1,123 @@
+use super::{
+    cnms_types::{wobj_type::FieldValue, Spawner, SpawnerMode},
+    LevelData,
+};
+
+/// How a field gets compared to the value in a [`FieldPredicate`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Comparison {
+    /// Field equals the value
+    #[default]
+    Equal,
+    /// Field doesn't equal the value
+    NotEqual,
+    /// Field is less than the value
+    Less,
+    /// Field is less than or equal to the value
+    LessOrEqual,
+    /// Field is greater than the value
+    Greater,
+    /// Field is greater than or equal to the value
+    GreaterOrEqual,
+}
+
+/// A check on one field of a spawner's object type, like `speed > 3`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPredicate {
+    /// Name of the field, see [`super::cnms_types::wobj_type::WobjType::get_fields`]
+    pub field: String,
+    /// How the field gets compared
+    pub comparison: Comparison,
+    /// Value to compare the field to
+    pub value: FieldValue,
+}
+
+impl FieldPredicate {
+    /// Checks the predicate against a field value. Numbers can be compared
+    /// with each other, everything else can only be equal or not equal.
+    pub fn test(&self, value: &FieldValue) -> bool {
+        let ordering = match (value.as_f32(), self.value.as_f32()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ if *value == self.value => Some(std::cmp::Ordering::Equal),
+            _ => None,
+        };
+        match (self.comparison, ordering) {
+            (Comparison::Equal, ordering) => ordering.is_some_and(|o| o.is_eq()),
+            (Comparison::NotEqual, ordering) => !ordering.is_some_and(|o| o.is_eq()),
+            (_, None) => false,
+            (Comparison::Less, Some(o)) => o.is_lt(),
+            (Comparison::LessOrEqual, Some(o)) => o.is_le(),
+            (Comparison::Greater, Some(o)) => o.is_gt(),
+            (Comparison::GreaterOrEqual, Some(o)) => o.is_ge(),
+        }
+    }
+}
+
+/// A search over the spawners of a level. Every part of the query that is set
+/// has to match for a spawner to be found.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct SpawnerQuery {
+    /// Name of the object type, see [`super::cnms_types::wobj_type::WobjType::get_type_name`]
+    pub type_name: Option<String>,
+    /// Checks on the fields of the object type. Spawners without the field don't match.
+    pub predicates: Vec<FieldPredicate>,
+    /// Spawner group the spawner has to be in
+    pub group: Option<u8>,
+    /// Spawning mode the spawner has to have
+    pub mode: Option<SpawnerMode>,
+    /// Area in pixels that the spawner's position has to be in
+    pub region: Option<crate::Rect>,
+}
+
+impl SpawnerQuery {
+    /// Checks if a spawner matches this query
+    pub fn matches(&self, spawner: &Spawner) -> bool {
+        if let Some(type_name) = &self.type_name {
+            if spawner.type_data.get_type_name() != type_name {
+                return false;
+            }
+        }
+        if self.group.is_some() && spawner.spawner_group != self.group {
+            return false;
+        }
+        if let Some(mode) = self.mode {
+            if spawner.spawning_criteria.mode != mode {
+                return false;
+            }
+        }
+        if let Some(region) = &self.region {
+            if spawner.pos.0 < region.x as f32
+                || spawner.pos.1 < region.y as f32
+                || spawner.pos.0 >= (region.x + region.w) as f32
+                || spawner.pos.1 >= (region.y + region.h) as f32
+            {
+                return false;
+            }
+        }
+        if !self.predicates.is_empty() {
+            let fields = spawner.type_data.get_fields();
+            return self.predicates.iter().all(|predicate| {
+                fields
+                    .iter()
+                    .find(|(name, _)| *name == predicate.field)
+                    .is_some_and(|(_, value)| predicate.test(value))
+            });
+        }
+        true
+    }
+}
+
+impl LevelData {
+    /// Gets the indices of every spawner that matches the query.
+    pub fn query_spawners(&self, query: &SpawnerQuery) -> Vec<usize> {
+        self.spawners
+            .iter()
+            .enumerate()
+            .filter(|(_, spawner)| query.matches(spawner))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
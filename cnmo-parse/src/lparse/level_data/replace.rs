@@ -0,0 +1,155 @@
+use super::{
+    cnms_types::{
+        wobj_type::{FieldValue, WobjType},
+        Spawner,
+    },
+    LevelData,
+};
+
+/// What happens to a field when replacing a spawner.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldAction {
+    /// Sets the field to a value
+    Set(FieldValue),
+    /// Copies the value of a field with a different name on the original object type
+    CopyFrom(String),
+    /// Adds to a number field. Rounded to a whole number for integer fields.
+    Add(f32),
+    /// Multiplies a number field. The result is rounded for integer fields.
+    Multiply(f32),
+}
+
+/// Changes one field of the replaced spawner, see [`WobjType::get_fields`]
+/// for the names of fields.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldRule {
+    /// Name of the field on the new object type
+    pub field: String,
+    /// What to do with it
+    pub action: FieldAction,
+}
+
+/// A find and replace operation on spawners. Can change the object type of
+/// the spawners and edit their fields.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct SpawnerReplace {
+    /// The object type to change to. Its field values are used as the defaults
+    /// of the new objects. Keeps the original object type if not set.
+    pub new_type: Option<WobjType>,
+    /// Copy over fields with the same name from the original object type when
+    /// changing to a new one.
+    pub keep_matching_fields: bool,
+    /// Rules applied in order after the type is changed
+    pub rules: Vec<FieldRule>,
+}
+
+impl SpawnerReplace {
+    /// Gets what the object type of a spawner would be after replacing it.
+    pub fn replace_type(&self, old: &WobjType) -> WobjType {
+        self.replace_type_checked(old).0
+    }
+
+    /// Same as [`SpawnerReplace::replace_type`], but also gets the names of the
+    /// fields that couldn't be set. That happens when the new object type
+    /// doesn't have the field, the value is the wrong type or out of range
+    /// for it, or a rule has nothing to work with (like adding to a text field).
+    pub fn replace_type_checked(&self, old: &WobjType) -> (WobjType, Vec<String>) {
+        let mut new = self.new_type.clone().unwrap_or_else(|| old.clone());
+        let mut failed = vec![];
+        let old_fields = old.get_fields();
+        if self.new_type.is_some() && self.keep_matching_fields {
+            let new_fields = new.get_fields();
+            for (name, value) in old_fields.iter() {
+                // Fields the new type doesn't have at all are expected to be dropped
+                let has_field = new_fields.iter().any(|(new_name, _)| new_name == name);
+                if has_field && !new.set_field(name, value) {
+                    failed.push(name.to_string());
+                }
+            }
+        }
+        for rule in self.rules.iter() {
+            let current = new
+                .get_fields()
+                .into_iter()
+                .find(|(name, _)| *name == rule.field)
+                .map(|(_, value)| value);
+            let value = match (&rule.action, current) {
+                (FieldAction::Set(value), _) => Some(value.clone()),
+                (FieldAction::CopyFrom(from), _) => old_fields
+                    .iter()
+                    .find(|(name, _)| name == from)
+                    .map(|(_, value)| value.clone()),
+                (FieldAction::Add(amount), Some(FieldValue::Int(v))) => {
+                    v.checked_add(amount.round() as i64).map(FieldValue::Int)
+                }
+                (FieldAction::Add(amount), Some(FieldValue::Float(v))) => Some(FieldValue::Float(v + amount)),
+                (FieldAction::Multiply(amount), Some(FieldValue::Int(v))) => {
+                    let product = (v as f64 * *amount as f64).round();
+                    // Out of range products fail here or in set_field instead of wrapping
+                    (product.is_finite() && product.abs() < i64::MAX as f64).then_some(FieldValue::Int(product as i64))
+                }
+                (FieldAction::Multiply(amount), Some(FieldValue::Float(v))) => Some(FieldValue::Float(v * amount)),
+                _ => None,
+            };
+            if !value.is_some_and(|value| new.set_field(&rule.field, &value)) {
+                failed.push(rule.field.clone());
+            }
+        }
+        (new, failed)
+    }
+
+    /// Replaces the object type of a spawner.
+    pub fn apply(&self, spawner: &mut Spawner) {
+        spawner.type_data = self.replace_type(&spawner.type_data);
+    }
+}
+
+impl LevelData {
+    /// Runs a replace operation on the spawners with the given indices.
+    /// Indices that are out of bounds are skipped.
+    pub fn replace_spawners(&mut self, indices: &[usize], replace: &SpawnerReplace) {
+        for idx in indices {
+            if let Some(spawner) = self.spawners.get_mut(*idx) {
+                replace.apply(spawner);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(field: &str, action: FieldAction) -> SpawnerReplace {
+        SpawnerReplace {
+            rules: vec![FieldRule { field: field.to_string(), action }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn integer_math_rounds() {
+        let checkpoint = WobjType::Checkpoint { checkpoint_num: 10 };
+        let replaced = rule("checkpoint_num", FieldAction::Add(0.5)).replace_type_checked(&checkpoint);
+        assert_eq!(replaced, (WobjType::Checkpoint { checkpoint_num: 11 }, vec![]));
+        let replaced = rule("checkpoint_num", FieldAction::Add(-0.9)).replace_type_checked(&checkpoint);
+        assert_eq!(replaced, (WobjType::Checkpoint { checkpoint_num: 9 }, vec![]));
+        let replaced = rule("checkpoint_num", FieldAction::Multiply(1.26)).replace_type_checked(&checkpoint);
+        assert_eq!(replaced, (WobjType::Checkpoint { checkpoint_num: 13 }, vec![]));
+    }
+
+    #[test]
+    fn overflows_are_not_applied() {
+        let checkpoint = WobjType::Checkpoint { checkpoint_num: 200 };
+        let failed = vec!["checkpoint_num".to_string()];
+        let replaced = rule("checkpoint_num", FieldAction::Multiply(2.0)).replace_type_checked(&checkpoint);
+        assert_eq!(replaced, (checkpoint.clone(), failed.clone()));
+        let replaced = rule("checkpoint_num", FieldAction::Add(-201.0)).replace_type_checked(&checkpoint);
+        assert_eq!(replaced, (checkpoint.clone(), failed.clone()));
+        let replaced = rule("checkpoint_num", FieldAction::Multiply(f32::INFINITY)).replace_type_checked(&checkpoint);
+        assert_eq!(replaced, (checkpoint, failed));
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use super::{
+    cnms_types::{wobj_type::WobjType, SpawnerMode},
+    consts::{SCREEN_HEIGHT, SCREEN_WIDTH, TILE_SIZE},
+    LevelData,
+};
+
+/// Encounter statistics for a level, used for balancing levels.
+/// Create it with [`LevelData::get_stats`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default, Clone)]
+pub struct LevelStats {
+    /// How many spawners of each object type there are, keyed by the name of the type.
+    pub wobj_counts: BTreeMap<String, usize>,
+    /// Total health of every object that has its health set in the level file
+    /// (breakable walls and lava dragons).
+    pub known_health: f32,
+    /// Total money from money items, both placed and dropped.
+    pub total_money: u32,
+    /// Number of checkpoints
+    pub checkpoints: usize,
+    /// Number of player spawns
+    pub player_spawns: usize,
+    /// How many objects can be alive at once from all of the spawners combined.
+    pub max_concurrent_objects: u32,
+    /// How many objects per second the spawners respawn at most, from each
+    /// spawner's delay between spawns. Spawners with no delay aren't counted.
+    pub respawns_per_second: f32,
+    /// Size of the density grid in screens (width, height). A screen is
+    /// [`SCREEN_WIDTH`] by [`SCREEN_HEIGHT`] pixels.
+    pub density_size: (usize, usize),
+    /// Number of spawners in each screen, row by row.
+    pub density: Vec<usize>,
+}
+
+impl LevelStats {
+    /// Number of spawners in the screen at screen coordinates `x` and `y`.
+    pub fn get_density(&self, x: usize, y: usize) -> usize {
+        self.density[y * self.density_size.0 + x]
+    }
+}
+
+impl LevelData {
+    /// Gathers encounter statistics from the spawners of the level.
+    /// Spawners that are set to never spawn still get counted.
+    pub fn get_stats(&self) -> LevelStats {
+        let density_size = (
+            (self.cells.width() * TILE_SIZE).div_ceil(SCREEN_WIDTH).max(1),
+            (self.cells.height() * TILE_SIZE).div_ceil(SCREEN_HEIGHT).max(1),
+        );
+        let mut stats = LevelStats {
+            density_size,
+            density: vec![0; density_size.0 * density_size.1],
+            ..Default::default()
+        };
+
+        for spawner in self.spawners.iter() {
+            *stats.wobj_counts.entry(spawner.type_data.get_type_name().to_string()).or_insert(0) += 1;
+            match spawner.type_data {
+                WobjType::BreakableWall { health, .. } | WobjType::LavaDragonHead { health, .. } => {
+                    stats.known_health += health;
+                },
+                WobjType::DroppedItem { item } => stats.total_money += item.get_money_value(),
+                WobjType::Checkpoint { .. } => stats.checkpoints += 1,
+                WobjType::PlayerSpawn { .. } => stats.player_spawns += 1,
+                _ => {},
+            }
+            if let Some(item) = spawner.dropped_item {
+                stats.total_money += item.get_money_value();
+            }
+
+            let criteria = &spawner.spawning_criteria;
+            if criteria.mode != SpawnerMode::NoSpawn {
+                stats.max_concurrent_objects += criteria.max_concurrent_spawns;
+                if criteria.spawn_delay_secs > 0.0 {
+                    stats.respawns_per_second += 1.0 / criteria.spawn_delay_secs;
+                }
+            }
+
+            let x = (spawner.pos.0.max(0.0) as usize / SCREEN_WIDTH).min(density_size.0 - 1);
+            let y = (spawner.pos.1.max(0.0) as usize / SCREEN_HEIGHT).min(density_size.1 - 1);
+            stats.density[y * density_size.0 + x] += 1;
+        }
+
+        stats
+    }
+}
+